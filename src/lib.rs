@@ -1,27 +1,59 @@
 use std::{
     path::{Path, PathBuf},
+    process::{ExitStatus, Output},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicI64, Ordering},
+    },
     time::Duration,
 };
 
+use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
 use clap::ValueEnum;
 use futures_util::StreamExt;
 use reqwest::{StatusCode, multipart};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
-use tokio::{fs, io::AsyncWriteExt, process::Command, time::sleep};
-use tracing::debug;
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    process::Command,
+    time::{Instant, sleep},
+};
+use tracing::{debug, info, warn};
 
 const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
 const DEFAULT_SORA_MODEL: &str = "sora-2";
 const DEFAULT_SECONDS: u32 = 12;
 const DEFAULT_SIZE: &str = "1280x720";
 const DEFAULT_POLL_INTERVAL_MS: u64 = 5_000;
-const THUMBNAIL_VARIANT: &str = "thumbnail";
-const SPRITESHEET_VARIANT: &str = "spritesheet";
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+const DEFAULT_REFERENCE_FIELD: &str = "input_reference";
+
+const DEFAULT_DURATION_TOLERANCE_SECS: f64 = 1.0;
+/// Base64 length of an inline Veo video (about 48 MiB decoded) above which a warning is logged.
+const DEFAULT_VEO_INLINE_WARN_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_PROBE_CONCURRENCY: usize = 8;
+
+const DEFAULT_ANNOTATION_FONT_SIZE: u32 = 36;
+/// Fonts tried in order when an annotation does not name one (or names one that is missing).
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/System/Library/Fonts/Helvetica.ttc",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
 
 const DEFAULT_VEO_MODEL: &str = "veo-3.0-generate-preview";
 const DEFAULT_VEO_SECONDS: u32 = 8;
+const DEFAULT_VEO_API_VERSION: &str = "v1";
 
 /// Error type for all operations in this crate.
 #[derive(Debug, Error)]
@@ -46,10 +78,26 @@ pub enum SoraError {
     FfmpegMissing,
     #[error("ffmpeg command failed: {0}")]
     FfmpegFailed(String),
+    #[error("ffprobe failed: {0}")]
+    FfprobeFailed(String),
     #[error("video concatenation failed: {0}")]
     FfmpegConcatFailed(String),
-    #[error("video generation job failed: {0}")]
-    JobFailed(String),
+    /// A render was rejected or failed on the provider. `remote_id` names the job when one was
+    /// created, for correlating with provider-side logs; `code` and `param` carry the API's
+    /// structured error code and offending parameter when it reports them. `status` is the
+    /// terminal state the job reached, when it ran at all.
+    #[error(
+        "{backend:?} video generation job failed{}: {message}",
+        remote_id.as_deref().map(|id| format!(" ({id})")).unwrap_or_default()
+    )]
+    JobFailed {
+        backend: ProviderKind,
+        remote_id: Option<String>,
+        message: String,
+        code: Option<String>,
+        param: Option<String>,
+        status: Option<VideoStatus>,
+    },
     #[error("video not found locally: {0}")]
     VideoNotFound(String),
     #[error("metadata missing for video: {0}")]
@@ -58,25 +106,328 @@ pub enum SoraError {
     InvalidConfig(String),
     #[error("operation unsupported for backend: {0}")]
     UnsupportedOperation(String),
+    #[error("{backend:?} backend does not support downloading {variant:?} assets")]
+    VariantUnsupported {
+        backend: ProviderKind,
+        variant: VideoVariant,
+    },
     #[error("invalid response: {0}")]
     InvalidResponse(String),
+    #[error("download of {} is incomplete: got {actual} of {expected} bytes", path.display())]
+    IncompleteDownload {
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("clip '{local_id}' was saved to {} but uploading it failed: {message}", path.display())]
+    UploadFailed {
+        local_id: String,
+        path: PathBuf,
+        message: String,
+    },
+    #[error("disk full while writing {}", path.display())]
+    DiskFull { path: PathBuf },
+    #[error("permission denied writing {}", path.display())]
+    WriteDenied { path: PathBuf },
+    #[error("invalid bundle: {0}")]
+    InvalidBundle(String),
+    /// [`ContinuatorConfig::print_ffmpeg`] is set: the operation's ffmpeg commands were
+    /// printed, and it stopped before saving anything or calling the provider.
+    #[error("ffmpeg commands were printed instead of run; nothing was rendered or saved")]
+    CommandsPrinted,
+    #[error("time {spec} is outside the clip's {duration:.2}s")]
+    TimeOutOfRange { spec: TimeSpec, duration: f64 },
+    #[error("clip '{local_id}' is {actual:.2}s long but {requested}s was requested")]
+    DurationMismatch {
+        local_id: String,
+        requested: u32,
+        actual: f64,
+    },
+    /// `expected` is the active provider for continuations and the first input's for stitches.
+    #[error("clip '{local_id}' was rendered with {recorded:?}, not {expected:?}")]
+    ProviderMismatch {
+        local_id: String,
+        recorded: ProviderKind,
+        expected: ProviderKind,
+    },
+}
+
+impl SoraError {
+    /// Whether the provider refused the requested size, going by its structured error.
+    fn is_size_rejection(&self) -> bool {
+        matches!(
+            self,
+            SoraError::JobFailed { param, code, .. }
+                if param.as_deref() == Some("size")
+                    || code.as_deref().is_some_and(|code| code.contains("size"))
+        )
+    }
+
+    /// Whether a job ran and failed on the provider's side for a reason other than a content
+    /// policy block, so resubmitting the same request may succeed.
+    fn is_render_failure(&self) -> bool {
+        let SoraError::JobFailed {
+            status: Some(VideoStatus::Failed),
+            code,
+            message,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        let reason = format!("{} {message}", code.as_deref().unwrap_or_default()).to_lowercase();
+        !["moderation", "policy", "safety"]
+            .iter()
+            .any(|blocked| reason.contains(blocked))
+    }
+
+    /// Note how many submissions a failed render was given.
+    fn after_attempts(self, attempts: u32) -> SoraError {
+        match self {
+            SoraError::JobFailed {
+                backend,
+                remote_id,
+                message,
+                code,
+                param,
+                status,
+            } => SoraError::JobFailed {
+                backend,
+                remote_id,
+                message: format!("{message} (gave up after {attempts} attempts)"),
+                code,
+                param,
+                status,
+            },
+            other => other,
+        }
+    }
+
+    /// Name the file when an I/O error while writing it is one an operator can act on (a full
+    /// disk or a read-only or unwritable location); other errors pass through unchanged.
+    fn writing(self, path: &Path) -> SoraError {
+        let SoraError::Io(err) = self else {
+            return self;
+        };
+        let path = path.to_path_buf();
+        match err.kind() {
+            std::io::ErrorKind::StorageFull | std::io::ErrorKind::QuotaExceeded => {
+                SoraError::DiskFull { path }
+            }
+            std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem => {
+                SoraError::WriteDenied { path }
+            }
+            _ => SoraError::Io(err),
+        }
+    }
+}
+
+/// Write `data` to `path`, removing whatever was written if it fails part way.
+async fn write_file(path: &Path, data: impl AsRef<[u8]>) -> Result<(), SoraError> {
+    if let Err(err) = fs::write(path, data).await {
+        let _ = fs::remove_file(path).await;
+        return Err(SoraError::from(err).writing(path));
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "snake_case")]
 pub enum ProviderKind {
     Sora,
     Veo,
+    /// Offline backend that fabricates clips locally, for tests.
+    #[cfg(any(test, feature = "mock"))]
+    Mock,
 }
 
 impl ProviderKind {
     const fn default_backend() -> Self {
         ProviderKind::Sora
     }
+
+    /// Lowercase name, as accepted by `--provider`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ProviderKind::Sora => "sora",
+            ProviderKind::Veo => "veo",
+            #[cfg(any(test, feature = "mock"))]
+            ProviderKind::Mock => "mock",
+        }
+    }
+
+    /// What this provider accepts and offers.
+    pub const fn capabilities(self) -> &'static ProviderCapabilities {
+        match self {
+            ProviderKind::Sora => &SORA_CAPABILITIES,
+            ProviderKind::Veo => &VEO_CAPABILITIES,
+            #[cfg(any(test, feature = "mock"))]
+            ProviderKind::Mock => &MOCK_CAPABILITIES,
+        }
+    }
+
+    /// `WIDTHxHEIGHT` sizes this provider renders.
+    pub const fn supported_sizes(self) -> &'static [&'static str] {
+        self.capabilities().sizes
+    }
+
+    /// Clip lengths `model` accepts: its entry in the per-model table when it has one,
+    /// otherwise the provider-wide list.
+    pub fn supported_seconds(self, model: &str) -> &'static [u32] {
+        MODEL_SECONDS
+            .iter()
+            .find(|(id, _)| *id == model)
+            .map_or(self.capabilities().seconds, |(_, seconds)| *seconds)
+    }
+
+    /// Size to render `model` at when none is given: its native size from the per-model table,
+    /// otherwise `1280x720`.
+    pub fn default_size(self, model: &str) -> &'static str {
+        MODEL_SIZES
+            .iter()
+            .find(|(id, _)| *id == model)
+            .map_or(DEFAULT_SIZE, |(_, size)| *size)
+    }
+
+    /// Sizes `model` accepts: its entry in the per-model table when it has one, otherwise the
+    /// provider-wide list.
+    pub fn model_sizes(self, model: &str) -> &'static [&'static str] {
+        MODEL_SUPPORTED_SIZES
+            .iter()
+            .find(|(id, _)| *id == model)
+            .map_or(self.capabilities().sizes, |(_, sizes)| *sizes)
+    }
+
+    /// The size `model` accepts that is closest to `size`: same orientation, then the nearest
+    /// aspect ratio, then the nearest pixel count. `None` when `size` can't be parsed or nothing
+    /// else fits.
+    pub fn nearest_size(self, model: &str, size: &str) -> Option<&'static str> {
+        let (width, height) = parse_dimensions(size).ok()?;
+        let aspect = f64::from(width) / f64::from(height);
+        let area = f64::from(width) * f64::from(height);
+        self.model_sizes(model)
+            .iter()
+            .copied()
+            .filter(|candidate| *candidate != size)
+            .filter_map(|candidate| {
+                let (w, h) = parse_dimensions(candidate).ok()?;
+                ((w >= h) == (width >= height)).then_some((candidate, w, h))
+            })
+            .min_by(|(_, w1, h1), (_, w2, h2)| {
+                let score = |w: u32, h: u32| {
+                    let candidate_aspect = f64::from(w) / f64::from(h);
+                    (
+                        (candidate_aspect - aspect).abs(),
+                        (f64::from(w) * f64::from(h) - area).abs(),
+                    )
+                };
+                score(*w1, *h1)
+                    .partial_cmp(&score(*w2, *h2))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(candidate, ..)| candidate)
+    }
+
+    /// Reject a size or duration this provider would refuse, naming the accepted values.
+    pub fn check_render(self, model: &str, size: &str, seconds: u32) -> Result<(), SoraError> {
+        self.check_size(size)?;
+        self.check_seconds(model, seconds)
+    }
+
+    pub fn check_size(self, size: &str) -> Result<(), SoraError> {
+        let sizes = self.capabilities().sizes;
+        if sizes.contains(&size) {
+            return Ok(());
+        }
+        Err(SoraError::InvalidConfig(format!(
+            "{self:?} does not support size {size} (expected one of {})",
+            sizes.join(", ")
+        )))
+    }
+
+    pub fn check_seconds(self, model: &str, seconds: u32) -> Result<(), SoraError> {
+        let allowed = self.supported_seconds(model);
+        if allowed.contains(&seconds) {
+            return Ok(());
+        }
+        let allowed: Vec<String> = allowed.iter().map(u32::to_string).collect();
+        Err(SoraError::InvalidConfig(format!(
+            "{model} does not support {seconds}s clips (expected one of {}; set \
+             skip_duration_validation for models this table doesn't know yet)",
+            allowed.join(", ")
+        )))
+    }
+}
+
+/// Sizes, durations, and features a provider supports. Requests are checked against this table
+/// before anything is submitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCapabilities {
+    /// `WIDTHxHEIGHT` sizes accepted for renders.
+    pub sizes: &'static [&'static str],
+    /// Clip lengths in seconds accepted for renders, unless the model has its own list (see
+    /// [`ProviderKind::supported_seconds`]).
+    pub seconds: &'static [u32],
+    /// Named output resolutions (Veo's `720p`/`1080p`); empty when sizes are the only knob.
+    pub resolutions: &'static [&'static str],
+    /// Asset variants that can be downloaded after a render.
+    pub variants: &'static [VideoVariant],
+    /// Whether existing clips can be remixed.
+    pub remix: bool,
 }
 
+const SORA_CAPABILITIES: ProviderCapabilities = ProviderCapabilities {
+    sizes: &["1280x720", "720x1280", "1792x1024", "1024x1792"],
+    seconds: &[4, 8, 12],
+    resolutions: &[],
+    variants: &[
+        VideoVariant::Video,
+        VideoVariant::Thumbnail,
+        VideoVariant::Spritesheet,
+    ],
+    remix: true,
+};
+
+const VEO_CAPABILITIES: ProviderCapabilities = ProviderCapabilities {
+    sizes: &["1280x720", "720x1280", "1920x1080", "1080x1920"],
+    seconds: &[4, 6, 8],
+    resolutions: &["720p", "1080p"],
+    variants: &[VideoVariant::Video],
+    remix: false,
+};
+
+#[cfg(any(test, feature = "mock"))]
+const MOCK_CAPABILITIES: ProviderCapabilities = SORA_CAPABILITIES;
+
+/// Models whose accepted clip lengths differ from their provider's list.
+const MODEL_SECONDS: &[(&str, &[u32])] = &[
+    ("veo-2.0-generate-001", &[5, 6, 7, 8]),
+    (DEFAULT_VEO_MODEL, &[4, 6, 8]),
+];
+
+/// Models that accept fewer sizes than their provider's list.
+const MODEL_SUPPORTED_SIZES: &[(&str, &[&str])] = &[("sora-2", &["1280x720", "720x1280"])];
+
+/// Models whose native size differs from [`DEFAULT_SIZE`].
+const MODEL_SIZES: &[(&str, &str)] = &[
+    ("sora-2-pro", "1792x1024"),
+    ("veo-3.0-generate-001", "1920x1080"),
+    ("veo-3.0-fast-generate-001", "1920x1080"),
+];
+
+/// Published list prices in US dollars per rendered second, for rough estimates only. A row
+/// with sizes applies only at those sizes; the first matching row wins.
+const MODEL_PRICES_USD_PER_SECOND: &[(&str, &[&str], f64)] = &[
+    ("sora-2", &[], 0.10),
+    ("sora-2-pro", &["1792x1024", "1024x1792"], 0.50),
+    ("sora-2-pro", &[], 0.30),
+    (DEFAULT_VEO_MODEL, &[], 0.75),
+    ("veo-3.0-generate-001", &[], 0.40),
+    ("veo-3.0-fast-generate-001", &[], 0.15),
+];
+
 /// High-level configuration for the video pipeline.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ContinuatorConfig {
     /// Backend provider to target (defaults to Sora).
     pub provider: Option<ProviderKind>,
@@ -84,13 +435,15 @@ pub struct ContinuatorConfig {
     pub api_key: Option<String>,
     /// Preferred model identifier.
     pub model: Option<String>,
-    /// Dimensions string (e.g., `1280x720`).
+    /// Dimensions string (e.g., `1280x720`). When unset, each render uses its model's native
+    /// size (see [`ProviderKind::default_size`]).
     pub size: Option<String>,
     /// Length of each clip in seconds.
     pub seconds: Option<u32>,
     /// Root directory for downloaded videos and metadata.
     pub data_dir: Option<PathBuf>,
-    /// Polling interval in milliseconds.
+    /// Polling interval in milliseconds. Zero polls back to back without touching the timer,
+    /// for mocks and providers that finish instantly.
     pub poll_interval_ms: Option<u64>,
     /// Google Cloud project id for Veo.
     pub gcp_project: Option<String>,
@@ -100,33 +453,132 @@ pub struct ContinuatorConfig {
     pub gcp_access_token: Option<String>,
     /// Optional Cloud Storage URI to store generated videos.
     pub gcp_storage_uri: Option<String>,
+    /// Vertex AI API version in Veo request URLs (defaults to `v1`), e.g. `v1beta1` for
+    /// features Google ships under a beta path first.
+    pub gcp_api_version: Option<String>,
+    /// Warn when Veo returns a video inline whose base64 payload is longer than this many bytes
+    /// (defaults to 64 MiB); it is decoded whole in memory, so `gcp_storage_uri` is safer for
+    /// large clips rendered concurrently.
+    pub gcp_inline_warn_bytes: Option<u64>,
     /// Whether to request audio generation for Veo (defaults to true).
     pub gcp_generate_audio: Option<bool>,
-    /// Preferred Veo resolution ("720p" or "1080p").
+    /// Preferred Veo resolution ("720p" or "1080p"), independent of `size`.
     pub gcp_resolution: Option<String>,
+    /// Veo aspect ratio ("16:9" or "9:16"), independent of `size`. Together with
+    /// `gcp_resolution` this picks the output format; whichever is unset follows the size.
+    pub gcp_aspect_ratio: Option<String>,
     /// Whether to let Gemini enhance prompts for Veo (defaults to true).
     pub gcp_enhance_prompt: Option<bool>,
-}
-
-impl Default for ContinuatorConfig {
-    fn default() -> Self {
-        Self {
-            provider: None,
-            api_key: None,
-            model: None,
-            size: None,
-            seconds: None,
-            data_dir: None,
-            poll_interval_ms: None,
-            gcp_project: None,
-            gcp_location: None,
-            gcp_access_token: None,
-            gcp_storage_uri: None,
-            gcp_generate_audio: None,
-            gcp_resolution: None,
-            gcp_enhance_prompt: None,
-        }
-    }
+    /// Maximum provider requests per minute across every operation on a manager.
+    /// Submissions, polls, and downloads all draw from the same bucket.
+    pub rate_limit_rpm: Option<u32>,
+    /// Multipart field name Sora expects the reference image under (defaults to
+    /// `input_reference`).
+    pub sora_reference_field: Option<String>,
+    /// `variant` query values Sora's content endpoint expects, layered over the defaults
+    /// (`thumbnail` and `spritesheet`; none for the video). An empty value requests the
+    /// variant without the parameter.
+    pub sora_variant_params: Option<std::collections::HashMap<VideoVariant, String>>,
+    /// Retries for rate-limited (429) or server-error (5xx) HTTP responses (defaults to 3).
+    /// Render submissions are only retried after a 429 or a failed connection, never after a
+    /// 5xx or timeout that may follow an accepted job.
+    pub max_retries: Option<u32>,
+    /// Seed for retry jitter, for reproducible backoff schedules in tests.
+    pub retry_jitter_seed: Option<u64>,
+    /// Idle connections kept open per provider host (reqwest's default keeps every one). For
+    /// a batch, at least the number of renders in flight lets each poll reuse a connection.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds an idle pooled connection is kept before closing (defaults to 90). Keep it above
+    /// the poll interval so polls don't reconnect every time.
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Send TCP keep-alive probes on provider connections at this interval in seconds, e.g. 30
+    /// to hold connections open through NATs and proxies during long renders.
+    pub tcp_keepalive_secs: Option<u64>,
+    /// How the built-in ffmpeg extractor captures seed frames (defaults to `fallback`).
+    pub frame_extraction: Option<FrameExtractionStrategy>,
+    /// Allowed difference between requested and rendered duration in seconds (defaults to 1.0).
+    pub duration_tolerance_secs: Option<f64>,
+    /// Fail renders whose duration falls outside the tolerance instead of only warning.
+    pub strict_duration: bool,
+    /// Image format for extracted seed frames (defaults to PNG).
+    pub seed_frame_format: Option<ImageFormat>,
+    /// JPEG quality for seed frames on ffmpeg's `-q:v` scale (2 = best, 31 = smallest).
+    pub seed_frame_quality: Option<u8>,
+    /// Resize downloaded thumbnails to this size (e.g., `320x180`).
+    pub thumbnail_size: Option<String>,
+    /// Fade stitched audio out and back in over this many milliseconds at each clip boundary.
+    pub audio_crossfade_ms: Option<u32>,
+    /// Record a SHA-256 of every clip written so archives can be verified later.
+    pub checksums: bool,
+    /// Print the ffmpeg commands that would write media to stderr, shell-quoted, instead of
+    /// running them, for debugging filtergraphs. ffprobe still runs so the commands match the
+    /// real inputs. Operations stop with [`SoraError::CommandsPrinted`] once their commands are
+    /// printed, before anything is saved, uploaded, or sent to a provider.
+    pub print_ffmpeg: bool,
+    /// Clips probed or hashed at once when scanning many clips, and chunks joined at once by a
+    /// chunked stitch (defaults to 8).
+    pub probe_concurrency: Option<usize>,
+    /// Stitch more clips than this in two passes: runs of this many are joined into
+    /// intermediate files concurrently, then the intermediates are joined. Unset stitches
+    /// everything in one ffmpeg call.
+    pub stitch_chunk_size: Option<usize>,
+    /// Encoder settings for operations that re-encode video (defaults to ffmpeg's own).
+    pub encode: EncodeSettings,
+    /// Directory for intermediate files such as seed frames and concat manifests (defaults to
+    /// `.tmp` inside the data directory).
+    pub temp_dir: Option<PathBuf>,
+    /// Sample rate stitched audio is normalized to. Without it, mismatched inputs are brought to
+    /// the highest rate among them.
+    pub audio_sample_rate: Option<u32>,
+    /// Channel count stitched audio is normalized to (1 = mono, 2 = stereo). Without it,
+    /// mismatched inputs are brought to the largest count among them.
+    pub audio_channels: Option<u32>,
+    /// Shell command run after each rendered or stitched clip is saved, e.g.
+    /// `gsutil cp "$1" "gs://bucket/$2.mp4"`. The clip path and local id are passed as the
+    /// positional arguments `$1` and `$2`; `{file}` and `{id}` are shorthand for `"$1"` and
+    /// `"$2"`.
+    pub upload_command: Option<String>,
+    /// Accept any clip length instead of checking it against the model's known durations, for
+    /// models newer than this crate.
+    pub skip_duration_validation: bool,
+    /// Keep each continuation's seed frame as `<id>.seed.<ext>` in the data directory instead
+    /// of deleting it, and record its path in the metadata.
+    pub keep_seed_frame: bool,
+    /// Keep each stitch's ffmpeg concat manifest (`concat-<id>.txt` in the temp directory)
+    /// instead of deleting it, for debugging a failed stitch.
+    pub keep_manifest: bool,
+    /// Name clip files `<id>.<model>.mp4` (e.g. `intro-001.sora-2.mp4`) so the data directory
+    /// shows which model made each clip. Metadata stays at `<id>.json` and records the path.
+    pub include_model_in_filename: bool,
+    /// Name clip files `<id>.<provider>.mp4`; combined with the model as
+    /// `<id>.<provider>.<model>.mp4`.
+    pub include_backend_in_filename: bool,
+    /// Prepended to every create and continue prompt, e.g. a house style directive.
+    pub prompt_prefix: Option<String>,
+    /// Appended to every create and continue prompt.
+    pub prompt_suffix: Option<String>,
+    /// When Sora rejects a size for the chosen model, retry once at the model's nearest
+    /// supported size (same orientation) and record that size instead of failing.
+    pub size_fallback: bool,
+    /// Fail instead of warning when a continuation's parent was rendered by another provider,
+    /// or when a stitch mixes clips from different providers.
+    pub strict_provider: bool,
+    /// Resubmit a render whose job fails on the provider (not a cancellation or a content
+    /// policy block) up to this many times before giving up. Defaults to no retries.
+    pub render_retries: Option<u32>,
+    /// Waits used by poll loops, retries, and the rate limiter (defaults to [`TokioSleeper`]).
+    pub sleeper: Option<Arc<dyn Sleeper>>,
+    /// File the mock provider copies for every render instead of generating a test pattern
+    /// with ffmpeg.
+    #[cfg(any(test, feature = "mock"))]
+    pub mock_fixture: Option<PathBuf>,
+    /// Fail this many mock renders with a provider-side failure before rendering normally.
+    #[cfg(any(test, feature = "mock"))]
+    pub mock_failures: u32,
+    /// Record every provider HTTP exchange to a cassette file, or answer requests from one
+    /// without touching the network.
+    #[cfg(any(test, feature = "cassette"))]
+    pub http_cassette: Option<CassetteMode>,
 }
 
 pub type SoraConfig = ContinuatorConfig;
@@ -134,34 +586,57 @@ pub type SoraConfig = ContinuatorConfig;
 impl ContinuatorConfig {
     fn resolve(&self) -> Result<ResolvedManagerConfig, SoraError> {
         let provider = self.provider.unwrap_or(ProviderKind::Sora);
-        let data_dir = self
-            .data_dir
-            .clone()
-            .unwrap_or_else(|| PathBuf::from("videos"));
+        let data_dir = self.data_dir();
         let poll_interval =
             Duration::from_millis(self.poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS));
+        let duration_tolerance = self.duration_tolerance()?;
+        let frame_options = self.frame_options();
+        frame_options.validate()?;
+        let thumbnail_size = self
+            .thumbnail_size
+            .as_deref()
+            .map(parse_dimensions)
+            .transpose()?;
+        self.encode.validate()?;
+        validate_audio_target(self.audio_sample_rate, self.audio_channels)?;
+        let probe_concurrency = self.probe_concurrency()?;
+        validate_chunk_size(self.stitch_chunk_size)?;
+        self.check_rate_limit()?;
+        let sleeper = self
+            .sleeper
+            .clone()
+            .unwrap_or_else(|| Arc::new(TokioSleeper));
+        let limiter = self
+            .rate_limit_rpm
+            .map(|rpm| RateLimiter::per_minute(rpm, sleeper.now()));
+        let policy = Arc::new(RequestPolicy {
+            limiter,
+            max_retries: self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            backoff: Backoff::new(RETRY_BASE_DELAY, RETRY_MAX_DELAY, self.retry_jitter_seed),
+            sleeper: sleeper.clone(),
+            rate_limit: Mutex::default(),
+            #[cfg(any(test, feature = "cassette"))]
+            cassette: self
+                .http_cassette
+                .as_ref()
+                .map(Cassette::open)
+                .transpose()?,
+        });
 
         let backend = match provider {
             ProviderKind::Sora => {
-                let api_key = match self.api_key.clone() {
-                    Some(key) => key,
-                    None => {
-                        std::env::var("OPENAI_API_KEY").map_err(|_| SoraError::MissingApiKey)?
-                    }
-                };
-                let defaults = BackendDefaults {
-                    model: self
-                        .model
-                        .clone()
-                        .unwrap_or_else(|| DEFAULT_SORA_MODEL.to_string()),
-                    size: self
-                        .size
-                        .clone()
-                        .unwrap_or_else(|| DEFAULT_SIZE.to_string()),
-                    seconds: self.seconds.unwrap_or(DEFAULT_SECONDS),
-                };
-                let client = SoraClient::new(api_key.clone())?;
-                Backend::Sora(SoraBackend { client, defaults })
+                let api_key = self.resolve_api_key()?;
+                let defaults = self.backend_defaults(provider);
+                let reference_field = self.sora_reference_field()?;
+                let variant_params = self.sora_variant_params()?;
+                let client = SoraClient::new(self.http_client()?, api_key, policy, variant_params);
+                Backend::Sora(SoraBackend {
+                    client,
+                    defaults,
+                    reference_field,
+                    size_fallback: self.size_fallback,
+                    in_flight: InFlight::default(),
+                })
             }
             ProviderKind::Veo => {
                 let project = self.resolve_gcp_project()?;
@@ -171,41 +646,352 @@ impl ContinuatorConfig {
                 } else {
                     VeoTokenSource::Gcloud
                 };
-                let defaults = BackendDefaults {
-                    model: self
-                        .model
-                        .clone()
-                        .unwrap_or_else(|| DEFAULT_VEO_MODEL.to_string()),
-                    size: self
-                        .size
-                        .clone()
-                        .unwrap_or_else(|| DEFAULT_SIZE.to_string()),
-                    seconds: self.seconds.unwrap_or(DEFAULT_VEO_SECONDS),
-                };
+                let defaults = self.backend_defaults(provider);
                 let generate_audio = self.gcp_generate_audio.unwrap_or(true);
                 let enhance_prompt = self.gcp_enhance_prompt.unwrap_or(true);
-                let resolution = self.gcp_resolution.clone();
-                let aspect_ratio = size_to_aspect_ratio(defaults.size.as_str());
-                let client = VeoClient::new(project, location, token_source)?;
+                self.check_veo_format(&defaults)?;
+                if let Some(uri) = &self.gcp_storage_uri {
+                    check_gs_uri("gcp_storage_uri", uri)?;
+                }
+                let client = VeoClient::new(
+                    self.http_client()?,
+                    project,
+                    location,
+                    self.gcp_api_version()?,
+                    token_source,
+                    policy,
+                );
                 Backend::Veo(VeoBackend {
                     client,
                     defaults,
                     generate_audio,
                     enhance_prompt,
                     storage_uri: self.gcp_storage_uri.clone(),
-                    resolution,
-                    aspect_ratio,
+                    inline_warn_bytes: self
+                        .gcp_inline_warn_bytes
+                        .unwrap_or(DEFAULT_VEO_INLINE_WARN_BYTES),
+                    resolution: self.gcp_resolution.clone(),
+                    aspect_ratio: self.gcp_aspect_ratio.clone(),
+                    in_flight: InFlight::default(),
                 })
             }
+            #[cfg(any(test, feature = "mock"))]
+            ProviderKind::Mock => Backend::Mock(MockBackend {
+                defaults: self.backend_defaults(provider),
+                fixture: self.mock_fixture.clone(),
+                failures: std::sync::atomic::AtomicU32::new(self.mock_failures),
+                renders: Mutex::default(),
+                sleeper: sleeper.clone(),
+            }),
         };
+        let defaults = backend.defaults();
+        provider.check_size(&defaults.size)?;
+        if !self.skip_duration_validation {
+            provider.check_seconds(&defaults.model, defaults.seconds)?;
+        }
+
+        let temp_dir = self.temp_dir(&data_dir);
+        check_not_file("data_dir", &data_dir)?;
+        check_not_file("temp_dir", &temp_dir)?;
 
         Ok(ResolvedManagerConfig {
             backend,
+            temp_dir,
             data_dir,
             poll_interval,
+            frame_extraction: self.frame_extraction.unwrap_or_default(),
+            duration_tolerance,
+            strict_duration: self.strict_duration,
+            frame_options,
+            thumbnail_size,
+            checksums: self.checksums,
+            print_ffmpeg: self.print_ffmpeg,
+            probe_concurrency,
+            stitch_chunk_size: self.stitch_chunk_size,
+            encode: self.encode.clone(),
+            audio_sample_rate: self.audio_sample_rate,
+            audio_channels: self.audio_channels,
+            upload_command: self.upload_command.clone(),
+            skip_duration_validation: self.skip_duration_validation,
+            keep_seed_frame: self.keep_seed_frame,
+            keep_manifest: self.keep_manifest,
+            include_model_in_filename: self.include_model_in_filename,
+            include_backend_in_filename: self.include_backend_in_filename,
+            strict_provider: self.strict_provider,
+            render_retries: self.render_retries.unwrap_or(0),
+            prompt_prefix: self.prompt_prefix.clone(),
+            prompt_suffix: self.prompt_suffix.clone(),
+            audio_crossfade: self
+                .audio_crossfade_ms
+                .filter(|ms| *ms > 0)
+                .map(|ms| Duration::from_millis(u64::from(ms))),
         })
     }
 
+    /// Expand `${VAR}` and `${VAR:-default}` references in string and path fields, so one
+    /// configuration can serve several environments. Credentials and `upload_command` (which
+    /// runs through a shell that expands variables itself) are left untouched.
+    fn expand_env(self) -> Result<Self, SoraError> {
+        self.expand_env_with(&|name| std::env::var(name).ok())
+    }
+
+    /// [`ContinuatorConfig::expand_env`] with variables looked up through `lookup`.
+    fn expand_env_with(
+        mut self,
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self, SoraError> {
+        for field in [
+            &mut self.model,
+            &mut self.size,
+            &mut self.gcp_project,
+            &mut self.gcp_location,
+            &mut self.gcp_storage_uri,
+            &mut self.gcp_resolution,
+            &mut self.gcp_aspect_ratio,
+            &mut self.gcp_api_version,
+            &mut self.sora_reference_field,
+            &mut self.thumbnail_size,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *field = expand_env_vars(field, lookup)?;
+        }
+        #[cfg(any(test, feature = "mock"))]
+        let mock_fixture = &mut self.mock_fixture;
+        #[cfg(not(any(test, feature = "mock")))]
+        let mock_fixture = &mut None;
+        for path in [&mut self.data_dir, &mut self.temp_dir, mock_fixture]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(text) = path.to_str() {
+                *path = PathBuf::from(expand_env_vars(text, lookup)?);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Check the whole configuration without building a manager, reporting every problem
+    /// instead of stopping at the first the way [`VideoManager::new`] does. Environment
+    /// references are expanded first, as the manager would.
+    pub fn validate(&self) -> Result<(), Vec<SoraError>> {
+        let config = self.clone().expand_env().map_err(|err| vec![err])?;
+        let provider = config.provider.unwrap_or(ProviderKind::Sora);
+        let defaults = config.backend_defaults(provider);
+        let data_dir = config.data_dir();
+        let temp_dir = config.temp_dir(&data_dir);
+        let mut results = vec![
+            config.duration_tolerance().map(drop),
+            config.frame_options().validate(),
+            config
+                .thumbnail_size
+                .as_deref()
+                .map(parse_dimensions)
+                .transpose()
+                .map(drop),
+            config.encode.validate(),
+            validate_audio_target(config.audio_sample_rate, config.audio_channels),
+            config.probe_concurrency().map(drop),
+            validate_chunk_size(config.stitch_chunk_size),
+            config.check_rate_limit(),
+            provider.check_size(&defaults.size),
+            check_not_file("data_dir", &data_dir),
+            check_not_file("temp_dir", &temp_dir),
+        ];
+        if !config.skip_duration_validation {
+            results.push(provider.check_seconds(&defaults.model, defaults.seconds));
+        }
+        match provider {
+            ProviderKind::Sora => results.extend([
+                config.resolve_api_key().map(drop),
+                config.sora_reference_field().map(drop),
+                config.sora_variant_params().map(drop),
+            ]),
+            ProviderKind::Veo => {
+                results.extend([
+                    config.resolve_gcp_project().map(drop),
+                    config.resolve_gcp_location().map(drop),
+                    config.check_veo_format(&defaults),
+                    config.gcp_api_version().map(drop),
+                ]);
+                if let Some(uri) = &config.gcp_storage_uri {
+                    results.push(check_gs_uri("gcp_storage_uri", uri));
+                }
+            }
+            #[cfg(any(test, feature = "mock"))]
+            ProviderKind::Mock => {}
+        }
+        let errors: Vec<SoraError> = results.into_iter().filter_map(Result::err).collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn data_dir(&self) -> PathBuf {
+        self.data_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("videos"))
+    }
+
+    fn temp_dir(&self, data_dir: &Path) -> PathBuf {
+        self.temp_dir
+            .clone()
+            .unwrap_or_else(|| data_dir.join(".tmp"))
+    }
+
+    fn duration_tolerance(&self) -> Result<f64, SoraError> {
+        let tolerance = self
+            .duration_tolerance_secs
+            .unwrap_or(DEFAULT_DURATION_TOLERANCE_SECS);
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return Err(SoraError::InvalidConfig(format!(
+                "duration_tolerance_secs must be a non-negative number (got {tolerance})"
+            )));
+        }
+        Ok(tolerance)
+    }
+
+    fn frame_options(&self) -> FrameOptions {
+        FrameOptions {
+            format: self.seed_frame_format.unwrap_or_default(),
+            quality: self.seed_frame_quality,
+        }
+    }
+
+    /// HTTP client for provider requests, with the configured pool and keep-alive tuning.
+    fn http_client(&self) -> Result<reqwest::Client, SoraError> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(secs) = self.pool_idle_timeout_secs {
+            builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.tcp_keepalive_secs {
+            builder = builder.tcp_keepalive(Duration::from_secs(secs));
+        }
+        Ok(builder.build()?)
+    }
+
+    fn probe_concurrency(&self) -> Result<usize, SoraError> {
+        let concurrency = self.probe_concurrency.unwrap_or(DEFAULT_PROBE_CONCURRENCY);
+        if concurrency == 0 {
+            return Err(SoraError::InvalidConfig(
+                "probe_concurrency must be at least 1".to_string(),
+            ));
+        }
+        Ok(concurrency)
+    }
+
+    fn check_rate_limit(&self) -> Result<(), SoraError> {
+        if self.rate_limit_rpm == Some(0) {
+            return Err(SoraError::InvalidConfig(
+                "rate_limit_rpm must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn resolve_api_key(&self) -> Result<String, SoraError> {
+        match self.api_key.clone() {
+            Some(key) => Ok(key),
+            None => std::env::var("OPENAI_API_KEY").map_err(|_| SoraError::MissingApiKey),
+        }
+    }
+
+    fn sora_reference_field(&self) -> Result<String, SoraError> {
+        match self.sora_reference_field.as_deref().map(str::trim) {
+            Some("") => Err(SoraError::InvalidConfig(
+                "sora_reference_field must not be empty".to_string(),
+            )),
+            Some(field) => Ok(field.to_string()),
+            None => Ok(DEFAULT_REFERENCE_FIELD.to_string()),
+        }
+    }
+
+    fn sora_variant_params(
+        &self,
+    ) -> Result<std::collections::HashMap<VideoVariant, String>, SoraError> {
+        let mut variant_params: std::collections::HashMap<_, _> = [
+            VideoVariant::Video,
+            VideoVariant::Thumbnail,
+            VideoVariant::Spritesheet,
+        ]
+        .into_iter()
+        .map(|variant| (variant, variant.default_query_value().to_string()))
+        .collect();
+        for (variant, value) in self.sora_variant_params.iter().flatten() {
+            let value = value.trim();
+            if !value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            {
+                return Err(SoraError::InvalidConfig(format!(
+                    "sora_variant_params value for {variant:?} must be letters, digits, '_', \
+                     '-', or '.' (got '{value}')"
+                )));
+            }
+            variant_params.insert(*variant, value.to_string());
+        }
+        Ok(variant_params)
+    }
+
+    /// Check the configured Veo resolution and aspect ratio, and that the default model renders
+    /// the format they (or the default size) select.
+    fn check_veo_format(&self, defaults: &BackendDefaults) -> Result<(), SoraError> {
+        if let Some(resolution) = &self.gcp_resolution
+            && !VEO_CAPABILITIES.resolutions.contains(&resolution.as_str())
+        {
+            return Err(SoraError::InvalidConfig(format!(
+                "Veo does not support resolution {resolution} (expected one of {})",
+                VEO_CAPABILITIES.resolutions.join(", ")
+            )));
+        }
+        if let Some(aspect_ratio) = &self.gcp_aspect_ratio
+            && !VEO_ASPECT_RATIOS.contains(&aspect_ratio.as_str())
+        {
+            return Err(SoraError::InvalidConfig(format!(
+                "Veo does not support aspect ratio {aspect_ratio} (expected one of {})",
+                VEO_ASPECT_RATIOS.join(", ")
+            )));
+        }
+        let (resolution, aspect_ratio) = veo_format(
+            self.gcp_resolution.as_deref(),
+            self.gcp_aspect_ratio.as_deref(),
+            &defaults.size,
+        )?;
+        check_veo_format(&defaults.model, &resolution, &aspect_ratio)
+    }
+
+    /// Default model, size, and duration for a backend. Without a configured size the default
+    /// model's native size is used.
+    fn backend_defaults(&self, provider: ProviderKind) -> BackendDefaults {
+        let (default_model, default_seconds) = match provider {
+            ProviderKind::Sora => (DEFAULT_SORA_MODEL, DEFAULT_SECONDS),
+            ProviderKind::Veo => (DEFAULT_VEO_MODEL, DEFAULT_VEO_SECONDS),
+            #[cfg(any(test, feature = "mock"))]
+            ProviderKind::Mock => ("mock", DEFAULT_SECONDS),
+        };
+        let seconds = self.seconds.unwrap_or(default_seconds);
+        let model = self
+            .model
+            .clone()
+            .unwrap_or_else(|| default_model.to_string());
+        let size = self
+            .size
+            .clone()
+            .unwrap_or_else(|| provider.default_size(&model).to_string());
+        BackendDefaults {
+            model,
+            size,
+            size_configured: self.size.is_some(),
+            seconds,
+        }
+    }
+
     fn resolve_gcp_project(&self) -> Result<String, SoraError> {
         if let Some(project) = self
             .gcp_project
@@ -230,6 +1016,28 @@ impl ContinuatorConfig {
         Err(SoraError::MissingGcpProject)
     }
 
+    /// The configured Vertex AI API version: `v` and a number, optionally followed by `alpha`
+    /// or `beta` and another number (`v1`, `v1beta1`).
+    fn gcp_api_version(&self) -> Result<String, SoraError> {
+        let Some(version) = self.gcp_api_version.as_deref().map(str::trim) else {
+            return Ok(DEFAULT_VEO_API_VERSION.to_string());
+        };
+        let plausible = version.strip_prefix('v').is_some_and(|rest| {
+            let major = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+            let channel = major
+                .strip_prefix("alpha")
+                .or_else(|| major.strip_prefix("beta"))
+                .unwrap_or(major);
+            major.len() < rest.len() && channel.chars().all(|c| c.is_ascii_digit())
+        });
+        if !plausible {
+            return Err(SoraError::InvalidConfig(format!(
+                "gcp_api_version must look like v1 or v1beta1 (got '{version}')"
+            )));
+        }
+        Ok(version.to_string())
+    }
+
     fn resolve_gcp_location(&self) -> Result<String, SoraError> {
         if let Some(location) = self
             .gcp_location
@@ -286,1124 +1094,10947 @@ fn gcloud_config_value(property: &str) -> Result<Option<String>, SoraError> {
     Ok(Some(value))
 }
 
-struct ResolvedManagerConfig {
-    backend: Backend,
-    data_dir: PathBuf,
-    poll_interval: Duration,
+/// Extension point for waiting between polls, retries, and rate-limited requests.
+///
+/// The default is a real [`tokio::time::sleep`], which also honours `tokio::time::pause` in
+/// tests. A fake that returns immediately should advance [`Sleeper::now`] by the requested
+/// duration so elapsed-time bookkeeping (the rate limiter) sees time pass.
+#[async_trait]
+pub trait Sleeper: Send + Sync + std::fmt::Debug {
+    async fn sleep(&self, duration: Duration);
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Default [`Sleeper`] backed by the tokio timer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+#[async_trait]
+impl Sleeper for TokioSleeper {
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await;
+    }
+}
+
+/// Wait `interval` between status polls. A zero interval only yields to the runtime, so fast
+/// providers and tests poll in a tight loop without going through the timer.
+async fn wait_to_poll(sleeper: &dyn Sleeper, interval: Duration) {
+    if interval.is_zero() {
+        tokio::task::yield_now().await;
+    } else {
+        sleeper.sleep(interval).await;
+    }
+}
+
+/// Poll a job until `decide` says it is over. `fetch` asks the provider once; `decide` turns
+/// the answer into the result (`Some`), a failure, or `None` to wait `interval` and ask again.
+/// Every render and status wait goes through here, so they share telemetry and pacing.
+async fn poll_until<S, T, Fut>(
+    backend: ProviderKind,
+    sleeper: &dyn Sleeper,
+    interval: Duration,
+    mut fetch: impl FnMut() -> Fut,
+    mut decide: impl FnMut(S) -> Result<Option<T>, SoraError>,
+) -> Result<T, SoraError>
+where
+    Fut: std::future::Future<Output = Result<S, SoraError>>,
+{
+    loop {
+        if let Some(done) = decide(fetch().await?)? {
+            return Ok(done);
+        }
+        telemetry::polled(backend);
+        wait_to_poll(sleeper, interval).await;
+    }
 }
 
+/// Token bucket shared by the clients of a single manager.
+///
+/// The bucket holds up to one minute's worth of requests and refills continuously, so short
+/// bursts are allowed while the sustained rate stays under the configured ceiling.
 #[derive(Debug)]
-struct BackendDefaults {
-    model: String,
-    size: String,
-    seconds: u32,
+struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<RateLimiterState>,
 }
 
 #[derive(Debug)]
-enum Backend {
-    Sora(SoraBackend),
-    Veo(VeoBackend),
+struct RateLimiterState {
+    tokens: f64,
+    last_refill: Instant,
 }
 
-impl Backend {
-    fn kind(&self) -> ProviderKind {
-        match self {
-            Backend::Sora(_) => ProviderKind::Sora,
-            Backend::Veo(_) => ProviderKind::Veo,
+impl RateLimiter {
+    fn per_minute(rpm: u32, now: Instant) -> Self {
+        let capacity = f64::from(rpm);
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            state: Mutex::new(RateLimiterState {
+                tokens: capacity,
+                last_refill: now,
+            }),
         }
     }
 
-    fn defaults(&self) -> &BackendDefaults {
-        match self {
-            Backend::Sora(backend) => &backend.defaults,
-            Backend::Veo(backend) => &backend.defaults,
+    /// Wait until a request token is available and consume it.
+    async fn acquire(&self, sleeper: &dyn Sleeper) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+                let now = sleeper.now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+                state.last_refill = now;
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_sec)
+            };
+            debug!(
+                wait_ms = wait.as_millis() as u64,
+                "rate limit reached; waiting"
+            );
+            sleeper.sleep(wait).await;
         }
     }
+}
 
-    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
-        match self {
-            Backend::Sora(backend) => backend.render(ctx).await,
-            Backend::Veo(backend) => backend.render(ctx).await,
+/// Exponential backoff with full jitter: each delay is drawn uniformly between zero and the
+/// capped exponential step, so many workers retrying the same error spread out instead of
+/// colliding again in lockstep.
+#[derive(Debug)]
+struct Backoff {
+    base: Duration,
+    max: Duration,
+    rng: Mutex<fastrand::Rng>,
+}
+
+impl Backoff {
+    fn new(base: Duration, max: Duration, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => fastrand::Rng::with_seed(seed),
+            None => fastrand::Rng::new(),
+        };
+        Self {
+            base,
+            max,
+            rng: Mutex::new(rng),
         }
     }
 
-    async fn download(
-        &self,
-        remote_id: &str,
-        variant: VideoVariant,
-        output_path: &Path,
-    ) -> Result<(), SoraError> {
-        match self {
-            Backend::Sora(backend) => backend.download(remote_id, variant, output_path).await,
-            Backend::Veo(backend) => backend.download(remote_id, variant, output_path).await,
-        }
+    /// Delay before retry number `attempt` (zero-based).
+    fn delay(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .base
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max);
+        let mut rng = self.rng.lock().unwrap_or_else(|err| err.into_inner());
+        let millis = rng.u64(0..=ceiling.as_millis() as u64);
+        Duration::from_millis(millis)
     }
 }
 
-struct RenderContext<'a> {
-    prompt: &'a str,
-    model: &'a str,
-    seconds: u32,
-    size: &'a str,
-    poll_interval: Duration,
-    output_path: &'a Path,
-    first_frame_path: Option<&'a Path>,
+/// Rate limiting and retry behaviour shared by every client on a manager.
+#[derive(Debug)]
+struct RequestPolicy {
+    limiter: Option<RateLimiter>,
+    max_retries: u32,
+    backoff: Backoff,
+    /// Also used by the backends for their poll loops.
+    sleeper: Arc<dyn Sleeper>,
+    /// Quota from the latest response that reported one, and when it arrived.
+    rate_limit: Mutex<Option<(RateLimitInfo, Instant)>>,
+    #[cfg(any(test, feature = "cassette"))]
+    cassette: Option<Cassette>,
 }
 
-struct RenderOutcome {
-    remote_id: String,
-    model: String,
-    seconds: u32,
-    size: String,
-    created_at: Option<i64>,
-}
-
-#[derive(Debug)]
-struct SoraBackend {
-    client: SoraClient,
-    defaults: BackendDefaults,
-}
-
-impl SoraBackend {
-    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
-        let mut builder = ApiCreateRequest {
-            prompt: ctx.prompt.to_string(),
-            model: ctx.model.to_string(),
-            seconds: ctx.seconds,
-            size: ctx.size.to_string(),
-            input_reference_path: ctx.first_frame_path.map(|path| path.to_path_buf()),
-        };
-
-        let job = self.client.create_video(&mut builder).await?;
-        let job = self
-            .wait_for_completion(job.id.clone(), ctx.poll_interval)
-            .await?;
-
-        self.client
-            .download_video(&job.id, VideoVariant::Video, ctx.output_path)
-            .await?;
-
-        Ok(RenderOutcome {
-            remote_id: job.id,
-            model: job.model,
-            seconds: job.seconds.unwrap_or(ctx.seconds),
-            size: job.size.unwrap_or_else(|| ctx.size.to_string()),
-            created_at: job.created_at,
-        })
+impl RequestPolicy {
+    /// Send a request, waiting on the rate limiter first and retrying transient failures.
+    ///
+    /// Idempotent methods (GET, DELETE, ...) are retried on timeouts, connection failures, 429,
+    /// and 5xx. Anything else, such as a POST that starts a paid render, is only retried when
+    /// the provider can't have acted on it: a 429, or a connection that was never made. A
+    /// timeout or 5xx may follow an accepted job, and resending would render it twice.
+    ///
+    /// `build` is called once per attempt because request bodies (multipart forms in
+    /// particular) cannot be replayed.
+    async fn send<F>(&self, build: F) -> Result<reqwest::Response, SoraError>
+    where
+        F: Fn() -> Result<reqwest::RequestBuilder, SoraError>,
+    {
+        self.send_with(build, None).await
     }
 
-    async fn download(
-        &self,
-        remote_id: &str,
-        variant: VideoVariant,
-        output_path: &Path,
-    ) -> Result<(), SoraError> {
-        self.client
-            .download_video(remote_id, variant, output_path)
-            .await
+    /// [`RequestPolicy::send`] for a POST that only reads state, such as Veo's operation poll,
+    /// which is as safe to retry as a GET.
+    async fn send_poll<F>(&self, build: F) -> Result<reqwest::Response, SoraError>
+    where
+        F: Fn() -> Result<reqwest::RequestBuilder, SoraError>,
+    {
+        self.send_with(build, Some(true)).await
     }
 
-    async fn wait_for_completion(
+    async fn send_with<F>(
         &self,
-        remote_id: String,
-        poll_interval: Duration,
-    ) -> Result<VideoJob, SoraError> {
+        build: F,
+        idempotent: Option<bool>,
+    ) -> Result<reqwest::Response, SoraError>
+    where
+        F: Fn() -> Result<reqwest::RequestBuilder, SoraError>,
+    {
+        let mut attempt = 0;
         loop {
-            let job = self.client.retrieve_video(&remote_id).await?;
-            match job.status {
-                VideoStatus::Completed => return Ok(job),
-                VideoStatus::Failed => {
-                    let message = job
-                        .error
-                        .and_then(|e| e.message)
-                        .unwrap_or_else(|| "unknown error".to_string());
-                    return Err(SoraError::JobFailed(message));
-                }
-                VideoStatus::Canceled => {
-                    return Err(SoraError::JobFailed("job was canceled".to_string()));
+            self.wait_for_quota().await;
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire(self.sleeper.as_ref()).await;
+            }
+            #[cfg(any(test, feature = "cassette"))]
+            if let Some(cassette) = &self.cassette {
+                let response = cassette.exchange(build()?).await?;
+                self.observe_rate_limit(response.headers());
+                return Ok(response);
+            }
+            let (client, request) = build()?.build_split();
+            let request = request?;
+            let idempotent = idempotent.unwrap_or_else(|| request.method().is_idempotent());
+            let result = client.execute(request).await;
+            if let Ok(response) = &result {
+                self.observe_rate_limit(response.headers());
+            }
+            let retries_left = attempt < self.max_retries;
+            let retry_reason = match result {
+                Ok(response)
+                    if retries_left && should_retry_status(response.status(), idempotent) =>
+                {
+                    response.status().to_string()
                 }
-                _ => {
-                    debug!(id = remote_id, status = ?job.status, "polling video status");
-                    sleep(poll_interval).await;
+                Ok(response) => return Ok(response),
+                Err(err)
+                    if retries_left && (err.is_connect() || (idempotent && err.is_timeout())) =>
+                {
+                    err.to_string()
                 }
-            }
+                Err(err) => return Err(err.into()),
+            };
+            let delay = self.backoff.delay(attempt);
+            attempt += 1;
+            warn!(
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                reason = %retry_reason,
+                "retrying provider request"
+            );
+            telemetry::retried();
+            self.sleeper.sleep(delay).await;
+        }
+    }
+
+    fn observe_rate_limit(&self, headers: &reqwest::header::HeaderMap) {
+        let Some(info) = RateLimitInfo::from_headers(headers) else {
+            return;
+        };
+        debug!(
+            remaining_requests = info.remaining_requests,
+            limit_requests = info.limit_requests,
+            reset_requests_ms = info.reset_requests.map(|reset| reset.as_millis() as u64),
+            remaining_tokens = info.remaining_tokens,
+            "provider rate limit"
+        );
+        *self
+            .rate_limit
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some((info, self.sleeper.now()));
+    }
+
+    fn last_rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .as_ref()
+            .map(|(info, _)| info.clone())
+    }
+
+    /// When the last response said no requests remain, hold off until the quota it reported
+    /// resets instead of spending a request on a 429.
+    async fn wait_for_quota(&self) {
+        let wait = match &*self
+            .rate_limit
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+        {
+            Some((info, seen)) if info.remaining_requests == Some(0) => info
+                .reset_requests
+                .and_then(|reset| (*seen + reset).checked_duration_since(self.sleeper.now())),
+            _ => None,
+        };
+        if let Some(wait) = wait.filter(|wait| !wait.is_zero()) {
+            debug!(
+                wait_ms = wait.as_millis() as u64,
+                "provider quota exhausted; waiting for reset"
+            );
+            self.sleeper.sleep(wait).await;
         }
     }
 }
 
-#[derive(Debug)]
-struct VeoBackend {
-    client: VeoClient,
-    defaults: BackendDefaults,
-    generate_audio: bool,
-    enhance_prompt: bool,
-    storage_uri: Option<String>,
-    resolution: Option<String>,
-    aspect_ratio: Option<String>,
+/// How [`ContinuatorConfig::http_cassette`] uses its cassette file.
+#[cfg(any(test, feature = "cassette"))]
+#[derive(Debug, Clone)]
+pub enum CassetteMode {
+    /// Send requests as usual and write every exchange to this file, replacing it. Each request
+    /// is sent once, without retries, so the cassette holds exactly what the provider said.
+    Record(PathBuf),
+    /// Answer requests from this file. Requests match on method, URL, and body; repeats of the
+    /// same request (status polls) get the recorded answers in order, then the last one again.
+    Replay(PathBuf),
 }
 
-impl VeoBackend {
-    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
-        validate_veo_duration(ctx.seconds)?;
-        let resolution = self
-            .resolution
-            .clone()
-            .or_else(|| size_to_resolution(ctx.size));
-        let aspect_ratio = self
-            .aspect_ratio
-            .clone()
-            .or_else(|| size_to_aspect_ratio(ctx.size));
+/// Response bodies larger than this are recorded to a file beside the cassette rather than
+/// inlined, so downloaded videos don't bloat the JSON.
+#[cfg(any(test, feature = "cassette"))]
+const CASSETTE_INLINE_BODY_BYTES: usize = 64 * 1024;
 
-        let image_base64 = if let Some(path) = ctx.first_frame_path {
-            Some(encode_first_frame(path).await?)
-        } else {
-            None
-        };
-        let image = image_base64.as_ref().map(|data| VeoImage {
-            bytes_base64_encoded: Some(data.clone()),
-            gcs_uri: None,
-            mime_type: "image/png".to_string(),
-        });
+/// One recorded request and the provider's answer. Request headers (and so credentials) are
+/// never stored; the response body is base64 so binary bytes survive the JSON, or, when large,
+/// lives in `body_file`.
+#[cfg(any(test, feature = "cassette"))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    /// Empty for bodiless and streamed (multipart) requests.
+    #[serde(default)]
+    request_body: String,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default)]
+    body: String,
+    /// Raw response body, relative to the cassette's directory; replaces `body` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_file: Option<PathBuf>,
+    #[serde(skip)]
+    served: bool,
+}
 
-        let payload = VeoPredictRequest {
-            instances: vec![VeoInstance {
-                prompt: ctx.prompt,
-                image,
-            }],
-            parameters: VeoParameters {
-                duration_seconds: ctx.seconds,
-                generate_audio: self.generate_audio,
-                storage_uri: self.storage_uri.as_deref(),
-                resolution: resolution.as_deref(),
-                aspect_ratio: aspect_ratio.as_deref(),
-                enhance_prompt: self.enhance_prompt,
-                sample_count: None,
-            },
+#[cfg(any(test, feature = "cassette"))]
+impl Interaction {
+    async fn to_response(&self, dir: &Path) -> Result<reqwest::Response, SoraError> {
+        let body = match &self.body_file {
+            Some(file) => fs::read(dir.join(file)).await.map_err(|err| {
+                SoraError::InvalidConfig(format!(
+                    "cannot read cassette body {} for {} {}: {err}",
+                    file.display(),
+                    self.method,
+                    self.url
+                ))
+            })?,
+            None => BASE64_STANDARD.decode(&self.body).map_err(|err| {
+                SoraError::InvalidConfig(format!(
+                    "cassette body for {} {} is not base64: {err}",
+                    self.method, self.url
+                ))
+            })?,
         };
+        let mut response = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            response = response.header(name, value);
+        }
+        let response = response.body(body).map_err(|err| {
+            SoraError::InvalidConfig(format!(
+                "cassette response for {} {} is invalid: {err}",
+                self.method, self.url
+            ))
+        })?;
+        Ok(reqwest::Response::from(response))
+    }
+}
 
-        let operation = self.client.submit_job(ctx.model, payload).await?;
+#[cfg(any(test, feature = "cassette"))]
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
 
-        let response = self
-            .client
-            .poll_operation(ctx.model, &operation, ctx.poll_interval)
-            .await?;
+#[cfg(any(test, feature = "cassette"))]
+#[derive(Debug)]
+struct Cassette {
+    path: PathBuf,
+    recording: bool,
+    interactions: Mutex<Vec<Interaction>>,
+    /// Held while the cassette file is rewritten, so an older snapshot never lands last.
+    writing: futures_util::lock::Mutex<()>,
+}
 
-        let videos = response.videos;
-        let maybe_bytes = videos
-            .iter()
-            .find_map(|video| video.bytes_base64_encoded.clone());
-        let video_bytes = if let Some(bytes) = maybe_bytes {
-            bytes
-        } else if videos.iter().any(|video| video.gcs_uri.is_some()) {
-            return Err(SoraError::UnsupportedOperation(
-                "Veo returned Cloud Storage URIs; provide gcp_storage_uri= or download manually"
-                    .to_string(),
-            ));
+#[cfg(any(test, feature = "cassette"))]
+impl Cassette {
+    fn open(mode: &CassetteMode) -> Result<Self, SoraError> {
+        let (path, recording) = match mode {
+            CassetteMode::Record(path) => (path, true),
+            CassetteMode::Replay(path) => (path, false),
+        };
+        let interactions = if recording {
+            Vec::new()
         } else {
-            return Err(SoraError::InvalidResponse(
-                "Veo response missing video payload".to_string(),
-            ));
+            let text = std::fs::read(path).map_err(|err| {
+                SoraError::InvalidConfig(format!("cannot read cassette {}: {err}", path.display()))
+            })?;
+            let file: CassetteFile = serde_json::from_slice(&text).map_err(|err| {
+                SoraError::InvalidConfig(format!("invalid cassette {}: {err}", path.display()))
+            })?;
+            file.interactions
         };
+        Ok(Self {
+            path: path.clone(),
+            recording,
+            interactions: Mutex::new(interactions),
+            writing: futures_util::lock::Mutex::new(()),
+        })
+    }
 
-        let data = BASE64_STANDARD.decode(video_bytes).map_err(|err| {
-            SoraError::InvalidResponse(format!("invalid base64 video payload: {err}"))
-        })?;
+    /// Directory that `body_file` paths are relative to.
+    fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Record a response body, inline when small and otherwise to a numbered file beside the
+    /// cassette (`<cassette>.bodies/<n>.bin`).
+    async fn record_body(&self, body: &[u8]) -> Result<(String, Option<PathBuf>), SoraError> {
+        if body.len() <= CASSETTE_INLINE_BODY_BYTES {
+            return Ok((BASE64_STANDARD.encode(body), None));
+        }
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let file =
+            PathBuf::from(format!("{name}.bodies")).join(format!("{}.bin", quick_local_id()));
+        let path = self.dir().join(&file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        write_file(&path, body).await?;
+        Ok((String::new(), Some(file)))
+    }
 
-        fs::write(ctx.output_path, data).await?;
+    /// Send (and record) a request, or answer it from the recording.
+    async fn exchange(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, SoraError> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let request_body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .unwrap_or_default();
+
+        if self.recording {
+            let response = client.execute(request).await?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.into())))
+                .collect();
+            let bytes = response.bytes().await?;
+            let (body, body_file) = self.record_body(&bytes).await?;
+            let interaction = Interaction {
+                method,
+                url,
+                request_body,
+                status,
+                headers,
+                body,
+                body_file,
+                served: true,
+            };
+            let response = interaction.to_response(self.dir()).await;
+            self.interactions
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push(interaction);
+            // Rewrite the whole file each time so an interrupted run still leaves valid JSON.
+            // The snapshot is taken under the write lock, so the last write holds everything.
+            let _writing = self.writing.lock().await;
+            let file = serde_json::to_vec_pretty(&CassetteFile {
+                interactions: self
+                    .interactions
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .clone(),
+            })?;
+            write_file(&self.path, file).await?;
+            return response;
+        }
 
-        Ok(RenderOutcome {
-            remote_id: operation,
-            model: ctx.model.to_string(),
-            seconds: ctx.seconds,
-            size: ctx.size.to_string(),
-            created_at: None,
-        })
+        let interaction = self.replay(&method, &url, &request_body)?;
+        interaction.to_response(self.dir()).await
     }
 
-    async fn download(
+    /// The recorded answer to a request: the first unserved match, or the last match again.
+    fn replay(
         &self,
-        _remote_id: &str,
-        variant: VideoVariant,
-        _output_path: &Path,
-    ) -> Result<(), SoraError> {
-        Err(SoraError::UnsupportedOperation(format!(
-            "Veo backend does not support downloading {variant:?} directly"
-        )))
+        method: &str,
+        url: &str,
+        request_body: &str,
+    ) -> Result<Interaction, SoraError> {
+        let mut interactions = self
+            .interactions
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let matches = |interaction: &Interaction| {
+            interaction.method == method
+                && interaction.url == url
+                && interaction.request_body == request_body
+        };
+        let Some(index) = interactions
+            .iter()
+            .position(|interaction| !interaction.served && matches(interaction))
+            .or_else(|| interactions.iter().rposition(matches))
+        else {
+            return Err(SoraError::InvalidResponse(format!(
+                "no recorded response for {method} {url} in cassette {}",
+                self.path.display()
+            )));
+        };
+        interactions[index].served = true;
+        Ok(interactions[index].clone())
     }
 }
 
-fn validate_veo_duration(seconds: u32) -> Result<(), SoraError> {
-    match seconds {
-        4 | 6 | 8 => Ok(()),
-        other => Err(SoraError::InvalidConfig(format!(
-            "Veo 3 Preview requires duration 4, 6, or 8 seconds (got {other})"
-        ))),
-    }
+/// Request and token quota from a provider's `x-ratelimit-*` response headers (sent by
+/// OpenAI). Fields the response left out are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub limit_requests: Option<u64>,
+    pub remaining_requests: Option<u64>,
+    /// Time until the request quota is fully replenished.
+    pub reset_requests: Option<Duration>,
+    pub limit_tokens: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    /// Time until the token quota is fully replenished.
+    pub reset_tokens: Option<Duration>,
 }
 
-fn size_to_resolution(size: &str) -> Option<String> {
-    match size {
-        "1280x720" | "720x1280" => Some("720p".to_string()),
-        "1920x1080" | "1080x1920" => Some("1080p".to_string()),
-        _ => None,
+impl RateLimitInfo {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(format!("x-ratelimit-{name}"))
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        let count = |name: &str| header(name).and_then(|value| value.parse().ok());
+        let reset = |name: &str| header(name).and_then(parse_reset_duration);
+        let info = Self {
+            limit_requests: count("limit-requests"),
+            remaining_requests: count("remaining-requests"),
+            reset_requests: reset("reset-requests"),
+            limit_tokens: count("limit-tokens"),
+            remaining_tokens: count("remaining-tokens"),
+            reset_tokens: reset("reset-tokens"),
+        };
+        (info != Self::default()).then_some(info)
     }
 }
 
-fn size_to_aspect_ratio(size: &str) -> Option<String> {
-    match size {
-        "1280x720" | "1920x1080" => Some("16:9".to_string()),
-        "720x1280" | "1080x1920" => Some("9:16".to_string()),
-        _ => None,
+/// Parse a reset time such as `1s`, `6m0s`, or `20ms`; a bare number is seconds.
+fn parse_reset_duration(text: &str) -> Option<Duration> {
+    if text.is_empty() {
+        return None;
+    }
+    let is_number = |c: char| c.is_ascii_digit() || c == '.';
+    let mut seconds = 0.0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (number, tail) = rest.split_at(rest.find(|c| !is_number(c)).unwrap_or(rest.len()));
+        let (unit, tail) = tail.split_at(tail.find(is_number).unwrap_or(tail.len()));
+        let scale = match unit {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" | "" => 1.0,
+            "ms" => 1e-3,
+            "us" | "µs" => 1e-6,
+            "ns" => 1e-9,
+            _ => return None,
+        };
+        seconds += number.parse::<f64>().ok()? * scale;
+        rest = tail;
     }
+    Duration::try_from_secs_f64(seconds).ok()
 }
 
-async fn encode_first_frame(path: &Path) -> Result<String, SoraError> {
-    let bytes = fs::read(path).await?;
-    Ok(BASE64_STANDARD.encode(bytes))
+/// Counters and histograms published through the `metrics` facade when the `metrics` feature
+/// is on; without it these are empty and compile away.
+mod telemetry {
+    use super::ProviderKind;
+    use std::time::Duration;
+
+    /// A render ended, successfully or not, after `elapsed`.
+    pub(crate) fn render_finished(provider: ProviderKind, succeeded: bool, elapsed: Duration) {
+        #[cfg(feature = "metrics")]
+        {
+            let status = if succeeded { "completed" } else { "failed" };
+            metrics::counter!(
+                "continuator_renders_total",
+                "provider" => provider.as_str(),
+                "status" => status
+            )
+            .increment(1);
+            metrics::histogram!(
+                "continuator_render_duration_seconds",
+                "provider" => provider.as_str()
+            )
+            .record(elapsed.as_secs_f64());
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = (provider, succeeded, elapsed);
+    }
+
+    /// One status check of an unfinished job.
+    pub(crate) fn polled(provider: ProviderKind) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!("continuator_poll_iterations_total", "provider" => provider.as_str())
+            .increment(1);
+        #[cfg(not(feature = "metrics"))]
+        let _ = provider;
+    }
+
+    /// A provider request was retried after a transient failure.
+    pub(crate) fn retried() {
+        #[cfg(feature = "metrics")]
+        metrics::counter!("continuator_retries_total").increment(1);
+    }
 }
 
-/// Request for creating a brand-new video.
-#[derive(Debug, Clone)]
-pub struct CreateVideoRequest {
-    pub prompt: String,
-    /// Local identifier for saving the video; used as filename stem.
-    pub local_id: String,
-    pub model: Option<String>,
-    pub seconds: Option<u32>,
-    pub size: Option<String>,
+/// Reject a Cloud Storage setting that isn't a `gs://` location.
+fn check_gs_uri(name: &str, uri: &str) -> Result<(), SoraError> {
+    if !uri.starts_with("gs://") || uri.len() == "gs://".len() {
+        return Err(SoraError::InvalidConfig(format!(
+            "{name} must be a gs:// location (got '{uri}')"
+        )));
+    }
+    Ok(())
 }
 
-/// Request for creating a continuation using the last frame of an existing video.
-#[derive(Debug, Clone)]
-pub struct ContinueVideoRequest {
-    /// Existing local video identifier to continue from.
-    pub parent_local_id: String,
-    /// New local identifier for the continuation clip.
-    pub local_id: String,
-    pub prompt: String,
-    pub model: Option<String>,
-    pub seconds: Option<u32>,
-    pub size: Option<String>,
+/// Reject a configured directory that already exists as a file, which `create_dir_all` would
+/// otherwise report as an opaque OS error.
+fn check_not_file(name: &str, path: &Path) -> Result<(), SoraError> {
+    match std::fs::metadata(path) {
+        Ok(metadata) if !metadata.is_dir() => Err(SoraError::InvalidConfig(format!(
+            "{name} exists and is not a directory: {}",
+            path.display()
+        ))),
+        _ => Ok(()),
+    }
 }
 
-/// Stored metadata for each downloaded clip.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VideoMetadata {
-    pub local_id: String,
-    pub remote_id: String,
-    pub prompt: String,
-    pub model: String,
-    pub seconds: u32,
-    pub size: String,
-    pub created_at: Option<i64>,
-    pub file_path: PathBuf,
-    pub parent: Option<String>,
-    #[serde(default = "ProviderKind::default_backend")]
-    pub backend: ProviderKind,
+/// Whether a response is worth retrying: 429 always, since the provider turned the request
+/// away, and 5xx only when resending can't duplicate work.
+fn should_retry_status(status: StatusCode, idempotent: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || (idempotent && status.is_server_error())
 }
 
-/// Primary entry point for managing videos and continuations.
-pub struct VideoManager {
+struct ResolvedManagerConfig {
     backend: Backend,
     data_dir: PathBuf,
+    temp_dir: PathBuf,
     poll_interval: Duration,
+    frame_extraction: FrameExtractionStrategy,
+    duration_tolerance: f64,
+    strict_duration: bool,
+    frame_options: FrameOptions,
+    thumbnail_size: Option<(u32, u32)>,
+    audio_crossfade: Option<Duration>,
+    checksums: bool,
+    print_ffmpeg: bool,
+    probe_concurrency: usize,
+    stitch_chunk_size: Option<usize>,
+    encode: EncodeSettings,
+    audio_sample_rate: Option<u32>,
+    audio_channels: Option<u32>,
+    upload_command: Option<String>,
+    skip_duration_validation: bool,
+    keep_seed_frame: bool,
+    keep_manifest: bool,
+    include_model_in_filename: bool,
+    include_backend_in_filename: bool,
+    strict_provider: bool,
+    render_retries: u32,
+    prompt_prefix: Option<String>,
+    prompt_suffix: Option<String>,
 }
 
-impl VideoManager {
-    /// Build a new manager from high-level configuration.
-    pub fn new(config: ContinuatorConfig) -> Result<Self, SoraError> {
-        let resolved = config.resolve()?;
-        Ok(Self {
-            backend: resolved.backend,
-            data_dir: resolved.data_dir,
-            poll_interval: resolved.poll_interval,
-        })
-    }
+#[derive(Debug)]
+struct BackendDefaults {
+    model: String,
+    size: String,
+    /// Whether `size` was configured rather than picked for the default model.
+    size_configured: bool,
+    seconds: u32,
+}
 
-    /// Ensure the data directory exists on disk.
-    async fn ensure_data_dir(&self) -> Result<(), SoraError> {
-        fs::create_dir_all(&self.data_dir).await?;
-        Ok(())
+impl BackendDefaults {
+    /// Size for a render of `model` that doesn't name one.
+    fn size_for(&self, provider: ProviderKind, model: &str) -> String {
+        if self.size_configured {
+            self.size.clone()
+        } else {
+            provider.default_size(model).to_string()
+        }
     }
+}
 
-    fn video_path(&self, local_id: &str) -> PathBuf {
-        self.data_dir.join(format!("{local_id}.mp4"))
-    }
+/// Remote jobs a backend is waiting on, keyed by job id. An entry outlives its wait only when
+/// the waiting future is dropped (say, by a timeout), which is exactly when the job needs
+/// canceling and its partial download cleaning up.
+#[derive(Debug, Default)]
+struct InFlight(Mutex<std::collections::HashMap<String, PathBuf>>);
 
-    fn metadata_path(&self, local_id: &str) -> PathBuf {
-        self.data_dir.join(format!("{local_id}.json"))
+impl InFlight {
+    async fn watch<T>(
+        &self,
+        remote_id: &str,
+        output_path: &Path,
+        wait: impl std::future::Future<Output = T>,
+    ) -> T {
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(remote_id.to_string(), output_path.to_path_buf());
+        let result = wait.await;
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(remote_id);
+        result
     }
 
-    async fn save_metadata(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
-        let path = self.metadata_path(&metadata.local_id);
-        let data = serde_json::to_vec_pretty(metadata)?;
-        fs::write(path, data).await?;
-        Ok(())
+    fn snapshot(&self) -> Vec<(String, PathBuf)> {
+        self.0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .map(|(id, path)| (id.clone(), path.clone()))
+            .collect()
     }
+}
 
-    async fn load_metadata(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
-        let path = self.metadata_path(local_id);
-        let bytes = fs::read(&path)
-            .await
-            .map_err(|_| SoraError::MetadataNotFound(local_id.to_string()))?;
-        let metadata: VideoMetadata = serde_json::from_slice(&bytes)?;
-        Ok(metadata)
+#[derive(Debug)]
+enum Backend {
+    Sora(SoraBackend),
+    Veo(VeoBackend),
+    #[cfg(any(test, feature = "mock"))]
+    Mock(MockBackend),
+}
+
+impl Backend {
+    fn kind(&self) -> ProviderKind {
+        match self {
+            Backend::Sora(_) => ProviderKind::Sora,
+            Backend::Veo(_) => ProviderKind::Veo,
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => ProviderKind::Mock,
+        }
     }
 
-    /// Fetch the metadata for a given local identifier.
-    pub async fn get_metadata(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
-        self.load_metadata(local_id).await
+    fn defaults(&self) -> &BackendDefaults {
+        match self {
+            Backend::Sora(backend) => &backend.defaults,
+            Backend::Veo(backend) => &backend.defaults,
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => &backend.defaults,
+        }
     }
 
-    /// Download a variant of the rendered asset (video, thumbnail, spritesheet).
-    pub async fn download_asset(
-        &self,
-        local_id: &str,
-        variant: VideoVariant,
-        output_path: &Path,
-    ) -> Result<(), SoraError> {
-        let metadata = self.load_metadata(local_id).await?;
-        if let Some(parent) = output_path.parent() {
-            if !parent.as_os_str().is_empty() {
-                fs::create_dir_all(parent).await?;
-            }
-        }
+    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        let started = Instant::now();
+        let result = match self {
+            Backend::Sora(backend) => backend.render(ctx).await,
+            Backend::Veo(backend) => backend.render(ctx).await,
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => backend.render(ctx).await,
+        };
+        telemetry::render_finished(self.kind(), result.is_ok(), started.elapsed());
+        result
+    }
 
-        if metadata.backend == ProviderKind::Veo && matches!(variant, VideoVariant::Video) {
-            fs::copy(&metadata.file_path, output_path).await?;
-            return Ok(());
+    /// Submit a render without waiting for it. The outcome carries the job id and the
+    /// requested values; [`Backend::resume`] collects the video later.
+    async fn submit(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        match self {
+            Backend::Sora(backend) => backend.submit(&ctx).await,
+            Backend::Veo(backend) => backend.submit(&ctx).await,
+            // Mock renders are instant, so the video is already in place when this returns.
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => backend.render(ctx).await,
         }
-
-        self.backend
-            .download(&metadata.remote_id, variant, output_path)
-            .await
     }
 
-    /// Generate a brand-new clip using the configured backend and persist the results locally.
-    pub async fn create_video(
+    /// Produce a variation of an existing remote video with a new prompt.
+    async fn remix(
         &self,
-        request: CreateVideoRequest,
-    ) -> Result<VideoMetadata, SoraError> {
-        self.ensure_data_dir().await?;
-        if fs::try_exists(self.metadata_path(&request.local_id)).await? {
-            return Err(SoraError::InvalidConfig(format!(
-                "local id '{}' already exists",
-                request.local_id
-            )));
+        source_remote_id: &str,
+        prompt: &str,
+        poll_interval: Duration,
+        output_path: &Path,
+        source_seconds: u32,
+        source_size: &str,
+    ) -> Result<RenderOutcome, SoraError> {
+        match self {
+            Backend::Sora(backend) => {
+                let job = backend.client.remix_video(source_remote_id, prompt).await?;
+                backend
+                    .finish(
+                        job.id,
+                        poll_interval,
+                        output_path,
+                        source_seconds,
+                        source_size,
+                    )
+                    .await
+            }
+            Backend::Veo(_) => Err(SoraError::UnsupportedOperation(
+                "Veo does not support remixing videos".to_string(),
+            )),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => {
+                wait_to_poll(backend.sleeper.as_ref(), poll_interval).await;
+                backend
+                    .copy_render(source_remote_id, output_path, source_seconds, source_size)
+                    .await
+            }
         }
-
-        let defaults = self.backend.defaults();
-        let model = request
-            .model
-            .as_deref()
-            .unwrap_or(&defaults.model)
-            .to_string();
-        let size = request
-            .size
-            .as_deref()
-            .unwrap_or(&defaults.size)
-            .to_string();
-        let seconds = request.seconds.unwrap_or(defaults.seconds);
-
-        let video_path = self.video_path(&request.local_id);
-        let outcome = self
-            .backend
-            .render(RenderContext {
-                prompt: &request.prompt,
-                model: &model,
-                seconds,
-                size: &size,
-                poll_interval: self.poll_interval,
-                output_path: &video_path,
-                first_frame_path: None,
-            })
-            .await?;
-
-        let metadata = VideoMetadata {
-            local_id: request.local_id,
-            remote_id: outcome.remote_id,
-            prompt: request.prompt,
-            model: outcome.model,
-            seconds: outcome.seconds,
-            size: outcome.size,
-            created_at: outcome.created_at,
-            file_path: video_path,
-            parent: None,
-            backend: self.backend.kind(),
-        };
-
-        self.save_metadata(&metadata).await?;
-        Ok(metadata)
     }
 
-    /// Create a continuation using the last frame of an existing clip as an image reference.
-    pub async fn continue_video(
+    /// Ask the provider where a job stands without waiting for it.
+    async fn remote_status(
         &self,
-        request: ContinueVideoRequest,
-    ) -> Result<VideoMetadata, SoraError> {
-        self.ensure_data_dir().await?;
-        if fs::try_exists(self.metadata_path(&request.local_id)).await? {
-            return Err(SoraError::InvalidConfig(format!(
-                "local id '{}' already exists",
-                request.local_id
-            )));
+        remote_id: &str,
+    ) -> Result<(VideoStatus, Option<f64>), SoraError> {
+        match self {
+            Backend::Sora(backend) => {
+                let job = backend.client.retrieve_video(remote_id).await?;
+                Ok((job.status, job.progress))
+            }
+            Backend::Veo(backend) => {
+                let model = veo_operation_model(remote_id)?;
+                let operation = backend.client.fetch_operation(model, remote_id).await?;
+                let status = match (operation.done.unwrap_or(false), operation.error) {
+                    (_, Some(_)) => VideoStatus::Failed,
+                    (true, None) => VideoStatus::Completed,
+                    (false, None) => VideoStatus::InProgress,
+                };
+                Ok((status, None))
+            }
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => {
+                backend.rendered(remote_id)?;
+                Ok((VideoStatus::Completed, Some(100.0)))
+            }
         }
-        let parent = self.load_metadata(&request.parent_local_id).await?;
-        let parent_video_path = self.video_path(&request.parent_local_id);
+    }
 
-        if !parent_video_path.exists() {
-            return Err(SoraError::VideoNotFound(request.parent_local_id));
+    /// Whether the provider still serves a finished job's video, or has expired it.
+    async fn remote_available(&self, remote_id: &str) -> Result<bool, SoraError> {
+        match self {
+            Backend::Sora(backend) => backend.client.content_available(remote_id).await,
+            Backend::Veo(_) => self.check_remote_copies().map(|()| false),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => Ok(backend.rendered(remote_id).is_ok()),
         }
+    }
 
-        let last_frame_path = self
-            .extract_last_frame(&parent_video_path, &request.local_id)
-            .await?;
-
-        let defaults = self.backend.defaults();
-        let model = request
-            .model
-            .as_ref()
-            .or_else(|| Some(&parent.model))
-            .unwrap_or(&defaults.model)
-            .to_string();
-        let size = request
-            .size
-            .as_ref()
-            .or_else(|| Some(&parent.size))
-            .unwrap_or(&defaults.size)
-            .to_string();
-        let seconds = request
-            .seconds
-            .or(Some(parent.seconds))
-            .unwrap_or(defaults.seconds);
-
-        let video_path = self.video_path(&request.local_id);
-        let outcome = self
-            .backend
-            .render(RenderContext {
-                prompt: &request.prompt,
-                model: &model,
-                seconds,
-                size: &size,
-                poll_interval: self.poll_interval,
-                output_path: &video_path,
-                first_frame_path: Some(&last_frame_path),
-            })
-            .await?;
-
-        let metadata = VideoMetadata {
-            local_id: request.local_id,
-            remote_id: outcome.remote_id,
-            prompt: request.prompt,
-            model: outcome.model,
-            seconds: outcome.seconds,
-            size: outcome.size,
-            created_at: outcome.created_at,
-            file_path: video_path,
-            parent: Some(parent.local_id),
-            backend: self.backend.kind(),
-        };
-
-        self.save_metadata(&metadata).await?;
+    /// Fail unless the provider keeps finished videos that [`Backend::remote_available`] can
+    /// check.
+    fn check_remote_copies(&self) -> Result<(), SoraError> {
+        match self {
+            Backend::Veo(_) => Err(SoraError::UnsupportedOperation(
+                "Veo returns videos with the finished operation and keeps no copy to check"
+                    .to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
 
-        let _ = fs::remove_file(last_frame_path).await;
+    /// Waits for poll loops, shared with the clients' retries and rate limiter.
+    fn sleeper(&self) -> &dyn Sleeper {
+        match self {
+            Backend::Sora(backend) => backend.client.policy.sleeper.as_ref(),
+            Backend::Veo(backend) => backend.client.policy.sleeper.as_ref(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => backend.sleeper.as_ref(),
+        }
+    }
 
-        Ok(metadata)
+    /// Jobs this process is still waiting on, with the file each one is being written to.
+    fn in_flight(&self) -> Vec<(String, PathBuf)> {
+        match self {
+            Backend::Sora(backend) => backend.in_flight.snapshot(),
+            Backend::Veo(backend) => backend.in_flight.snapshot(),
+            // Mock renders finish before anything could abandon them.
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => Vec::new(),
+        }
     }
 
-    /// Enumerate all locally stored clips.
-    pub async fn list_videos(&self) -> Result<Vec<VideoMetadata>, SoraError> {
-        self.ensure_data_dir().await?;
-        let mut entries = Vec::new();
-        let mut dir = fs::read_dir(&self.data_dir).await?;
-        while let Some(entry) = dir.next_entry().await? {
-            if entry.path().extension().and_then(|s| s.to_str()) == Some("json") {
-                let stem = entry
-                    .path()
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .map(|s| s.to_string());
-                if let Some(local_id) = stem {
-                    if let Ok(metadata) = self.load_metadata(&local_id).await {
-                        entries.push(metadata);
-                    }
+    /// Ask the provider to stop a render. Veo's operations API has no cancel, so those are
+    /// reported as unsupported rather than failed.
+    ///
+    /// A render can finish between the status check and the cancel, so a cancel the provider
+    /// refuses because the job is done (or already deleted) is reported as already finished.
+    async fn cancel(&self, remote_id: &str) -> Result<CancelOutcome, SoraError> {
+        match self {
+            Backend::Sora(backend) => {
+                if backend.client.cancel_video(remote_id).await? {
+                    return Ok(CancelOutcome::Canceled);
                 }
+                let status = match backend.client.find_video(remote_id).await? {
+                    Some(job) => job.status,
+                    None => VideoStatus::Unknown("deleted".to_string()),
+                };
+                Ok(CancelOutcome::AlreadyFinished(status))
+            }
+            Backend::Veo(_) => Ok(CancelOutcome::Unsupported),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => {
+                backend.rendered(remote_id)?;
+                Ok(CancelOutcome::Canceled)
             }
         }
-        entries.sort_by(|a, b| a.local_id.cmp(&b.local_id));
-        Ok(entries)
     }
 
-    /// Concatenate multiple local clips into a single MP4 under the output identifier.
-    pub async fn stitch_videos(
-        &self,
-        output_local_id: &str,
-        input_local_ids: &[String],
-    ) -> Result<PathBuf, SoraError> {
-        if input_local_ids.is_empty() {
-            return Err(SoraError::InvalidConfig(
-                "stitch requires at least one input clip".to_string(),
-            ));
+    /// Remote ids of every job the provider reports as queued or in progress, including ones
+    /// submitted elsewhere. Veo can't list its operations, so it reports none.
+    async fn unfinished_jobs(&self) -> Result<Vec<String>, SoraError> {
+        match self {
+            Backend::Sora(backend) => Ok(backend
+                .client
+                .list_jobs()
+                .await?
+                .into_iter()
+                .filter(|job| !job.status.is_terminal())
+                .map(|job| job.id)
+                .collect()),
+            Backend::Veo(_) => Ok(Vec::new()),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => Ok(Vec::new()),
         }
+    }
 
-        self.ensure_data_dir().await?;
-
-        let output_path = self.video_path(output_local_id);
-        let manifest_path = self
-            .data_dir
-            .join(format!(".concat-{}.txt", output_local_id));
-
-        let mut manifest = String::new();
-        for id in input_local_ids {
-            let metadata = self.load_metadata(id).await?;
-            if !metadata.file_path.exists() {
-                return Err(SoraError::VideoNotFound(id.clone()));
+    /// Pick up a job submitted elsewhere, wait for it, and download the result.
+    async fn resume(
+        &self,
+        remote_id: &str,
+        poll_interval: Duration,
+        output_path: &Path,
+        seconds: u32,
+        size: &str,
+    ) -> Result<RenderOutcome, SoraError> {
+        match self {
+            Backend::Sora(backend) => {
+                backend
+                    .in_flight
+                    .watch(
+                        remote_id,
+                        output_path,
+                        backend.finish(
+                            remote_id.to_string(),
+                            poll_interval,
+                            output_path,
+                            seconds,
+                            size,
+                        ),
+                    )
+                    .await
+            }
+            Backend::Veo(backend) => {
+                let model = veo_operation_model(remote_id)?;
+                backend
+                    .in_flight
+                    .watch(
+                        remote_id,
+                        output_path,
+                        backend.finish(
+                            model,
+                            remote_id.to_string(),
+                            poll_interval,
+                            output_path,
+                            seconds,
+                            size,
+                        ),
+                    )
+                    .await
+            }
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => {
+                wait_to_poll(backend.sleeper.as_ref(), poll_interval).await;
+                backend
+                    .copy_render(remote_id, output_path, seconds, size)
+                    .await
             }
-            let abs_path = fs::canonicalize(&metadata.file_path).await?;
-            manifest.push_str(&format!("file '{}'\n", abs_path.display()));
         }
+    }
 
-        fs::write(&manifest_path, manifest).await?;
-
-        let status = Command::new("ffmpeg")
-            .arg("-y")
-            .arg("-f")
-            .arg("concat")
-            .arg("-safe")
-            .arg("0")
-            .arg("-i")
-            .arg(&manifest_path)
-            .arg("-c")
-            .arg("copy")
-            .arg(&output_path)
-            .status()
-            .await
-            .map_err(|_| SoraError::FfmpegMissing)?;
-
-        let _ = fs::remove_file(&manifest_path).await;
-
-        if !status.success() {
-            return Err(SoraError::FfmpegConcatFailed(format!(
-                "ffmpeg exited with status {status}"
-            )));
+    async fn download(
+        &self,
+        remote_id: &str,
+        variant: VideoVariant,
+        output_path: &Path,
+        resume: bool,
+    ) -> Result<(), SoraError> {
+        match self {
+            Backend::Sora(backend) => {
+                backend
+                    .client
+                    .download_video(remote_id, variant, output_path, resume)
+                    .await
+            }
+            Backend::Veo(backend) => backend.download(remote_id, variant, output_path).await,
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => backend.download(remote_id, variant, output_path).await,
         }
-
-        Ok(output_path)
     }
 
-    async fn extract_last_frame(
+    async fn download_to_writer<W>(
         &self,
-        video_path: &Path,
-        local_id: &str,
-    ) -> Result<PathBuf, SoraError> {
-        let frame_path = std::env::temp_dir().join(format!("{local_id}_last.png"));
-        let status = Command::new("ffmpeg")
-            .arg("-v")
-            .arg("error")
-            .arg("-i")
-            .arg(video_path)
-            .arg("-vf")
-            .arg("reverse")
-            .arg("-frames:v")
-            .arg("1")
-            .arg("-y")
-            .arg(&frame_path)
-            .status()
-            .await
-            .map_err(|_| SoraError::FfmpegMissing)?;
-
-        if !status.success() {
-            return Err(SoraError::FfmpegFailed(format!(
-                "ffmpeg exited with status {status}"
-            )));
+        remote_id: &str,
+        variant: VideoVariant,
+        writer: &mut W,
+    ) -> Result<(), SoraError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        match self {
+            Backend::Sora(backend) => {
+                backend
+                    .client
+                    .download_to_writer(remote_id, variant, writer)
+                    .await
+            }
+            Backend::Veo(_) => Err(SoraError::VariantUnsupported {
+                backend: ProviderKind::Veo,
+                variant,
+            }),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(backend) => match variant {
+                VideoVariant::Video => {
+                    copy_file_to_writer(&backend.rendered(remote_id)?, writer).await
+                }
+                _ => {
+                    writer.write_all(MOCK_IMAGE).await?;
+                    Ok(())
+                }
+            },
         }
-
-        Ok(frame_path)
     }
 }
 
-/// Type-safe variants for downloads.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VideoVariant {
-    Video,
-    Thumbnail,
-    Spritesheet,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-pub struct VideoJob {
-    pub id: String,
-    pub object: Option<String>,
-    pub created_at: Option<i64>,
-    pub status: VideoStatus,
-    pub model: String,
-    pub progress: Option<f64>,
-    #[serde(deserialize_with = "deserialize_optional_u32", default)]
-    pub seconds: Option<u32>,
-    pub size: Option<String>,
-    pub error: Option<ApiError>,
+#[derive(Clone, Copy)]
+struct RenderContext<'a> {
+    prompt: &'a str,
+    model: &'a str,
+    seconds: u32,
+    size: &'a str,
+    poll_interval: Duration,
+    output_path: &'a Path,
+    first_frame: Option<&'a ReferenceSource>,
+    /// Overrides the Veo backend's Cloud Storage prefix for this render.
+    storage_uri: Option<&'a str>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct ApiError {
-    pub message: Option<String>,
+struct RenderOutcome {
+    remote_id: String,
+    model: String,
+    seconds: u32,
+    size: String,
+    created_at: Option<i64>,
+    created_at_source: ValueSource,
+    seconds_source: ValueSource,
+    /// Additional videos saved from the same render, beyond the one at the output path.
+    samples: Vec<PathBuf>,
+    /// Videos the provider left in Cloud Storage rather than returning inline.
+    storage_uris: Vec<String>,
+    /// Decoded size of the primary video when it came back inline in the response.
+    inline_bytes: Option<u64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum VideoStatus {
-    Queued,
-    InProgress,
-    Completed,
-    Failed,
-    Canceled,
-    Unknown(String),
+#[derive(Debug)]
+struct SoraBackend {
+    client: SoraClient,
+    defaults: BackendDefaults,
+    reference_field: String,
+    size_fallback: bool,
+    in_flight: InFlight,
 }
 
-fn deserialize_optional_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let opt = Option::<serde_json::Value>::deserialize(deserializer)?;
-    if let Some(value) = opt {
-        match value {
-            serde_json::Value::Number(n) => n
-                .as_u64()
-                .map(|n| n as u32)
-                .ok_or_else(|| serde::de::Error::custom("seconds field is not a valid u32"))
-                .map(Some),
-            serde_json::Value::String(s) => s
-                .parse::<u32>()
-                .map(Some)
-                .map_err(|_| serde::de::Error::custom("seconds field string is not a valid u32")),
-            _ => Err(serde::de::Error::custom(
-                "unexpected type for seconds field",
-            )),
-        }
-    } else {
-        Ok(None)
+impl SoraBackend {
+    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        let (job, size) = self.create_job(&ctx).await?;
+        self.in_flight
+            .watch(
+                &job.id,
+                ctx.output_path,
+                self.finish(
+                    job.id.clone(),
+                    ctx.poll_interval,
+                    ctx.output_path,
+                    ctx.seconds,
+                    &size,
+                ),
+            )
+            .await
     }
-}
 
-impl<'de> Deserialize<'de> for VideoStatus {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let value = String::deserialize(deserializer)?;
-        let status = match value.as_str() {
-            "queued" => VideoStatus::Queued,
-            "in_progress" => VideoStatus::InProgress,
-            "completed" => VideoStatus::Completed,
-            "failed" => VideoStatus::Failed,
-            "canceled" => VideoStatus::Canceled,
-            other => VideoStatus::Unknown(other.to_string()),
-        };
-        Ok(status)
+    async fn submit(&self, ctx: &RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        let (job, size) = self.create_job(ctx).await?;
+        Ok(RenderOutcome {
+            remote_id: job.id,
+            model: job.model,
+            seconds_source: ValueSource::reported(job.seconds.is_some()),
+            seconds: job.seconds.unwrap_or(ctx.seconds),
+            size: job.size.unwrap_or(size),
+            created_at_source: ValueSource::reported(job.created_at.is_some()),
+            created_at: job.created_at,
+            samples: Vec::new(),
+            storage_uris: Vec::new(),
+            inline_bytes: None,
+        })
     }
-}
 
-impl Serialize for VideoStatus {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let value = match self {
-            VideoStatus::Queued => "queued",
-            VideoStatus::InProgress => "in_progress",
-            VideoStatus::Completed => "completed",
-            VideoStatus::Failed => "failed",
-            VideoStatus::Canceled => "canceled",
-            VideoStatus::Unknown(other) => other.as_str(),
+    /// Submit a render and return the job with the size it was submitted at. With
+    /// `size_fallback`, a size the model refuses is retried once at its nearest supported size.
+    async fn create_job(&self, ctx: &RenderContext<'_>) -> Result<(VideoJob, String), SoraError> {
+        let mut builder = ApiCreateRequest {
+            prompt: ctx.prompt.to_string(),
+            model: ctx.model.to_string(),
+            seconds: ctx.seconds,
+            size: ctx.size.to_string(),
+            input_reference: ctx.first_frame.cloned(),
+            reference_field: self.reference_field.clone(),
+        };
+        let err = match self.client.create_video(&mut builder).await {
+            Ok(job) => return Ok((job, builder.size)),
+            Err(err) => err,
+        };
+        let fallback = ProviderKind::Sora.nearest_size(ctx.model, ctx.size);
+        let Some(fallback) = fallback.filter(|_| self.size_fallback && err.is_size_rejection())
+        else {
+            return Err(err);
         };
-        serializer.serialize_str(value)
+        warn!(
+            model = ctx.model,
+            requested = ctx.size,
+            fallback,
+            "size rejected; retrying at the nearest supported size"
+        );
+        builder.size = fallback.to_string();
+        let job = self.client.create_video(&mut builder).await?;
+        Ok((job, builder.size))
     }
-}
 
-impl VideoStatus {
-    pub fn is_terminal(&self) -> bool {
-        matches!(
-            self,
-            VideoStatus::Completed | VideoStatus::Failed | VideoStatus::Canceled
+    /// Wait for an already-submitted job and download its video.
+    async fn finish(
+        &self,
+        remote_id: String,
+        poll_interval: Duration,
+        output_path: &Path,
+        requested_seconds: u32,
+        requested_size: &str,
+    ) -> Result<RenderOutcome, SoraError> {
+        let job = self.wait_for_completion(remote_id, poll_interval).await?;
+
+        self.client
+            .download_video(&job.id, VideoVariant::Video, output_path, false)
+            .await?;
+
+        Ok(RenderOutcome {
+            remote_id: job.id,
+            model: job.model,
+            seconds_source: ValueSource::reported(job.seconds.is_some()),
+            seconds: job.seconds.unwrap_or(requested_seconds),
+            size: job.size.unwrap_or_else(|| requested_size.to_string()),
+            created_at_source: ValueSource::reported(job.created_at.is_some()),
+            created_at: job.created_at,
+            samples: Vec::new(),
+            storage_uris: Vec::new(),
+            inline_bytes: None,
+        })
+    }
+
+    async fn wait_for_completion(
+        &self,
+        remote_id: String,
+        poll_interval: Duration,
+    ) -> Result<VideoJob, SoraError> {
+        let id = remote_id.as_str();
+        poll_until(
+            ProviderKind::Sora,
+            self.client.policy.sleeper.as_ref(),
+            poll_interval,
+            || self.client.retrieve_video(id),
+            |job| match job.status {
+                VideoStatus::Completed => Ok(Some(job)),
+                VideoStatus::Failed => {
+                    let error = job.error.unwrap_or(ApiError {
+                        message: None,
+                        code: None,
+                        param: None,
+                    });
+                    Err(SoraError::JobFailed {
+                        backend: ProviderKind::Sora,
+                        remote_id: Some(remote_id.clone()),
+                        message: error.message.unwrap_or_else(|| "unknown error".to_string()),
+                        code: error.code,
+                        param: error.param,
+                        status: Some(VideoStatus::Failed),
+                    })
+                }
+                VideoStatus::Canceled => Err(SoraError::JobFailed {
+                    backend: ProviderKind::Sora,
+                    remote_id: Some(remote_id.clone()),
+                    message: "job was canceled".to_string(),
+                    code: None,
+                    param: None,
+                    status: Some(VideoStatus::Canceled),
+                }),
+                _ => {
+                    debug!(id = %remote_id, status = ?job.status, "polling video status");
+                    Ok(None)
+                }
+            },
         )
+        .await
     }
 }
 
-struct ApiCreateRequest {
-    prompt: String,
-    model: String,
-    seconds: u32,
-    size: String,
-    input_reference_path: Option<PathBuf>,
+#[derive(Debug)]
+struct VeoBackend {
+    client: VeoClient,
+    defaults: BackendDefaults,
+    generate_audio: bool,
+    enhance_prompt: bool,
+    storage_uri: Option<String>,
+    inline_warn_bytes: u64,
+    resolution: Option<String>,
+    aspect_ratio: Option<String>,
+    in_flight: InFlight,
 }
 
-impl ApiCreateRequest {
-    fn build_form(&self) -> Result<multipart::Form, SoraError> {
-        let mut form = multipart::Form::new()
-            .text("model", self.model.clone())
-            .text("prompt", self.prompt.clone())
-            .text("seconds", self.seconds.to_string())
-            .text("size", self.size.clone());
-
-        if let Some(path) = &self.input_reference_path {
-            let data = std::fs::read(path)?;
-            let part = multipart::Part::bytes(data)
-                .file_name("input.png")
-                .mime_str("image/png")
-                .map_err(SoraError::Request)?;
-            form = form.part("input_reference", part);
-        }
+impl VeoBackend {
+    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        let submitted_at = unix_timestamp();
+        let (operation, size) = self.submit_operation(&ctx).await?;
+        let mut outcome = self
+            .in_flight
+            .watch(
+                &operation,
+                ctx.output_path,
+                self.finish(
+                    ctx.model,
+                    operation.clone(),
+                    ctx.poll_interval,
+                    ctx.output_path,
+                    ctx.seconds,
+                    &size,
+                ),
+            )
+            .await?;
+        outcome.created_at = Some(submitted_at);
+        Ok(outcome)
+    }
 
-        Ok(form)
+    async fn submit(&self, ctx: &RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        let submitted_at = unix_timestamp();
+        let (operation, size) = self.submit_operation(ctx).await?;
+        Ok(RenderOutcome {
+            remote_id: operation,
+            model: ctx.model.to_string(),
+            seconds: ctx.seconds,
+            size,
+            created_at: Some(submitted_at),
+            created_at_source: ValueSource::Local,
+            seconds_source: ValueSource::Local,
+            samples: Vec::new(),
+            storage_uris: Vec::new(),
+            inline_bytes: None,
+        })
     }
-}
 
-#[derive(Debug, Clone)]
-struct SoraClient {
-    http: reqwest::Client,
-    api_key: String,
-}
+    /// Start a render and return its operation name with the size it will come out at.
+    async fn submit_operation(
+        &self,
+        ctx: &RenderContext<'_>,
+    ) -> Result<(String, String), SoraError> {
+        let (resolution, aspect_ratio) = veo_format(
+            self.resolution.as_deref(),
+            self.aspect_ratio.as_deref(),
+            ctx.size,
+        )?;
+        check_veo_format(ctx.model, &resolution, &aspect_ratio)?;
+        let size = veo_output_size(&resolution, &aspect_ratio);
+
+        let image = if let Some(reference) = ctx.first_frame {
+            let (encoded, mime_type) = reference.encode_base64().await?;
+            Some(VeoImage {
+                bytes_base64_encoded: Some(encoded),
+                gcs_uri: None,
+                mime_type,
+            })
+        } else {
+            None
+        };
 
-impl SoraClient {
-    fn new(api_key: String) -> Result<Self, SoraError> {
-        let http = reqwest::Client::builder().build()?;
-        Ok(Self { http, api_key })
+        let payload = VeoPredictRequest {
+            instances: vec![VeoInstance {
+                prompt: ctx.prompt,
+                image,
+            }],
+            parameters: VeoParameters {
+                duration_seconds: ctx.seconds,
+                generate_audio: self.generate_audio,
+                storage_uri: ctx.storage_uri.or(self.storage_uri.as_deref()),
+                resolution: Some(&resolution),
+                aspect_ratio: Some(&aspect_ratio),
+                enhance_prompt: self.enhance_prompt,
+                sample_count: None,
+            },
+        };
+
+        let operation = self.client.submit_job(ctx.model, payload).await?;
+        Ok((operation, size))
     }
 
-    async fn create_video(&self, request: &mut ApiCreateRequest) -> Result<VideoJob, SoraError> {
-        let form = request.build_form()?;
-        let url = format!("{OPENAI_API_BASE}/videos");
+    /// Poll a submitted operation to completion and write the returned video.
+    async fn finish(
+        &self,
+        model: &str,
+        operation: String,
+        poll_interval: Duration,
+        output_path: &Path,
+        seconds: u32,
+        size: &str,
+    ) -> Result<RenderOutcome, SoraError> {
         let response = self
-            .http
-            .post(&url)
-            .bearer_auth(&self.api_key)
-            .multipart(form)
-            .send()
+            .client
+            .poll_operation(model, &operation, poll_interval)
             .await?;
 
-        Self::handle_response(response).await
-    }
+        let (inline, mut storage_uris) =
+            split_veo_videos(&response.videos, self.inline_warn_bytes)?;
+        let mut inline = inline.into_iter();
+        let inline_bytes = match inline.next() {
+            Some(data) => {
+                let inline_bytes = data.len() as u64;
+                write_file(output_path, data).await?;
+                Some(inline_bytes)
+            }
+            // Written to Cloud Storage (see `storage_uri`): fetch the first video from there.
+            None if !storage_uris.is_empty() => {
+                let uri = storage_uris.remove(0);
+                self.client.download_object(&uri, output_path).await?;
+                None
+            }
+            None => {
+                return Err(SoraError::InvalidResponse(
+                    "Veo response missing video payload".to_string(),
+                ));
+            }
+        };
 
-    async fn retrieve_video(&self, video_id: &str) -> Result<VideoJob, SoraError> {
-        let url = format!("{OPENAI_API_BASE}/videos/{video_id}");
-        let response = self
-            .http
-            .get(&url)
-            .bearer_auth(&self.api_key)
-            .send()
-            .await?;
-        Self::handle_response(response).await
+        // Extra samples are paid for too, so keep them beside the primary clip.
+        let mut samples = Vec::new();
+        for (index, data) in inline.enumerate() {
+            let path = output_path.with_extension(format!("sample-{}.mp4", index + 2));
+            write_file(&path, data).await?;
+            samples.push(path);
+        }
+        if !samples.is_empty() {
+            warn!(
+                count = samples.len(),
+                "Veo returned extra samples; saved beside {}",
+                output_path.display()
+            );
+        }
+        if !storage_uris.is_empty() {
+            warn!(
+                uris = %storage_uris.join(", "),
+                "Veo left some samples in Cloud Storage"
+            );
+        }
+
+        Ok(RenderOutcome {
+            remote_id: operation,
+            model: model.to_string(),
+            seconds,
+            size: size.to_string(),
+            // Veo reports neither; fresh renders replace this with their submission time.
+            created_at: Some(unix_timestamp()),
+            created_at_source: ValueSource::Local,
+            seconds_source: ValueSource::Local,
+            samples,
+            storage_uris,
+            inline_bytes,
+        })
     }
 
-    async fn download_video(
+    async fn download(
         &self,
-        video_id: &str,
+        _remote_id: &str,
         variant: VideoVariant,
-        path: &Path,
+        _output_path: &Path,
     ) -> Result<(), SoraError> {
-        let mut url = format!("{OPENAI_API_BASE}/videos/{video_id}/content");
-        match variant {
-            VideoVariant::Video => {}
-            VideoVariant::Thumbnail => {
-                url.push_str(&format!("?variant={THUMBNAIL_VARIANT}"));
-            }
-            VideoVariant::Spritesheet => {
-                url.push_str(&format!("?variant={SPRITESHEET_VARIANT}"));
-            }
-        }
+        Err(SoraError::VariantUnsupported {
+            backend: ProviderKind::Veo,
+            variant,
+        })
+    }
+}
 
-        let response = self
-            .http
-            .get(&url)
-            .bearer_auth(&self.api_key)
-            .send()
-            .await?;
+/// 1x1 PNG served for the mock backend's thumbnail and spritesheet variants.
+#[cfg(any(test, feature = "mock"))]
+const MOCK_IMAGE: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x60, 0x60, 0x60, 0xf8,
+    0x0f, 0x00, 0x01, 0x04, 0x01, 0x00, 0x5f, 0xe5, 0xc3, 0x4b, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+    0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+/// Offline backend: "renders" by copying a fixture or generating an ffmpeg test pattern, and
+/// remembers where each remote id's video went so remixes, resumes, and downloads work.
+#[cfg(any(test, feature = "mock"))]
+#[derive(Debug)]
+struct MockBackend {
+    defaults: BackendDefaults,
+    fixture: Option<PathBuf>,
+    failures: std::sync::atomic::AtomicU32,
+    renders: Mutex<std::collections::HashMap<String, PathBuf>>,
+    sleeper: Arc<dyn Sleeper>,
+}
 
-        if !response.status().is_success() {
-            return Err(SoraError::Request(response.error_for_status().unwrap_err()));
+#[cfg(any(test, feature = "mock"))]
+impl MockBackend {
+    async fn render(&self, ctx: RenderContext<'_>) -> Result<RenderOutcome, SoraError> {
+        if let Some(reference) = ctx.first_frame {
+            reference.validate()?;
+        }
+        // Stand in for the provider's queue time.
+        wait_to_poll(self.sleeper.as_ref(), ctx.poll_interval).await;
+        let failing = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            });
+        if let Ok(left) = failing {
+            return Err(SoraError::JobFailed {
+                backend: ProviderKind::Mock,
+                remote_id: Some(format!("mock_failed_{left}")),
+                message: "mock render failed".to_string(),
+                code: None,
+                param: None,
+                status: Some(VideoStatus::Failed),
+            });
         }
 
-        let mut file = fs::File::create(path).await?;
-        let mut stream = response.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            file.write_all(&chunk).await?;
+        match &self.fixture {
+            Some(fixture) => {
+                fs::copy(fixture, ctx.output_path).await?;
+            }
+            None => {
+                let (width, height) = parse_dimensions(ctx.size)?;
+                let status = Command::new("ffmpeg")
+                    .arg("-v")
+                    .arg("error")
+                    .arg("-y")
+                    .arg("-f")
+                    .arg("lavfi")
+                    .arg("-i")
+                    .arg(format!(
+                        "testsrc=size={width}x{height}:rate=24:duration={}",
+                        ctx.seconds
+                    ))
+                    .arg("-f")
+                    .arg("lavfi")
+                    .arg("-i")
+                    .arg(format!("sine=frequency=440:duration={}", ctx.seconds))
+                    .arg("-pix_fmt")
+                    .arg("yuv420p")
+                    .arg("-shortest")
+                    .arg(ctx.output_path)
+                    .run_media(false)
+                    .await?;
+                if !status.success() {
+                    return Err(SoraError::FfmpegFailed(format!(
+                        "ffmpeg exited with status {status}"
+                    )));
+                }
+            }
         }
-        file.flush().await?;
-        Ok(())
+
+        Ok(self.record(ctx.output_path, ctx.model, ctx.seconds, ctx.size))
     }
 
-    async fn handle_response(response: reqwest::Response) -> Result<VideoJob, SoraError> {
-        let status = response.status();
-        if status == StatusCode::NO_CONTENT {
-            return Err(SoraError::InvalidConfig(
-                "empty response from server".to_string(),
-            ));
+    /// Serve an earlier render as a new one (used for remixes and resumes).
+    async fn copy_render(
+        &self,
+        remote_id: &str,
+        output_path: &Path,
+        seconds: u32,
+        size: &str,
+    ) -> Result<RenderOutcome, SoraError> {
+        let source = self.rendered(remote_id)?;
+        // A job collected into its own output path is already in place.
+        if source != output_path {
+            fs::copy(source, output_path).await?;
         }
+        Ok(self.record(output_path, &self.defaults.model, seconds, size))
+    }
 
-        if !status.is_success() {
-            let text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "<no body>".to_string());
-            return Err(SoraError::JobFailed(format!(
-                "API error ({status}): {text}"
-            )));
+    async fn download(
+        &self,
+        remote_id: &str,
+        variant: VideoVariant,
+        output_path: &Path,
+    ) -> Result<(), SoraError> {
+        match variant {
+            VideoVariant::Video => {
+                fs::copy(self.rendered(remote_id)?, output_path).await?;
+            }
+            _ => {
+                self.rendered(remote_id)?;
+                fs::write(output_path, MOCK_IMAGE).await?;
+            }
         }
+        Ok(())
+    }
 
-        let job = response.json::<VideoJob>().await?;
-        Ok(job)
+    fn record(&self, output_path: &Path, model: &str, seconds: u32, size: &str) -> RenderOutcome {
+        let mut renders = self.renders.lock().unwrap_or_else(|err| err.into_inner());
+        let remote_id = format!("mock_{}", renders.len() + 1);
+        renders.insert(remote_id.clone(), output_path.to_path_buf());
+        RenderOutcome {
+            remote_id,
+            model: model.to_string(),
+            seconds,
+            size: size.to_string(),
+            created_at: Some(unix_timestamp()),
+            created_at_source: ValueSource::Provider,
+            seconds_source: ValueSource::Provider,
+            samples: Vec::new(),
+            storage_uris: Vec::new(),
+            inline_bytes: None,
+        }
     }
-}
 
-#[derive(Debug, Clone)]
-struct VeoClient {
-    http: reqwest::Client,
-    project: String,
-    location: String,
-    token_source: VeoTokenSource,
+    fn rendered(&self, remote_id: &str) -> Result<PathBuf, SoraError> {
+        self.renders
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(remote_id)
+            .cloned()
+            .ok_or_else(|| SoraError::JobFailed {
+                backend: ProviderKind::Mock,
+                remote_id: Some(remote_id.to_string()),
+                message: "unknown mock job".to_string(),
+                code: None,
+                param: None,
+                status: None,
+            })
+    }
 }
 
-impl VeoClient {
-    fn new(
-        project: String,
-        location: String,
-        token_source: VeoTokenSource,
-    ) -> Result<Self, SoraError> {
-        let http = reqwest::Client::builder().build()?;
-        Ok(Self {
-            http,
-            project,
-            location,
-            token_source,
-        })
-    }
+/// Default for [`FlowRequest::beat_name_template`].
+pub const DEFAULT_BEAT_NAME_TEMPLATE: &str = "{base}-{index:02}";
 
-    async fn submit_job(
-        &self,
-        model_id: &str,
-        payload: VeoPredictRequest<'_>,
-    ) -> Result<String, SoraError> {
-        let token = self.token_source.access_token().await?;
-        let url = format!(
-            "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predictLongRunning",
-            self.location, self.project, self.location, model_id
-        );
-        let response = self
-            .http
-            .post(&url)
-            .bearer_auth(&token)
-            .json(&payload)
-            .send()
-            .await?;
+/// Beats a graph flow renders at once unless [`FlowRequest::concurrency`] says otherwise.
+pub const DEFAULT_FLOW_CONCURRENCY: usize = 4;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "<no body>".to_string());
-            return Err(SoraError::JobFailed(format!(
-                "Veo predictLongRunning failed ({status}): {body}"
-            )));
-        }
+/// Clips a batch renders at once unless told otherwise.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
 
-        let envelope: VeoOperationName = response.json().await?;
-        Ok(envelope.name)
+/// Upper bound on the random delay before a graph flow beat that starts alongside others.
+const FLOW_START_JITTER_MS: u64 = 250;
+
+/// Expand a beat name template for the one-based beat `number`. `{base}` is the flow id,
+/// `{index}` the beat number, and `{index:03}` the number zero-padded to three digits.
+fn render_beat_name(template: &str, base: &str, number: usize) -> Result<String, SoraError> {
+    let invalid = |reason: String| {
+        SoraError::InvalidConfig(format!("invalid beat name template '{template}': {reason}"))
+    };
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| invalid("unclosed '{'".to_string()))?;
+        let token = &rest[start + 1..start + end];
+        match token.split_once(':') {
+            None if token == "base" => name.push_str(base),
+            None if token == "index" => name.push_str(&number.to_string()),
+            Some(("index", width)) => {
+                let width: usize = width
+                    .trim_start_matches('0')
+                    .parse()
+                    .map_err(|_| invalid(format!("bad width in {{{token}}}")))?;
+                name.push_str(&format!("{number:0width$}"));
+            }
+            _ => return Err(invalid(format!("unknown token {{{token}}}"))),
+        }
+        rest = &rest[start + end + 1..];
     }
+    name.push_str(rest);
+    Ok(name)
+}
 
-    async fn poll_operation(
-        &self,
-        model_id: &str,
-        operation_name: &str,
-        poll_interval: Duration,
-    ) -> Result<VeoOperationResponse, SoraError> {
-        loop {
-            let token = self.token_source.access_token().await?;
-            let url = format!(
-                "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:fetchPredictOperation",
-                self.location, self.project, self.location, model_id
-            );
-            let body = VeoFetchRequest {
-                operation_name: operation_name.to_string(),
-            };
-            let response = self
-                .http
-                .post(&url)
-                .bearer_auth(&token)
-                .json(&body)
-                .send()
-                .await?;
+/// Local ids for every beat of `request`, rejecting templates that would collide with each
+/// other, the stitched output, or the starting clip.
+fn flow_beat_ids(request: &FlowRequest) -> Result<Vec<String>, SoraError> {
+    let template = request
+        .beat_name_template
+        .as_deref()
+        .unwrap_or(DEFAULT_BEAT_NAME_TEMPLATE);
+    let partial_id = format!("{}-partial", request.id);
+    let mut ids: Vec<String> = Vec::with_capacity(request.beats.len());
+    for number in 1..=request.beats.len() {
+        let id = render_beat_name(template, &request.id, number)?;
+        let collides = ids.contains(&id)
+            || id == request.id
+            || id == partial_id
+            || request.start_from.as_ref() == Some(&id);
+        if id.trim().is_empty() || id.contains(['/', '\\']) || collides {
+            return Err(SoraError::InvalidConfig(format!(
+                "beat name template '{template}' produces unusable id '{id}' for beat {number} \
+                 (ids must be unique, non-empty, free of path separators, and differ from the \
+                 flow id and starting clip)"
+            )));
+        }
+        ids.push(id);
+    }
+    Ok(ids)
+}
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "<no body>".to_string());
-                return Err(SoraError::JobFailed(format!(
-                    "Veo fetchPredictOperation failed ({status}): {text}"
-                )));
-            }
+/// Whether a batch file is JSON Lines (`.jsonl`/`.ndjson`) rather than CSV.
+fn is_jsonl(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("jsonl" | "ndjson")
+    )
+}
 
-            let status: VeoFetchResponse = response.json().await?;
-            if let Some(error) = status.error {
-                let message = error.message.unwrap_or_else(|| "unknown error".to_string());
-                return Err(SoraError::JobFailed(message));
-            }
-            if status.done.unwrap_or(false) {
-                if let Some(response) = status.response {
-                    return Ok(response);
-                }
-                return Err(SoraError::InvalidResponse(
-                    "operation completed without response payload".to_string(),
-                ));
+/// Parse the jobs in a batch file: CSV with a header row naming the [`BatchJob`] columns, or
+/// one JSON object per line. Ids must be unique. Each job comes with the line it starts on.
+fn parse_batch(path: &Path, contents: &str) -> Result<Vec<(usize, BatchJob)>, SoraError> {
+    let invalid = |line: usize, error: &dyn std::fmt::Display| {
+        SoraError::InvalidConfig(format!("{}:{line}: {error}", path.display()))
+    };
+    let mut jobs = Vec::new();
+    if is_jsonl(path) {
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
             }
+            let job: BatchJob =
+                serde_json::from_str(line).map_err(|error| invalid(index + 1, &error))?;
+            jobs.push((index + 1, job));
+        }
+    } else {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(contents.as_bytes());
+        let error_line = |error: &csv::Error| {
+            error
+                .position()
+                .map_or(0, |position| position.line() as usize)
+        };
+        let headers = reader
+            .headers()
+            .map_err(|error| invalid(error_line(&error), &error))?
+            .clone();
+        // Quoted fields can span lines, so take each row's line from the reader.
+        for record in reader.records() {
+            let record = record.map_err(|error| invalid(error_line(&error), &error))?;
+            let line = record
+                .position()
+                .map_or(0, |position| position.line() as usize);
+            let job = record
+                .deserialize(Some(&headers))
+                .map_err(|error| invalid(line, &error))?;
+            jobs.push((line, job));
+        }
+    }
 
-            sleep(poll_interval).await;
+    let mut seen = std::collections::HashSet::new();
+    for (line, job) in &jobs {
+        if job.id.trim().is_empty() {
+            return Err(invalid(*line, &"missing id"));
+        }
+        if !seen.insert(job.id.as_str()) {
+            return Err(invalid(*line, &format!("duplicate id '{}'", job.id)));
         }
     }
+    Ok(jobs)
 }
 
-#[derive(Debug, Clone)]
-enum VeoTokenSource {
-    Static(String),
-    Gcloud,
+/// Serialize batch results in the batch file's format.
+fn format_batch_results(jsonl: bool, results: &[BatchResult]) -> Result<Vec<u8>, SoraError> {
+    if jsonl {
+        let mut out = Vec::new();
+        for result in results {
+            serde_json::to_writer(&mut out, result)?;
+            out.push(b'\n');
+        }
+        return Ok(out);
+    }
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for result in results {
+        writer.serialize(result).map_err(std::io::Error::from)?;
+    }
+    writer
+        .into_inner()
+        .map_err(|error| SoraError::Io(error.into_error()))
 }
 
-impl VeoTokenSource {
-    async fn access_token(&self) -> Result<String, SoraError> {
-        match self {
-            VeoTokenSource::Static(token) => Ok(token.clone()),
-            VeoTokenSource::Gcloud => {
-                let output = Command::new("gcloud")
-                    .arg("auth")
-                    .arg("print-access-token")
-                    .output()
-                    .await
-                    .map_err(|err| {
-                        if err.kind() == std::io::ErrorKind::NotFound {
-                            SoraError::MissingGcpToken
-                        } else {
-                            SoraError::GcpAuth(err.to_string())
-                        }
-                    })?;
-                if !output.status.success() {
-                    return Err(SoraError::GcpAuth(format!(
-                        "gcloud exited with status {}",
-                        output.status
+/// For each beat of a graph flow, the index of the beat it continues (`None` when it continues
+/// a saved clip or nothing), rejecting dependency cycles.
+fn flow_graph_parents(
+    request: &FlowRequest,
+    beat_ids: &[String],
+) -> Result<Vec<Option<usize>>, SoraError> {
+    let parents: Vec<Option<usize>> = request
+        .beats
+        .iter()
+        .map(|beat| {
+            let depends_on = beat.depends_on.as_ref()?;
+            beat_ids.iter().position(|id| id == depends_on)
+        })
+        .collect();
+    for (start, id) in beat_ids.iter().enumerate() {
+        let mut current = parents[start];
+        for _ in 0..beat_ids.len() {
+            match current {
+                Some(parent) if parent == start => {
+                    return Err(SoraError::InvalidConfig(format!(
+                        "beat '{id}' depends on itself through a cycle"
                     )));
                 }
-                let token = String::from_utf8(output.stdout)
-                    .map_err(|err| SoraError::GcpAuth(err.to_string()))?
-                    .trim()
-                    .to_string();
-                if token.is_empty() {
-                    return Err(SoraError::MissingGcpToken);
-                }
-                Ok(token)
+                Some(parent) => current = parents[parent],
+                None => break,
             }
         }
     }
+    Ok(parents)
 }
 
-#[derive(Serialize)]
-struct VeoImage {
-    #[serde(rename = "bytesBase64Encoded", skip_serializing_if = "Option::is_none")]
-    bytes_base64_encoded: Option<String>,
-    #[serde(rename = "gcsUri", skip_serializing_if = "Option::is_none")]
-    gcs_uri: Option<String>,
-    #[serde(rename = "mimeType")]
-    mime_type: String,
+/// Decode the inline videos in a Veo response, in order, and collect the Cloud Storage URIs of
+/// any returned by reference instead.
+/// Inline payloads longer than `warn_bytes` are logged, since each is held in memory twice
+/// (encoded and decoded) while it is written out.
+fn split_veo_videos(
+    videos: &[VeoGeneratedVideo],
+    warn_bytes: u64,
+) -> Result<(Vec<Vec<u8>>, Vec<String>), SoraError> {
+    let mut inline = Vec::new();
+    let mut storage_uris = Vec::new();
+    for video in videos {
+        if let Some(bytes) = &video.bytes_base64_encoded {
+            if bytes.len() as u64 > warn_bytes {
+                warn!(
+                    encoded_bytes = bytes.len(),
+                    threshold = warn_bytes,
+                    "Veo returned a large inline video; set gcp_storage_uri to have Veo write \
+                     renders to Cloud Storage instead of the response"
+                );
+            }
+            let data = BASE64_STANDARD.decode(bytes).map_err(|err| {
+                SoraError::InvalidResponse(format!("invalid base64 video payload: {err}"))
+            })?;
+            inline.push(data);
+        } else if let Some(uri) = &video.gcs_uri {
+            storage_uris.push(uri.clone());
+        }
+    }
+    Ok((inline, storage_uris))
 }
 
-#[derive(Serialize)]
-struct VeoInstance<'a> {
-    prompt: &'a str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    image: Option<VeoImage>,
+/// Split a Cloud Storage location into its bucket and object name. Accepts `gs://bucket/object`
+/// (as Veo reports them) and `https://storage.googleapis.com/bucket/object`, whose
+/// percent-encoded parts are decoded and whose query string (e.g. a signature) is ignored.
+/// Locations naming a bucket or folder rather than an object are rejected.
+pub fn parse_gcs_uri(uri: &str) -> Result<(String, String), SoraError> {
+    let invalid = |reason: &str| {
+        SoraError::InvalidConfig(format!(
+            "'{uri}' is not a Cloud Storage object ({reason}; expected gs://bucket/object or \
+             https://storage.googleapis.com/bucket/object)"
+        ))
+    };
+    let trimmed = uri.trim();
+    let (path, encoded) = if let Some(rest) = trimmed.strip_prefix("gs://") {
+        (rest, false)
+    } else if let Some(rest) = trimmed.strip_prefix("https://storage.googleapis.com/") {
+        let end = rest.find(['?', '#']).unwrap_or(rest.len());
+        (&rest[..end], true)
+    } else {
+        return Err(invalid("unrecognised scheme"));
+    };
+
+    let (bucket, object) = path.split_once('/').unwrap_or((path, ""));
+    let decode = |part: &str| {
+        if encoded {
+            percent_decode(part).ok_or_else(|| invalid("bad percent-encoding"))
+        } else {
+            Ok(part.to_string())
+        }
+    };
+    let bucket = decode(bucket)?;
+    let object = decode(object)?;
+    let bucket_ok = !bucket.is_empty()
+        && bucket
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c));
+    if !bucket_ok {
+        return Err(invalid("missing or invalid bucket"));
+    }
+    if object.is_empty() || object.ends_with('/') {
+        return Err(invalid("no object name"));
+    }
+    Ok((bucket, object))
 }
 
-#[derive(Serialize)]
-struct VeoParameters<'a> {
-    #[serde(rename = "durationSeconds")]
-    duration_seconds: u32,
-    #[serde(rename = "generateAudio")]
-    generate_audio: bool,
-    #[serde(rename = "storageUri", skip_serializing_if = "Option::is_none")]
-    storage_uri: Option<&'a str>,
-    #[serde(rename = "resolution", skip_serializing_if = "Option::is_none")]
-    resolution: Option<&'a str>,
-    #[serde(rename = "aspectRatio", skip_serializing_if = "Option::is_none")]
-    aspect_ratio: Option<&'a str>,
-    #[serde(rename = "enhancePrompt")]
-    enhance_prompt: bool,
-    #[serde(rename = "sampleCount", skip_serializing_if = "Option::is_none")]
-    sample_count: Option<u32>,
+/// Escape everything but unreserved characters as `%XX`, for a single URL path segment.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
 }
 
-#[derive(Serialize)]
-struct VeoPredictRequest<'a> {
-    instances: Vec<VeoInstance<'a>>,
-    parameters: VeoParameters<'a>,
+/// Decode `%XX` escapes; `None` for a malformed escape or bytes that aren't UTF-8.
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2)?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
 }
 
-#[derive(Deserialize)]
-struct VeoOperationName {
-    name: String,
+/// Like [`veo_model_from_operation`], but explaining what was expected when it isn't one.
+fn veo_operation_model(remote_id: &str) -> Result<&str, SoraError> {
+    veo_model_from_operation(remote_id).ok_or_else(|| {
+        SoraError::InvalidConfig(format!(
+            "'{remote_id}' is not a Veo operation name \
+             (expected projects/.../models/<model>/operations/<id>)"
+        ))
+    })
 }
 
-#[derive(Serialize)]
-struct VeoFetchRequest {
-    #[serde(rename = "operationName")]
-    operation_name: String,
+/// Replace `${VAR}` with the variable's value, or with `default` in `${VAR:-default}` when the
+/// variable is unset or empty. Undefined variables without a default are an error. Variables
+/// are read through `lookup`.
+fn expand_env_vars(
+    value: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, SoraError> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            SoraError::InvalidConfig(format!("unclosed '${{' in config value '{value}'"))
+        })?;
+        let reference = &rest[start + 2..start + end];
+        let (name, default) = match reference.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (reference, None),
+        };
+        match (lookup(name).filter(|v| !v.is_empty()), default) {
+            (Some(found), _) => expanded.push_str(&found),
+            (None, Some(default)) => expanded.push_str(default),
+            (None, None) => {
+                return Err(SoraError::InvalidConfig(format!(
+                    "environment variable {name} referenced in '{value}' is not set"
+                )));
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct VeoFetchResponse {
-    name: String,
-    done: Option<bool>,
-    response: Option<VeoOperationResponse>,
-    error: Option<VeoOperationError>,
+/// Runs ffmpeg and ffprobe.
+trait MediaCommand {
+    /// Run an ffmpeg command that writes media to completion, or with `print` only print it to
+    /// stderr and report success ([`ContinuatorConfig::print_ffmpeg`]). A missing binary is
+    /// [`SoraError::FfmpegMissing`].
+    async fn run_media(&mut self, print: bool) -> Result<ExitStatus, SoraError>;
+    /// Run a read-only command such as ffprobe and capture stdout and stderr. These always
+    /// run, so printed commands are built from the real inputs.
+    async fn output_media(&mut self) -> Result<Output, SoraError>;
 }
 
-#[derive(Debug, Deserialize)]
-struct VeoOperationError {
-    message: Option<String>,
+impl MediaCommand for Command {
+    async fn run_media(&mut self, print: bool) -> Result<ExitStatus, SoraError> {
+        if print {
+            eprintln!("{}", command_line(self));
+            return Ok(ExitStatus::default());
+        }
+        // Killed if the caller gives up (a timeout drops this future), not left running.
+        self.kill_on_drop(true)
+            .status()
+            .await
+            .map_err(|_| SoraError::FfmpegMissing)
+    }
+
+    async fn output_media(&mut self) -> Result<Output, SoraError> {
+        self.kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|_| SoraError::FfmpegMissing)
+    }
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct VeoOperationResponse {
-    #[serde(rename = "@type")]
-    type_url: Option<String>,
-    #[serde(default)]
-    videos: Vec<VeoGeneratedVideo>,
+/// A command as a shell line that can be pasted back into a terminal.
+fn command_line(command: &Command) -> String {
+    let command = command.as_std();
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            let plain = !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./:=+,@%".contains(c));
+            if plain {
+                arg.into_owned()
+            } else {
+                shell_quote(&arg)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
-struct VeoGeneratedVideo {
-    #[serde(rename = "gcsUri")]
-    gcs_uri: Option<String>,
-    #[serde(rename = "bytesBase64Encoded")]
-    bytes_base64_encoded: Option<String>,
-    #[serde(rename = "mimeType")]
-    mime_type: Option<String>,
+/// Expand the `{file}` and `{id}` shorthands of an upload command into references to its
+/// positional arguments, in a single pass over the template.
+fn upload_script(template: &str) -> String {
+    let mut script = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        script.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("{file}") {
+            script.push_str("\"$1\"");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{id}") {
+            script.push_str("\"$2\"");
+            rest = after;
+        } else {
+            script.push('{');
+            rest = &rest[1..];
+        }
+    }
+    script.push_str(rest);
+    script
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Quote a value for safe interpolation into a `sh -c` script.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
 
-    #[test]
-    fn status_deserialization() {
-        let json = "\"completed\"";
-        let status: VideoStatus = serde_json::from_str(json).unwrap();
-        assert!(matches!(status, VideoStatus::Completed));
+/// Extract the model id from a Veo long-running operation name.
+fn veo_model_from_operation(operation: &str) -> Option<&str> {
+    let (_, rest) = operation.split_once("/models/")?;
+    let (model, _) = rest.split_once("/operations/")?;
+    (!model.is_empty()).then_some(model)
+}
+
+/// Aspect ratios Veo renders.
+const VEO_ASPECT_RATIOS: &[&str] = &["16:9", "9:16"];
+
+/// Resolution and aspect ratio pairs each Veo model accepts; models not listed accept every
+/// pair in [`VEO_FORMATS`].
+const VEO_MODEL_FORMATS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "veo-2.0-generate-001",
+        &[("720p", "16:9"), ("720p", "9:16")],
+    ),
+    (
+        DEFAULT_VEO_MODEL,
+        &[("720p", "16:9"), ("720p", "9:16"), ("1080p", "16:9")],
+    ),
+];
+
+const VEO_FORMATS: &[(&str, &str)] = &[
+    ("720p", "16:9"),
+    ("720p", "9:16"),
+    ("1080p", "16:9"),
+    ("1080p", "9:16"),
+];
+
+/// Veo's resolution and aspect ratio for a render: the configured values where set, otherwise
+/// derived from the `WIDTHxHEIGHT` size (orientation picks the aspect ratio, the short side
+/// the resolution).
+fn veo_format(
+    resolution: Option<&str>,
+    aspect_ratio: Option<&str>,
+    size: &str,
+) -> Result<(String, String), SoraError> {
+    let (width, height) = parse_dimensions(size)?;
+    let resolution = resolution.unwrap_or(if width.min(height) >= 1080 {
+        "1080p"
+    } else {
+        "720p"
+    });
+    let aspect_ratio = aspect_ratio.unwrap_or(if height > width { "9:16" } else { "16:9" });
+    Ok((resolution.to_string(), aspect_ratio.to_string()))
+}
+
+/// Reject a resolution and aspect ratio pair `model` can't render.
+fn check_veo_format(model: &str, resolution: &str, aspect_ratio: &str) -> Result<(), SoraError> {
+    let allowed = VEO_MODEL_FORMATS
+        .iter()
+        .find(|(id, _)| *id == model)
+        .map_or(VEO_FORMATS, |(_, formats)| *formats);
+    if allowed.contains(&(resolution, aspect_ratio)) {
+        return Ok(());
+    }
+    let allowed: Vec<String> = allowed
+        .iter()
+        .map(|(resolution, aspect_ratio)| format!("{resolution} {aspect_ratio}"))
+        .collect();
+    Err(SoraError::InvalidConfig(format!(
+        "{model} does not support {resolution} at {aspect_ratio} (expected one of {})",
+        allowed.join(", ")
+    )))
+}
+
+/// Frame size Veo produces for a resolution and aspect ratio.
+fn veo_output_size(resolution: &str, aspect_ratio: &str) -> String {
+    let (long, short) = if resolution == "1080p" {
+        (1920, 1080)
+    } else {
+        (1280, 720)
+    };
+    if aspect_ratio == "9:16" {
+        format!("{short}x{long}")
+    } else {
+        format!("{long}x{short}")
+    }
+}
+
+/// Stream and container details reported by `ffprobe`.
+#[derive(Debug, Clone, Default)]
+struct MediaInfo {
+    duration_seconds: Option<f64>,
+    width: Option<u32>,
+    height: Option<u32>,
+    video_codec: Option<String>,
+    audio_codec: Option<String>,
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+    /// Video frame rate as ffprobe reports it, e.g. `24/1` or `30000/1001`.
+    frame_rate: Option<String>,
+}
+
+impl MediaInfo {
+    fn has_audio(&self) -> bool {
+        self.audio_codec.is_some()
+    }
+
+    /// Whether two files can be joined by the concat demuxer without re-encoding.
+    fn concat_compatible(&self, other: &MediaInfo) -> bool {
+        self.video_codec == other.video_codec
+            && self.width == other.width
+            && self.height == other.height
+            && self.audio_codec == other.audio_codec
+            && self.sample_rate == other.sample_rate
+            && self.channels == other.channels
+    }
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    /// ffprobe reports this as a string, e.g. `"48000"`.
+    sample_rate: Option<String>,
+    channels: Option<u32>,
+    r_frame_rate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+}
+
+async fn probe_media(path: &Path) -> Result<MediaInfo, SoraError> {
+    let output = Command::new("ffprobe")
+        .arg("-v")
+        .arg("error")
+        .arg("-print_format")
+        .arg("json")
+        .arg("-show_format")
+        .arg("-show_streams")
+        .arg(path)
+        .output_media()
+        .await?;
+
+    if !output.status.success() {
+        return Err(SoraError::FfprobeFailed(format!(
+            "ffprobe exited with status {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let parsed: FfprobeOutput = serde_json::from_slice(&output.stdout)?;
+    let mut info = MediaInfo {
+        duration_seconds: parsed
+            .format
+            .and_then(|format| format.duration)
+            .and_then(|duration| duration.parse().ok()),
+        ..MediaInfo::default()
+    };
+    for stream in parsed.streams {
+        match stream.codec_type.as_deref() {
+            Some("video") if info.video_codec.is_none() => {
+                info.video_codec = stream.codec_name;
+                info.width = stream.width;
+                info.height = stream.height;
+                info.frame_rate = stream.r_frame_rate.filter(|rate| {
+                    !rate.starts_with('0') && rate.chars().all(|c| c.is_ascii_digit() || c == '/')
+                });
+            }
+            Some("audio") if info.audio_codec.is_none() => {
+                info.audio_codec = stream.codec_name;
+                info.sample_rate = stream.sample_rate.and_then(|rate| rate.parse().ok());
+                info.channels = stream.channels;
+            }
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+/// Check that ffmpeg produced a file ffprobe can read, with a video stream and a duration.
+async fn verify_playable(path: &Path) -> Result<(), SoraError> {
+    let info = probe_media(path).await.map_err(|err| {
+        SoraError::FfmpegConcatFailed(format!("stitched output failed verification: {err}"))
+    })?;
+    if info.video_codec.is_none() || !info.duration_seconds.is_some_and(|seconds| seconds > 0.0) {
+        return Err(SoraError::FfmpegConcatFailed(
+            "stitched output failed verification: no video stream or zero duration".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Escape a path for use inside a single-quoted ffmpeg filter option. The quotes protect
+/// commas, brackets, and semicolons from the graph parser; backslashes, colons, and quotes
+/// still need escaping for the option parser beneath it.
+fn escape_filter_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "\\\\")
+        .replace('\'', "'\\\\\\''")
+        .replace(':', "\\:")
+}
+
+/// Pick a font file for drawtext, falling back to well-known system fonts.
+fn resolve_font(requested: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = requested {
+        if path.exists() {
+            return Some(path.to_path_buf());
+        }
+        warn!(font = %path.display(), "font file not found; falling back to a system font");
+    }
+    let fallback = FALLBACK_FONT_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.exists());
+    if fallback.is_none() {
+        debug!("no known font file found; relying on ffmpeg's default font");
+    }
+    fallback
+}
+
+/// Rescale an image in place with ffmpeg.
+async fn resize_image(path: &Path, width: u32, height: u32, print: bool) -> Result<(), SoraError> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let staging_path = path.with_file_name(format!(".resize-{file_name}"));
+    let status = Command::new("ffmpeg")
+        .arg("-v")
+        .arg("error")
+        .arg("-y")
+        .arg("-i")
+        .arg(path)
+        .arg("-vf")
+        .arg(format!("scale={width}:{height}"))
+        .arg(&staging_path)
+        .run_media(print)
+        .await?;
+
+    if !status.success() {
+        let _ = fs::remove_file(&staging_path).await;
+        return Err(SoraError::FfmpegFailed(format!(
+            "ffmpeg exited with status {status}"
+        )));
+    }
+
+    fs::rename(&staging_path, path).await?;
+    Ok(())
+}
+
+/// Hex-encoded SHA-256 of a file, streamed in chunks.
+async fn sha256_file(path: &Path) -> Result<String, SoraError> {
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Encoder knobs for re-encoding operations. Unset fields leave ffmpeg's defaults in place.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeSettings {
+    /// Video codec (e.g., `libx264`, `libx265`).
+    pub vcodec: Option<String>,
+    /// Constant rate factor; lower is higher quality.
+    pub crf: Option<u8>,
+    /// Encoder speed/efficiency preset (e.g., `slow`).
+    pub preset: Option<String>,
+    /// Target video bitrate (e.g., `8M` or `2500k`).
+    pub bitrate: Option<String>,
+    /// Output pixel format (e.g., `yuv420p`).
+    pub pix_fmt: Option<String>,
+    /// Audio codec (e.g., `aac`).
+    pub acodec: Option<String>,
+    /// Target audio bitrate (e.g., `192k`).
+    pub abitrate: Option<String>,
+}
+
+const X264_PRESETS: &[&str] = &[
+    "ultrafast",
+    "superfast",
+    "veryfast",
+    "faster",
+    "fast",
+    "medium",
+    "slow",
+    "slower",
+    "veryslow",
+    "placebo",
+];
+
+impl EncodeSettings {
+    /// Whether any video setting is present, i.e. video has to be re-encoded to honour it.
+    pub fn touches_video(&self) -> bool {
+        self.vcodec.is_some()
+            || self.crf.is_some()
+            || self.preset.is_some()
+            || self.bitrate.is_some()
+            || self.pix_fmt.is_some()
+    }
+
+    /// Fill unset fields from `defaults`.
+    pub fn or(&self, defaults: &EncodeSettings) -> EncodeSettings {
+        EncodeSettings {
+            vcodec: self.vcodec.clone().or_else(|| defaults.vcodec.clone()),
+            crf: self.crf.or(defaults.crf),
+            preset: self.preset.clone().or_else(|| defaults.preset.clone()),
+            bitrate: self.bitrate.clone().or_else(|| defaults.bitrate.clone()),
+            pix_fmt: self.pix_fmt.clone().or_else(|| defaults.pix_fmt.clone()),
+            acodec: self.acodec.clone().or_else(|| defaults.acodec.clone()),
+            abitrate: self.abitrate.clone().or_else(|| defaults.abitrate.clone()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), SoraError> {
+        let invalid = |message: String| Err(SoraError::InvalidConfig(message));
+        if let Some(crf) = self.crf
+            && crf > 63
+        {
+            return invalid(format!("crf must be between 0 and 63 (got {crf})"));
+        }
+        if self.crf.is_some() && self.bitrate.is_some() {
+            return invalid("crf and bitrate are mutually exclusive".to_string());
+        }
+        let x264_family = matches!(self.vcodec.as_deref(), None | Some("libx264" | "libx265"));
+        if let Some(preset) = &self.preset
+            && x264_family
+            && !X264_PRESETS.contains(&preset.as_str())
+        {
+            return invalid(format!(
+                "unknown preset '{preset}' (expected one of {})",
+                X264_PRESETS.join(", ")
+            ));
+        }
+        for (name, value) in [("bitrate", &self.bitrate), ("abitrate", &self.abitrate)] {
+            if let Some(value) = value
+                && !is_bitrate(value)
+            {
+                return invalid(format!("{name} must look like 2500k or 8M (got '{value}')"));
+            }
+        }
+        for (name, value) in [
+            ("vcodec", &self.vcodec),
+            ("pix_fmt", &self.pix_fmt),
+            ("acodec", &self.acodec),
+        ] {
+            if value
+                .as_deref()
+                .is_some_and(|value| value.trim().is_empty())
+            {
+                return invalid(format!("{name} must not be empty"));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_video(&self, command: &mut Command) {
+        if let Some(vcodec) = &self.vcodec {
+            command.arg("-c:v").arg(vcodec);
+        }
+        if let Some(crf) = self.crf {
+            command.arg("-crf").arg(crf.to_string());
+        }
+        if let Some(preset) = &self.preset {
+            command.arg("-preset").arg(preset);
+        }
+        if let Some(bitrate) = &self.bitrate {
+            command.arg("-b:v").arg(bitrate);
+        }
+        if let Some(pix_fmt) = &self.pix_fmt {
+            command.arg("-pix_fmt").arg(pix_fmt);
+        }
+    }
+
+    /// Add audio flags, using `default_codec` when no codec is set. A bitrate cannot apply to a
+    /// stream copy, so setting one alone switches a `copy` default to AAC.
+    fn apply_audio(&self, command: &mut Command, default_codec: &str) {
+        let codec = match (&self.acodec, &self.abitrate) {
+            (Some(codec), _) => codec.as_str(),
+            (None, Some(_)) if default_codec == "copy" => "aac",
+            (None, _) => default_codec,
+        };
+        command.arg("-c:a").arg(codec);
+        if let Some(abitrate) = &self.abitrate {
+            command.arg("-b:a").arg(abitrate);
+        }
+    }
+}
+
+/// Settings for a chunked stitch's first pass: the video settings only, so chunk audio is
+/// copied, or rebuilt losslessly, rather than encoded twice.
+fn chunk_pass_encode(encode: &EncodeSettings) -> EncodeSettings {
+    EncodeSettings {
+        acodec: None,
+        abitrate: None,
+        ..encode.clone()
+    }
+}
+
+/// Settings for a chunked stitch's second pass: the audio settings only, since video was
+/// handled in the first. Lossless chunk audio has to be encoded even when it would otherwise
+/// be copied.
+fn join_pass_encode(encode: &EncodeSettings, rebuilt: bool) -> EncodeSettings {
+    let acodec = match &encode.acodec {
+        None if rebuilt => Some("aac".to_string()),
+        acodec => acodec.clone(),
+    };
+    EncodeSettings {
+        acodec,
+        abitrate: encode.abitrate.clone(),
+        ..EncodeSettings::default()
+    }
+}
+
+fn is_bitrate(value: &str) -> bool {
+    let digits = value.strip_suffix(['k', 'K', 'm', 'M']).unwrap_or(value);
+    !digits.is_empty() && digits.parse::<f64>().is_ok_and(|rate| rate > 0.0)
+}
+
+/// Options for [`VideoManager::download_asset_with`].
+#[derive(Debug, Clone, Default)]
+pub struct DownloadOptions {
+    /// Continue an existing partial file with a range request instead of starting over.
+    pub resume: bool,
+}
+
+/// Options for [`VideoManager::stitch_videos_with`].
+#[derive(Debug, Clone, Default)]
+pub struct StitchOptions {
+    /// Encoder settings for this stitch, layered over the configured defaults. When any video
+    /// setting ends up present the clips are re-encoded instead of stream-copied.
+    pub encode: EncodeSettings,
+    /// Overrides [`ContinuatorConfig::audio_sample_rate`] for this stitch.
+    pub audio_sample_rate: Option<u32>,
+    /// Overrides [`ContinuatorConfig::audio_channels`] for this stitch.
+    pub audio_channels: Option<u32>,
+    /// Replace an existing clip with the output id instead of refusing.
+    pub overwrite: bool,
+    /// Overrides [`ContinuatorConfig::stitch_chunk_size`] for this stitch.
+    pub chunk_size: Option<usize>,
+    /// Probe the joined file with ffprobe before it replaces the output, rejecting one without
+    /// a video stream or a duration.
+    pub verify: bool,
+    /// Put a solid-colour pause between clips. The whole stitch is then re-encoded in one pass,
+    /// so it is refused with a chunk size smaller than the clip count, an audio crossfade, or
+    /// clips carrying their parent's audio.
+    pub gap: Option<GapSpec>,
+}
+
+/// A pause inserted between stitched clips: a frame of solid colour with silent audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapSpec {
+    pub seconds: f32,
+    /// Any colour ffmpeg knows by name (`black`, `white`) or as hex (`#202020`).
+    pub color: String,
+}
+
+impl Default for GapSpec {
+    fn default() -> Self {
+        Self {
+            seconds: 1.0,
+            color: "black".to_string(),
+        }
+    }
+}
+
+impl GapSpec {
+    fn validate(&self) -> Result<(), SoraError> {
+        if !(self.seconds.is_finite() && self.seconds > 0.0) {
+            return Err(SoraError::InvalidConfig(format!(
+                "gap must be a positive number of seconds (got {})",
+                self.seconds
+            )));
+        }
+        // The colour goes straight into the filtergraph, so keep it to names and hex values.
+        let plain = |c: char| c.is_ascii_alphanumeric() || matches!(c, '#' | '@' | '.' | '_');
+        if self.color.is_empty() || !self.color.chars().all(plain) {
+            return Err(SoraError::InvalidConfig(format!(
+                "gap colour must be a name or hex value like #202020 (got '{}')",
+                self.color
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A gap stitch is a single re-encode with silent pauses, so it can't also be joined in chunks
+/// or crossfade audio across the joins. Refuse those combinations instead of dropping them.
+fn check_gap_stitch(
+    shaping: &AudioShaping,
+    chunk_size: Option<usize>,
+    inputs: usize,
+) -> Result<(), SoraError> {
+    if let Some(size) = chunk_size.filter(|size| inputs > *size) {
+        return Err(SoraError::InvalidConfig(format!(
+            "a stitch with gaps is joined in one pass, but {inputs} clips exceed the chunk size \
+             of {size}; raise the chunk size or drop the gap"
+        )));
+    }
+    if shaping.fade.is_some() {
+        return Err(SoraError::InvalidConfig(
+            "a stitch with gaps can't crossfade audio across its joins; unset the audio \
+             crossfade or drop the gap"
+                .to_string(),
+        ));
+    }
+    if shaping.carried.iter().any(Option::is_some) {
+        return Err(SoraError::InvalidConfig(
+            "these clips carry their parent's audio into their openings, which a gap would \
+             cut off; stitch them without a gap"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Chunks of one clip would never shrink the final join.
+fn validate_chunk_size(chunk_size: Option<usize>) -> Result<(), SoraError> {
+    if chunk_size.is_some_and(|size| size < 2) {
+        return Err(SoraError::InvalidConfig(
+            "stitch chunk size must be at least 2".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// How a stitch rebuilds its audio track.
+struct AudioShaping {
+    /// Fade length at each join, if any.
+    fade: Option<Duration>,
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+    /// Per input, parent audio to crossfade into its opening (see
+    /// [`VideoMetadata::carried_audio`]).
+    carried: Vec<Option<PathBuf>>,
+}
+
+/// One `file` line of an ffmpeg concat manifest. Single quotes in the path are closed, escaped,
+/// and reopened (`'\''`), the only escaping the demuxer needs inside a quoted string.
+fn concat_manifest_entry(path: &Path) -> String {
+    format!(
+        "file '{}'\n",
+        path.display().to_string().replace('\'', r"'\''")
+    )
+}
+
+/// Seconds of a parent's audio recorded by [`ContinueVideoRequest::carry_audio`], and the length
+/// of the crossfade it gets when stitched.
+const CARRY_AUDIO_SECONDS: f64 = 1.0;
+
+/// Sample rate and channel count shared by every input of a normalized stitch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AudioFormat {
+    sample_rate: u32,
+    channels: u32,
+}
+
+impl AudioFormat {
+    /// ffmpeg channel layout name for the channel count.
+    fn layout(self) -> String {
+        match self.channels {
+            1 => "mono".to_string(),
+            2 => "stereo".to_string(),
+            6 => "5.1".to_string(),
+            8 => "7.1".to_string(),
+            channels => format!("{channels}c"),
+        }
+    }
+
+    /// `aresample`/`aformat` chain converting one input to this format.
+    fn filter(self) -> String {
+        format!(
+            "aresample={rate},aformat=sample_rates={rate}:channel_layouts={layout}",
+            rate = self.sample_rate,
+            layout = self.layout()
+        )
+    }
+}
+
+fn validate_audio_target(sample_rate: Option<u32>, channels: Option<u32>) -> Result<(), SoraError> {
+    if let Some(rate) = sample_rate
+        && !(8_000..=192_000).contains(&rate)
+    {
+        return Err(SoraError::InvalidConfig(format!(
+            "audio sample rate must be between 8000 and 192000 Hz (got {rate})"
+        )));
+    }
+    if let Some(channels) = channels
+        && !(1..=8).contains(&channels)
+    {
+        return Err(SoraError::InvalidConfig(format!(
+            "audio channels must be between 1 and 8 (got {channels})"
+        )));
+    }
+    Ok(())
+}
+
+/// The format to convert stitched inputs to, or `None` when they already agree with each other
+/// and with any explicit target.
+fn pick_audio_format(
+    infos: &[MediaInfo],
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+) -> Option<AudioFormat> {
+    let target = AudioFormat {
+        sample_rate: sample_rate
+            .or_else(|| infos.iter().filter_map(|info| info.sample_rate).max())?,
+        channels: channels.or_else(|| infos.iter().filter_map(|info| info.channels).max())?,
+    };
+    let uniform = infos.iter().all(|info| {
+        info.sample_rate == Some(target.sample_rate) && info.channels == Some(target.channels)
+    });
+    (!uniform).then_some(target)
+}
+
+/// Join two files with the concat filter, scaling the second to the first's frame size.
+async fn concat_filter(
+    inputs: &[PathBuf; 2],
+    first: &MediaInfo,
+    second: &MediaInfo,
+    encode: &EncodeSettings,
+    output_path: &Path,
+    print: bool,
+) -> Result<(), SoraError> {
+    let (width, height) = match (first.width, first.height) {
+        (Some(width), Some(height)) => (width, height),
+        _ => {
+            return Err(SoraError::FfprobeFailed(
+                "unable to determine frame size for concat".to_string(),
+            ));
+        }
+    };
+    let with_audio = first.has_audio() && second.has_audio();
+    let mut graph = format!(
+        "[0:v]scale={width}:{height},setsar=1[v0];[1:v]scale={width}:{height},setsar=1[v1];"
+    );
+    if with_audio {
+        graph.push_str("[v0][0:a][v1][1:a]concat=n=2:v=1:a=1[v][a]");
+    } else {
+        graph.push_str("[v0][v1]concat=n=2:v=1:a=0[v]");
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-y")
+        .arg("-i")
+        .arg(&inputs[0])
+        .arg("-i")
+        .arg(&inputs[1])
+        .arg("-filter_complex")
+        .arg(graph)
+        .arg("-map")
+        .arg("[v]");
+    encode.apply_video(&mut command);
+    if with_audio {
+        command.arg("-map").arg("[a]");
+        encode.apply_audio(&mut command, "aac");
+    }
+    let status = command.arg(output_path).run_media(print).await?;
+
+    if !status.success() {
+        return Err(SoraError::FfmpegConcatFailed(format!(
+            "ffmpeg exited with status {status}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Join clips with the concat filter, putting a [`GapSpec`] pause between each pair. Every
+/// clip is re-encoded; see [`gap_filtergraph`] for how they are brought together.
+async fn concat_with_gaps(
+    inputs: &[PathBuf],
+    gap: &GapSpec,
+    audio: (Option<u32>, Option<u32>),
+    encode: &EncodeSettings,
+    output_path: &Path,
+    print: bool,
+) -> Result<(), SoraError> {
+    let mut infos = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        infos.push(probe_media(input).await?);
+    }
+    let (graph, with_audio) = gap_filtergraph(&infos, gap, audio)?;
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-v").arg("error").arg("-y");
+    for input in inputs {
+        command.arg("-i").arg(input);
+    }
+    command
+        .arg("-filter_complex")
+        .arg(graph)
+        .arg("-map")
+        .arg("[v]");
+    encode.apply_video(&mut command);
+    if with_audio {
+        command.arg("-map").arg("[a]");
+        encode.apply_audio(&mut command, "aac");
+    }
+    let status = command.arg(output_path).run_media(print).await?;
+
+    if !status.success() {
+        return Err(SoraError::FfmpegConcatFailed(format!(
+            "ffmpeg exited with status {status}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// The filtergraph for [`concat_with_gaps`], and whether it produces an `[a]` audio output.
+///
+/// Every clip is brought to the first one's frame size and rate. When any clip has audio the
+/// output does too, in `audio` or else the first audible clip's format: gaps and clips without
+/// a soundtrack are filled with silence of their length.
+fn gap_filtergraph(
+    infos: &[MediaInfo],
+    gap: &GapSpec,
+    audio: (Option<u32>, Option<u32>),
+) -> Result<(String, bool), SoraError> {
+    let first = &infos[0];
+    let (Some(width), Some(height)) = (first.width, first.height) else {
+        return Err(SoraError::FfprobeFailed(
+            "unable to determine frame size for concat".to_string(),
+        ));
+    };
+    let rate = first.frame_rate.clone().unwrap_or_else(|| "30".to_string());
+    let audible = infos.iter().find(|info| info.has_audio());
+    let audio = audible.map(|audible| AudioFormat {
+        sample_rate: audio.0.or(audible.sample_rate).unwrap_or(48_000),
+        channels: audio.1.or(audible.channels).unwrap_or(2),
+    });
+    let silence = |audio: AudioFormat, seconds: f64, label: &str| {
+        format!(
+            "anullsrc=r={}:cl={},atrim=duration={seconds}[{label}];",
+            audio.sample_rate,
+            audio.layout()
+        )
+    };
+    let (seconds, color) = (gap.seconds, &gap.color);
+
+    let mut graph = String::new();
+    let mut segments = String::new();
+    for (index, info) in infos.iter().enumerate() {
+        if index > 0 {
+            graph.push_str(&format!(
+                "color=c={color}:s={width}x{height}:r={rate}:d={seconds},setsar=1,\
+                 format=yuv420p[gv{index}];"
+            ));
+            segments.push_str(&format!("[gv{index}]"));
+            if let Some(audio) = audio {
+                graph.push_str(&silence(audio, f64::from(seconds), &format!("ga{index}")));
+                segments.push_str(&format!("[ga{index}]"));
+            }
+        }
+        graph.push_str(&format!(
+            "[{index}:v]scale={width}:{height},setsar=1,fps={rate},format=yuv420p[v{index}];"
+        ));
+        segments.push_str(&format!("[v{index}]"));
+        if let Some(audio) = audio {
+            if info.has_audio() {
+                graph.push_str(&format!("[{index}:a]{}[a{index}];", audio.filter()));
+            } else {
+                let Some(duration) = info.duration_seconds else {
+                    return Err(SoraError::FfprobeFailed(format!(
+                        "unable to determine the duration of stitch input {} to fill its \
+                         silence",
+                        index + 1
+                    )));
+                };
+                graph.push_str(&silence(audio, duration, &format!("a{index}")));
+            }
+            segments.push_str(&format!("[a{index}]"));
+        }
+    }
+    graph.push_str(&segments);
+    graph.push_str(&format!(
+        "concat=n={}:v=1:a={}[v]",
+        infos.len() * 2 - 1,
+        u8::from(audio.is_some())
+    ));
+    if audio.is_some() {
+        graph.push_str("[a]");
+    }
+    Ok((graph, audio.is_some()))
+}
+
+/// Position within a clip at which a frame should be captured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameTime {
+    /// The final decoded frame of the clip.
+    Last,
+    /// An absolute offset from the start of the clip, in seconds.
+    At(f64),
+}
+
+/// A point in a clip as written on the command line: absolute seconds (`12.5`), seconds from
+/// the end (`-2.0`), or a fraction of the duration (`50%`).
+///
+/// Every time-based operation resolves these through [`TimeSpec::resolve`] so they agree on
+/// what is in range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeSpec {
+    Absolute(f64),
+    /// Seconds before the end of the clip (stored positive).
+    FromEnd(f64),
+    /// Fraction of the duration between 0.0 and 1.0.
+    Fraction(f64),
+}
+
+impl TimeSpec {
+    /// Convert to an offset from the start of a clip of the given duration.
+    pub fn resolve(self, duration: f64) -> Result<f64, SoraError> {
+        let seconds = match self {
+            TimeSpec::Absolute(seconds) => seconds,
+            TimeSpec::FromEnd(seconds) => duration - seconds,
+            TimeSpec::Fraction(fraction) => duration * fraction,
+        };
+        if (0.0..=duration).contains(&seconds) {
+            Ok(seconds)
+        } else {
+            Err(SoraError::TimeOutOfRange {
+                spec: self,
+                duration,
+            })
+        }
+    }
+}
+
+impl std::str::FromStr for TimeSpec {
+    type Err = SoraError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let invalid = || {
+            SoraError::InvalidConfig(format!(
+                "expected a time like 12.5, -2.0 or 50% (got '{value}')"
+            ))
+        };
+        let parse = |number: &str| {
+            number
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite() && *number >= 0.0)
+                .ok_or_else(invalid)
+        };
+        if let Some(percent) = trimmed.strip_suffix('%') {
+            Ok(TimeSpec::Fraction(parse(percent)? / 100.0))
+        } else if let Some(from_end) = trimmed.strip_prefix('-') {
+            Ok(TimeSpec::FromEnd(parse(from_end)?))
+        } else {
+            Ok(TimeSpec::Absolute(parse(trimmed)?))
+        }
+    }
+}
+
+impl std::fmt::Display for TimeSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSpec::Absolute(seconds) => write!(f, "{seconds}s"),
+            TimeSpec::FromEnd(seconds) => write!(f, "-{seconds}s"),
+            TimeSpec::Fraction(fraction) => write!(f, "{}%", fraction * 100.0),
+        }
+    }
+}
+
+/// Resolve a [`TimeSpec`] against a file by probing its duration.
+pub async fn resolve_time(path: &Path, spec: TimeSpec) -> Result<f64, SoraError> {
+    let duration = probe_media(path).await?.duration_seconds.ok_or_else(|| {
+        SoraError::FfprobeFailed(format!("no duration reported for {}", path.display()))
+    })?;
+    spec.resolve(duration)
+}
+
+/// Strategy used by the built-in ffmpeg frame extractor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameExtractionStrategy {
+    /// Seek to the last 50ms first, then fall back to `reverse`, then to selecting the final
+    /// frame by index after counting frames. The first method that writes a frame wins.
+    #[default]
+    Fallback,
+    /// Decode the whole clip through ffmpeg's `reverse` filter. Exact, but slow and
+    /// memory-hungry on long clips.
+    Reverse,
+    /// Seek close to the end with `-sseof` and keep the last decoded frame.
+    SeekFromEnd,
+}
+
+/// How far from the end the fallback chain's first attempt seeks, in `-sseof` form.
+const SHORT_SEEK_FROM_END: &str = "-0.05";
+
+/// Input side of an ffmpeg command that writes the final frame of `video_path`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LastFrameMethod {
+    /// `-sseof` to this offset and keep overwriting the output until the last frame.
+    SeekFromEnd(&'static str),
+    Reverse,
+    /// `select` the frame with this index (the frame count minus one).
+    Select(u64),
+}
+
+impl LastFrameMethod {
+    fn name(self) -> &'static str {
+        match self {
+            LastFrameMethod::SeekFromEnd(_) => "seek_from_end",
+            LastFrameMethod::Reverse => "reverse",
+            LastFrameMethod::Select(_) => "select",
+        }
+    }
+
+    fn apply(self, command: &mut Command, video_path: &Path) {
+        match self {
+            LastFrameMethod::SeekFromEnd(offset) => {
+                // Decode only the tail and let the image muxer keep overwriting the output,
+                // which leaves the last frame on disk.
+                command
+                    .arg("-sseof")
+                    .arg(offset)
+                    .arg("-i")
+                    .arg(video_path)
+                    .arg("-update")
+                    .arg("1");
+            }
+            LastFrameMethod::Reverse => {
+                command
+                    .arg("-i")
+                    .arg(video_path)
+                    .arg("-vf")
+                    .arg("reverse")
+                    .arg("-frames:v")
+                    .arg("1");
+            }
+            LastFrameMethod::Select(index) => {
+                command
+                    .arg("-i")
+                    .arg(video_path)
+                    .arg("-vf")
+                    .arg(format!("select=eq(n\\,{index})"))
+                    .arg("-frames:v")
+                    .arg("1");
+            }
+        }
+    }
+}
+
+/// Count a clip's video frames by reading its packets, which works even when the container
+/// doesn't record a frame count.
+async fn count_frames(path: &Path) -> Result<u64, SoraError> {
+    let output = Command::new("ffprobe")
+        .arg("-v")
+        .arg("error")
+        .arg("-select_streams")
+        .arg("v:0")
+        .arg("-count_packets")
+        .arg("-show_entries")
+        .arg("stream=nb_read_packets")
+        .arg("-of")
+        .arg("csv=p=0")
+        .arg(path)
+        .output_media()
+        .await?;
+    if !output.status.success() {
+        return Err(SoraError::FfprobeFailed(format!(
+            "counting frames exited with status {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&count| count > 0)
+        .ok_or_else(|| {
+            SoraError::FfprobeFailed(format!("no video frames counted in {}", path.display()))
+        })
+}
+
+/// Still-image formats used for extracted frames.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+        }
+    }
+
+    /// Guess the format from a file extension, falling back to PNG.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("jpg" | "jpeg") => ImageFormat::Jpeg,
+            _ => ImageFormat::Png,
+        }
+    }
+}
+
+/// Encoding options for an extracted frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameOptions {
+    pub format: ImageFormat,
+    /// JPEG quality on ffmpeg's `-q:v` scale (2 = best, 31 = smallest). Ignored for PNG.
+    pub quality: Option<u8>,
+}
+
+impl FrameOptions {
+    fn validate(&self) -> Result<(), SoraError> {
+        match (self.format, self.quality) {
+            (ImageFormat::Jpeg, Some(quality)) if !(2..=31).contains(&quality) => {
+                Err(SoraError::InvalidConfig(format!(
+                    "JPEG frame quality must be between 2 and 31 (got {quality})"
+                )))
+            }
+            (ImageFormat::Png, Some(_)) => Err(SoraError::InvalidConfig(
+                "frame quality only applies to JPEG frames".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn apply(&self, command: &mut Command) {
+        if self.format == ImageFormat::Jpeg {
+            command
+                .arg("-q:v")
+                .arg(self.quality.unwrap_or(2).to_string());
+        }
+    }
+}
+
+/// Parse a `WIDTHxHEIGHT` string such as `1280x720`.
+fn parse_dimensions(value: &str) -> Result<(u32, u32), SoraError> {
+    value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.trim().parse().ok()?, height.trim().parse().ok()?)))
+        .filter(|&(width, height)| width > 0 && height > 0)
+        .ok_or_else(|| {
+            SoraError::InvalidConfig(format!("expected dimensions like 1280x720 (got '{value}')"))
+        })
+}
+
+/// Extension point for capturing still frames from rendered clips.
+///
+/// The manager uses this to grab the seed frame for continuations. Implementations write an
+/// image in the requested format to `output_path` and return the path of the image they
+/// produced.
+#[async_trait]
+pub trait FrameExtractor: Send + Sync + std::fmt::Debug {
+    async fn extract_frame(
+        &self,
+        video_path: &Path,
+        at: FrameTime,
+        options: &FrameOptions,
+        output_path: &Path,
+    ) -> Result<PathBuf, SoraError>;
+}
+
+/// Default [`FrameExtractor`] that shells out to `ffmpeg`.
+#[derive(Debug, Clone, Default)]
+pub struct FfmpegFrameExtractor {
+    strategy: FrameExtractionStrategy,
+    print: bool,
+}
+
+impl FfmpegFrameExtractor {
+    pub fn new(strategy: FrameExtractionStrategy) -> Self {
+        Self {
+            strategy,
+            print: false,
+        }
+    }
+
+    /// Print the ffmpeg commands instead of running them ([`ContinuatorConfig::print_ffmpeg`]).
+    pub fn with_print_ffmpeg(mut self, print: bool) -> Self {
+        self.print = print;
+        self
+    }
+}
+
+#[async_trait]
+impl FrameExtractor for FfmpegFrameExtractor {
+    async fn extract_frame(
+        &self,
+        video_path: &Path,
+        at: FrameTime,
+        options: &FrameOptions,
+        output_path: &Path,
+    ) -> Result<PathBuf, SoraError> {
+        let method = match (at, self.strategy) {
+            (FrameTime::Last, FrameExtractionStrategy::Fallback) => {
+                return extract_last_frame(video_path, options, output_path, self.print).await;
+            }
+            (FrameTime::Last, FrameExtractionStrategy::Reverse) => LastFrameMethod::Reverse,
+            (FrameTime::Last, FrameExtractionStrategy::SeekFromEnd) => {
+                LastFrameMethod::SeekFromEnd("-1")
+            }
+            (FrameTime::At(seconds), _) => {
+                let status = frame_command(options, output_path, |command| {
+                    command
+                        .arg("-ss")
+                        .arg(format!("{seconds:.3}"))
+                        .arg("-i")
+                        .arg(video_path)
+                        .arg("-frames:v")
+                        .arg("1");
+                })
+                .run_media(self.print)
+                .await?;
+                return check_frame_status(status, output_path);
+            }
+        };
+        let status = frame_command(options, output_path, |command| {
+            method.apply(command, video_path)
+        })
+        .run_media(self.print)
+        .await?;
+        check_frame_status(status, output_path)
+    }
+}
+
+/// An ffmpeg invocation that writes one frame to `output_path`, with `input` adding the
+/// input and selection arguments.
+fn frame_command(
+    options: &FrameOptions,
+    output_path: &Path,
+    input: impl FnOnce(&mut Command),
+) -> Command {
+    let mut command = Command::new("ffmpeg");
+    command.arg("-v").arg("error");
+    input(&mut command);
+    options.apply(&mut command);
+    command.arg("-y").arg(output_path);
+    command
+}
+
+fn check_frame_status(status: ExitStatus, output_path: &Path) -> Result<PathBuf, SoraError> {
+    if !status.success() {
+        return Err(SoraError::FfmpegFailed(format!(
+            "ffmpeg exited with status {status}"
+        )));
+    }
+    Ok(output_path.to_path_buf())
+}
+
+/// Capture the final frame with the cheapest method that works on this clip: a short seek
+/// from the end, then `reverse`, then selecting the last frame by index. Some codecs leave
+/// the seek with nothing to decode, and `reverse` can fail or run out of memory on long
+/// clips, so each is only a step in the chain.
+async fn extract_last_frame(
+    video_path: &Path,
+    options: &FrameOptions,
+    output_path: &Path,
+    print: bool,
+) -> Result<PathBuf, SoraError> {
+    let mut failures = Vec::new();
+    for method in [
+        Some(LastFrameMethod::SeekFromEnd(SHORT_SEEK_FROM_END)),
+        Some(LastFrameMethod::Reverse),
+        None,
+    ] {
+        let method = match method {
+            Some(method) => method,
+            None => match count_frames(video_path).await {
+                Ok(count) => LastFrameMethod::Select(count - 1),
+                Err(err) => {
+                    failures.push(format!("select: {err}"));
+                    break;
+                }
+            },
+        };
+        // A stale image from an earlier attempt would look like success.
+        match tokio::fs::remove_file(output_path).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+        }
+        let status = frame_command(options, output_path, |command| {
+            method.apply(command, video_path)
+        })
+        .run_media(print)
+        .await?;
+        let wrote_frame = print
+            || tokio::fs::metadata(output_path)
+                .await
+                .is_ok_and(|meta| meta.len() > 0);
+        if status.success() && wrote_frame {
+            debug!(
+                video = %video_path.display(),
+                method = method.name(),
+                "extracted last frame"
+            );
+            return Ok(output_path.to_path_buf());
+        }
+        let failure = if status.success() {
+            "no frame written".to_string()
+        } else {
+            format!("exited with status {status}")
+        };
+        warn!(
+            video = %video_path.display(),
+            method = method.name(),
+            "last-frame extraction method failed ({failure})"
+        );
+        failures.push(format!("{}: {failure}", method.name()));
+    }
+    Err(SoraError::FfmpegFailed(format!(
+        "could not extract the last frame of {} ({})",
+        video_path.display(),
+        failures.join("; ")
+    )))
+}
+
+/// Placement of burned-in annotation text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum AnnotationPosition {
+    TopLeft,
+    TopRight,
+    #[default]
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+impl AnnotationPosition {
+    /// `x`/`y` expressions for ffmpeg's `drawtext` filter.
+    fn drawtext_coordinates(self) -> (&'static str, &'static str) {
+        match self {
+            AnnotationPosition::TopLeft => ("24", "24"),
+            AnnotationPosition::TopRight => ("w-tw-24", "24"),
+            AnnotationPosition::BottomLeft => ("24", "h-th-24"),
+            AnnotationPosition::BottomRight => ("w-tw-24", "h-th-24"),
+            AnnotationPosition::Center => ("(w-tw)/2", "(h-th)/2"),
+        }
+    }
+}
+
+/// Text overlay burned into a clip by [`VideoManager::annotate`].
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationSpec {
+    /// Text to draw; defaults to the source clip's local identifier.
+    pub text: Option<String>,
+    pub position: AnnotationPosition,
+    /// Font size in pixels (defaults to 36).
+    pub font_size: Option<u32>,
+    /// Draw a translucent box behind the text.
+    pub boxed: bool,
+    /// Font file to use; a system default is used when unset or missing.
+    pub font_file: Option<PathBuf>,
+    /// Replace an existing clip with the output id instead of refusing.
+    pub overwrite: bool,
+}
+
+/// Options for [`VideoManager::boomerang`].
+#[derive(Debug, Clone, Default)]
+pub struct BoomerangOptions {
+    /// Drop the audio track instead of reversing it.
+    pub strip_audio: bool,
+    /// Replace an existing clip with the output id instead of refusing.
+    pub overwrite: bool,
+}
+
+/// Request for creating a brand-new video.
+#[derive(Debug, Clone, Default)]
+pub struct CreateVideoRequest {
+    pub prompt: String,
+    /// Local identifier for saving the video; used as filename stem.
+    pub local_id: String,
+    pub model: Option<String>,
+    pub seconds: Option<u32>,
+    pub size: Option<String>,
+    /// Poll interval for this render only, overriding the manager default.
+    pub poll_interval_ms: Option<u64>,
+    /// Image to use as the first frame, e.g. an upload already held in memory.
+    pub reference: Option<ReferenceSource>,
+    /// Submit the render and save a pending record instead of waiting for the video; collect
+    /// it later with [`VideoManager::finish_pending`].
+    pub no_wait: bool,
+    /// Recorded as [`VideoMetadata::notes`].
+    pub notes: Option<String>,
+    /// Recorded as [`VideoMetadata::extra`].
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// Send the prompt as given, without [`ContinuatorConfig::prompt_prefix`] and
+    /// [`ContinuatorConfig::prompt_suffix`].
+    pub no_prompt_wrap: bool,
+    /// Cloud Storage prefix (`gs://bucket/path/`) Veo writes this render to, overriding
+    /// [`ContinuatorConfig::gcp_storage_uri`]. The video is still downloaded into the data
+    /// directory. Veo only.
+    pub storage_uri: Option<String>,
+}
+
+/// An image handed to the provider as the first frame of a render.
+#[derive(Debug, Clone)]
+pub enum ReferenceSource {
+    /// An image file on disk; its format is guessed from the extension.
+    Path(PathBuf),
+    /// Encoded image bytes already in memory, sent as-is without touching disk.
+    Bytes {
+        data: Vec<u8>,
+        /// MIME type of `data`, e.g. `image/png` or `image/jpeg`.
+        mime: String,
+    },
+}
+
+impl ReferenceSource {
+    fn validate(&self) -> Result<(), SoraError> {
+        match self {
+            ReferenceSource::Path(path) if !path.exists() => {
+                Err(SoraError::VideoNotFound(path.display().to_string()))
+            }
+            ReferenceSource::Bytes { data, .. } if data.is_empty() => Err(
+                SoraError::InvalidConfig("reference image bytes are empty".to_string()),
+            ),
+            ReferenceSource::Bytes { mime, .. } if !mime.starts_with("image/") => Err(
+                SoraError::InvalidConfig(format!("reference mime type '{mime}' is not an image")),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Image bytes, MIME type, and a file name to upload them under.
+    fn load_blocking(&self) -> Result<(Vec<u8>, String, String), SoraError> {
+        match self {
+            ReferenceSource::Path(path) => {
+                let format = ImageFormat::from_path(path);
+                Ok((
+                    std::fs::read(path)?,
+                    format.mime_type().to_string(),
+                    format!("input.{}", format.extension()),
+                ))
+            }
+            ReferenceSource::Bytes { data, mime } => {
+                let extension = match mime.as_str() {
+                    "image/jpeg" => "jpg",
+                    other => other.trim_start_matches("image/"),
+                };
+                Ok((data.clone(), mime.clone(), format!("input.{extension}")))
+            }
+        }
+    }
+
+    /// Base64 image data and MIME type for an inline JSON payload.
+    async fn encode_base64(&self) -> Result<(String, String), SoraError> {
+        match self {
+            ReferenceSource::Path(path) => Ok((
+                BASE64_STANDARD.encode(fs::read(path).await?),
+                ImageFormat::from_path(path).mime_type().to_string(),
+            )),
+            ReferenceSource::Bytes { data, mime } => {
+                Ok((BASE64_STANDARD.encode(data), mime.clone()))
+            }
+        }
+    }
+}
+
+/// Request for creating a continuation using the last frame of an existing video.
+#[derive(Debug, Clone, Default)]
+pub struct ContinueVideoRequest {
+    /// Existing local video identifier to continue from.
+    pub parent_local_id: String,
+    /// New local identifier for the continuation clip.
+    pub local_id: String,
+    pub prompt: String,
+    pub model: Option<String>,
+    pub seconds: Option<u32>,
+    pub size: Option<String>,
+    /// Poll interval for this render only, overriding the manager default.
+    pub poll_interval_ms: Option<u64>,
+    /// Take the seed frame from this clip instead of the parent, e.g. for match cuts. The parent
+    /// still supplies model, size, and duration defaults.
+    pub seed_from: Option<String>,
+    /// Where in the seed clip to take the frame (defaults to its last frame). Pick a point with
+    /// [`VideoManager::tail_contact_sheet`] to hand-tune a transition.
+    pub seed_at: Option<TimeSpec>,
+    /// Record the parent's last second of audio so a later stitch can crossfade it into this
+    /// clip's opening, smoothing the audio seam a still-frame seed can't carry.
+    pub carry_audio: bool,
+    /// Submit the render and save a pending record instead of waiting for the video, as with
+    /// [`CreateVideoRequest::no_wait`].
+    pub no_wait: bool,
+    /// Recorded as [`VideoMetadata::notes`].
+    pub notes: Option<String>,
+    /// Recorded as [`VideoMetadata::extra`].
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// Send the prompt as given, without [`ContinuatorConfig::prompt_prefix`] and
+    /// [`ContinuatorConfig::prompt_suffix`].
+    pub no_prompt_wrap: bool,
+    /// As [`CreateVideoRequest::storage_uri`].
+    pub storage_uri: Option<String>,
+}
+
+/// Frames from the end of a clip tiled into one image, from
+/// [`VideoManager::tail_contact_sheet`].
+#[derive(Debug, Clone)]
+pub struct ContactSheet {
+    pub path: PathBuf,
+    /// Timestamp of each tile in reading order, in seconds from the start of the clip. Each
+    /// tile is also labelled with it.
+    pub timestamps: Vec<f64>,
+    pub columns: u32,
+}
+
+const CONTACT_SHEET_COLUMNS: u32 = 4;
+const CONTACT_SHEET_TILES: u32 = 12;
+const CONTACT_SHEET_TILE_WIDTH: u32 = 320;
+
+/// Stored metadata for each downloaded clip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoMetadata {
+    pub local_id: String,
+    pub remote_id: String,
+    pub prompt: String,
+    pub model: String,
+    pub seconds: u32,
+    pub size: String,
+    pub created_at: Option<i64>,
+    pub file_path: PathBuf,
+    pub parent: Option<String>,
+    #[serde(default = "ProviderKind::default_backend")]
+    pub backend: ProviderKind,
+    /// Whether `created_at` came from the provider or was filled in locally.
+    #[serde(default)]
+    pub created_at_source: ValueSource,
+    /// Unix time in milliseconds when this clip's metadata was first written here, whatever the
+    /// provider reports. [`VideoManager::list_videos`] sorts by it; zero for clips recorded
+    /// before it existed.
+    #[serde(default)]
+    pub local_created_at: i64,
+    /// Whether `seconds` came from the provider or is the requested duration.
+    #[serde(default)]
+    pub seconds_source: ValueSource,
+    /// Rendered duration minus requested duration, when the clip could be probed.
+    #[serde(default)]
+    pub duration_delta: Option<f64>,
+    /// Clips concatenated into this one, in playback order (empty for rendered clips).
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Remote id of the video this clip was remixed from.
+    #[serde(default)]
+    pub remix_of: Option<String>,
+    /// Hex SHA-256 of the clip file, recorded when checksums are enabled.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Clip whose last frame seeded this continuation (usually the parent).
+    #[serde(default)]
+    pub seed_source: Option<String>,
+    /// Free-form labels added with [`VideoManager::update_metadata`].
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Seed frame kept beside the clip when `keep_seed_frame` is enabled.
+    #[serde(default)]
+    pub seed_frame: Option<PathBuf>,
+    /// Extra videos returned by the same render (Veo can return several), saved beside the clip
+    /// as `<id>.sample-N.mp4`.
+    #[serde(default)]
+    pub samples: Vec<PathBuf>,
+    /// Videos from the same render that the provider left in Cloud Storage.
+    #[serde(default)]
+    pub storage_uris: Vec<String>,
+    /// Decoded size in bytes of a video the provider returned inline in its response (Veo
+    /// without `gcp_storage_uri`).
+    #[serde(default)]
+    pub inline_bytes: Option<u64>,
+    /// The parent's last second of audio, recorded with [`ContinueVideoRequest::carry_audio`]
+    /// and crossfaded into this clip's opening when it is stitched right after its parent.
+    #[serde(default)]
+    pub carried_audio: Option<PathBuf>,
+    /// Submitted without waiting; the video isn't downloaded until
+    /// [`VideoManager::finish_pending`] collects it.
+    #[serde(default)]
+    pub pending: bool,
+    /// Free-form note about the clip ("best take"); never sent to the provider.
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// The prompt as given, when the configured prefix or suffix changed what was sent (which
+    /// is what `prompt` records).
+    #[serde(default)]
+    pub original_prompt: Option<String>,
+    /// Key/value data for downstream tools, such as a shot number or asset-manager id; never
+    /// sent to the provider.
+    #[serde(default)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A render as it would be submitted, from [`VideoManager::plan_create`] or
+/// [`VideoManager::plan_continue`], for showing before any money is spent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RenderPlan {
+    pub provider: ProviderKind,
+    /// The prompt as sent, after the configured prefix and suffix.
+    pub prompt: String,
+    pub model: String,
+    pub size: String,
+    pub seconds: u32,
+    /// Clip a continuation extends.
+    pub parent: Option<String>,
+    /// Rough cost in US dollars at published per-second prices, when the model's price is
+    /// known. The provider's bill is what counts.
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl RenderPlan {
+    fn new(
+        provider: ProviderKind,
+        prompt: String,
+        (model, size, seconds): (String, String, u32),
+        parent: Option<String>,
+    ) -> Self {
+        let estimated_cost_usd = match provider {
+            ProviderKind::Sora | ProviderKind::Veo => MODEL_PRICES_USD_PER_SECOND
+                .iter()
+                .find(|(name, sizes, _)| {
+                    *name == model && (sizes.is_empty() || sizes.contains(&size.as_str()))
+                })
+                .map(|(_, _, price)| price * f64::from(seconds)),
+            #[cfg(any(test, feature = "mock"))]
+            ProviderKind::Mock => Some(0.0),
+        };
+        Self {
+            provider,
+            prompt,
+            model,
+            size,
+            seconds,
+            parent,
+            estimated_cost_usd,
+        }
+    }
+}
+
+/// Edits applied by [`VideoManager::update_metadata`]. Only descriptive fields can change;
+/// identifiers, paths, and render results stay as recorded.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataPatch {
+    pub prompt: Option<String>,
+    pub model: Option<String>,
+    pub backend: Option<ProviderKind>,
+    /// Replacement notes; an empty string clears them.
+    pub notes: Option<String>,
+    /// Tags to add; ones already present are ignored.
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    /// Entries to add to [`VideoMetadata::extra`], replacing any value under the same key.
+    pub set_extra: serde_json::Map<String, serde_json::Value>,
+    /// Keys to remove from [`VideoMetadata::extra`].
+    pub remove_extra: Vec<String>,
+}
+
+impl MetadataPatch {
+    pub fn is_empty(&self) -> bool {
+        self.prompt.is_none()
+            && self.model.is_none()
+            && self.backend.is_none()
+            && self.notes.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.set_extra.is_empty()
+            && self.remove_extra.is_empty()
+    }
+}
+
+/// Result of [`VideoManager::verify_checksums`].
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// Clips that had a checksum to compare.
+    pub checked: usize,
+    pub mismatches: Vec<ChecksumMismatch>,
+    pub errors: Vec<ClipError>,
+}
+
+/// One beat of a [`FlowRequest`]. Unset fields fall back to the previous clip, then to the
+/// manager's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct FlowBeat {
+    pub prompt: String,
+    pub model: Option<String>,
+    pub size: Option<String>,
+    pub seconds: Option<u32>,
+    /// Clip this beat continues under [`VideoManager::run_flow_graph`]: another beat's id or
+    /// any saved clip. Ignored by [`VideoManager::run_flow`], which always continues the
+    /// previous beat.
+    pub depends_on: Option<String>,
+}
+
+/// A chain of continuations rendered one after another and stitched together.
+#[derive(Debug, Clone, Default)]
+pub struct FlowRequest {
+    /// Base identifier: beats are named from it (see `beat_name_template`) and the stitch is
+    /// saved as `<id>`.
+    pub id: String,
+    /// Existing clip to continue from and include at the start of the stitch.
+    pub start_from: Option<String>,
+    pub beats: Vec<FlowBeat>,
+    /// Poll interval override applied to every beat.
+    pub poll_interval_ms: Option<u64>,
+    /// Reuse beats already saved by an earlier, interrupted run instead of failing on them. A
+    /// saved clip is only reused when its parent and prompt match the beat.
+    pub resume: bool,
+    /// How beats are named (defaults to [`DEFAULT_BEAT_NAME_TEMPLATE`], `<id>-01`, `<id>-02`,
+    /// ...). Supports `{base}`, `{index}`, and zero-padded `{index:03}`.
+    pub beat_name_template: Option<String>,
+    /// Render and save the beats without stitching them, e.g. to edit them elsewhere.
+    pub skip_stitch: bool,
+    /// Beats [`VideoManager::run_flow_graph`] renders at once (defaults to
+    /// [`DEFAULT_FLOW_CONCURRENCY`]).
+    pub concurrency: Option<usize>,
+}
+
+/// Result of [`VideoManager::run_flow`]. Beats that finished before a failure are kept.
+#[derive(Debug, Default)]
+pub struct FlowReport {
+    /// Beats rendered (or reused when resuming), in order.
+    pub completed: Vec<VideoMetadata>,
+    /// The beat that stopped the flow, if one failed.
+    pub failure: Option<FlowFailure>,
+    /// The stitched output: `<id>` when every beat finished, or `<id>-partial` covering the
+    /// completed beats after a failure. Always `None` with [`FlowRequest::skip_stitch`].
+    pub stitched: Option<PathBuf>,
+    /// Why stitching failed, when the beats themselves succeeded (or partially succeeded).
+    pub stitch_error: Option<SoraError>,
+}
+
+impl FlowReport {
+    /// Whether every beat rendered and the final stitch, unless skipped, was written.
+    pub fn is_complete(&self) -> bool {
+        self.failure.is_none() && self.stitch_error.is_none()
+    }
+}
+
+/// One row of a batch file for [`VideoManager::run_batch`]: a clip to create.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchJob {
+    pub id: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub size: Option<String>,
+    #[serde(default)]
+    pub seconds: Option<u32>,
+}
+
+/// What happened to one [`BatchJob`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Rendered,
+    /// The clip was already saved by an earlier run.
+    Skipped,
+    Failed,
+}
+
+impl BatchStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BatchStatus::Rendered => "rendered",
+            BatchStatus::Skipped => "skipped",
+            BatchStatus::Failed => "failed",
+        }
+    }
+}
+
+/// A row of the results file written by [`VideoManager::run_batch`].
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult {
+    pub id: String,
+    pub status: BatchStatus,
+    pub error: Option<String>,
+}
+
+/// Result of [`VideoManager::run_batch`].
+#[derive(Debug)]
+pub struct BatchReport {
+    /// One result per job, in file order.
+    pub results: Vec<BatchResult>,
+    /// Where the results were written: `<name>.results.csv` or `<name>.results.jsonl` beside
+    /// the batch file.
+    pub results_path: PathBuf,
+}
+
+impl BatchReport {
+    pub fn failed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.status == BatchStatus::Failed)
+            .count()
+    }
+}
+
+/// A flow beat that failed, from [`FlowReport::failure`].
+#[derive(Debug)]
+pub struct FlowFailure {
+    /// One-based position of the beat in the flow.
+    pub beat: usize,
+    pub local_id: String,
+    pub error: SoraError,
+}
+
+/// A problem with a flow found by [`VideoManager::validate_flow`] before anything renders.
+#[derive(Debug)]
+pub struct ValidationIssue {
+    /// One-based position of the beat at fault, or `None` for the flow as a whole.
+    pub beat: Option<usize>,
+    pub error: SoraError,
+}
+
+/// Where a clip's render stands on the provider, from [`VideoManager::remote_status_many`].
+#[derive(Debug, Clone)]
+pub struct RemoteStatus {
+    /// Empty for a provider job with no local record.
+    pub local_id: String,
+    pub remote_id: String,
+    pub status: VideoStatus,
+    /// Percent complete, when the provider reports it.
+    pub progress: Option<f64>,
+}
+
+/// What [`VideoManager::cancel`] did about a clip's render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CancelOutcome {
+    /// The provider accepted the cancellation.
+    Canceled,
+    /// The render had already reached this terminal status, so nothing was sent.
+    AlreadyFinished(VideoStatus),
+    /// The provider has no way to cancel a render (Veo); it was left running.
+    Unsupported,
+}
+
+/// A clip that could not be processed during a scan over many clips.
+#[derive(Debug)]
+pub struct ClipError {
+    pub local_id: String,
+    pub error: SoraError,
+}
+
+/// Table of contents written as `manifest.json` at the root of an exported bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    /// Local ids of the bundled clips; each has `<id>.mp4` and `<id>.json` beside the manifest.
+    pub clips: Vec<String>,
+}
+
+const BUNDLE_VERSION: u32 = 1;
+const BUNDLE_MANIFEST: &str = "manifest.json";
+
+/// A stored clip whose file no longer matches its recorded checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    pub local_id: String,
+    pub expected: String,
+    /// Checksum of the file on disk, or `None` if the file is missing.
+    pub actual: Option<String>,
+}
+
+/// Where a metadata value came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    /// Reported by the provider's API.
+    #[default]
+    Provider,
+    /// Inferred locally because the provider did not report it.
+    Local,
+}
+
+impl ValueSource {
+    fn reported(present: bool) -> Self {
+        if present { Self::Provider } else { Self::Local }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Provider => "provider",
+            Self::Local => "local",
+        }
+    }
+}
+
+/// Combined duration and size of a set of clips.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Aggregate {
+    pub total_seconds: f64,
+    pub total_bytes: u64,
+    pub clip_count: usize,
+}
+
+/// Options for [`quick_create`] and [`quick_continue`].
+#[derive(Debug, Clone, Default)]
+pub struct QuickOpts {
+    /// Backend to render with; defaults to Sora, with credentials read from the environment.
+    pub provider: Option<ProviderKind>,
+    pub model: Option<String>,
+    pub size: Option<String>,
+    pub seconds: Option<u32>,
+    /// Where to keep the clip and its metadata (defaults to a directory under the system temp
+    /// dir).
+    pub data_dir: Option<PathBuf>,
+}
+
+impl QuickOpts {
+    fn into_parts(self) -> (ContinuatorConfig, CreateVideoRequest) {
+        let data_dir = self
+            .data_dir
+            .unwrap_or_else(|| std::env::temp_dir().join("continuator"));
+        let config = ContinuatorConfig {
+            provider: self.provider,
+            data_dir: Some(data_dir),
+            ..ContinuatorConfig::default()
+        };
+        let request = CreateVideoRequest {
+            prompt: String::new(),
+            local_id: quick_local_id(),
+            model: self.model,
+            seconds: self.seconds,
+            size: self.size,
+            poll_interval_ms: None,
+            reference: None,
+            no_wait: false,
+            notes: None,
+            extra: serde_json::Map::new(),
+            no_prompt_wrap: false,
+            storage_uri: None,
+        };
+        (config, request)
+    }
+}
+
+/// Reject local ids that can't be used as a file stem inside the data directory: empty ids,
+/// `.` and `..`, and ids containing path separators.
+fn check_local_id(local_id: &str) -> Result<(), SoraError> {
+    if local_id.trim().is_empty()
+        || local_id == "."
+        || local_id == ".."
+        || local_id.contains(['/', '\\', '\0'])
+    {
+        return Err(SoraError::InvalidConfig(format!(
+            "local id '{local_id}' must be non-empty and free of path separators"
+        )));
+    }
+    Ok(())
+}
+
+/// Reject [`VideoMetadata::extra`] keys that are blank or padded, which would be awkward to
+/// look up again.
+fn check_extra(extra: &serde_json::Map<String, serde_json::Value>) -> Result<(), SoraError> {
+    match extra
+        .keys()
+        .find(|key| key.is_empty() || key.trim() != key.as_str())
+    {
+        Some(key) => Err(SoraError::InvalidConfig(format!(
+            "metadata keys must be non-empty without surrounding spaces (got '{key}')"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Reject blank prompts before any work is done. Seeded renders may legitimately rely on the
+/// image alone, so those only get a warning.
+fn check_prompt(prompt: &str, seeded: bool) -> Result<(), SoraError> {
+    if !prompt.trim().is_empty() {
+        return Ok(());
+    }
+    if seeded {
+        warn!("prompt is empty; relying on the seed frame alone");
+        return Ok(());
+    }
+    Err(SoraError::InvalidConfig(
+        "prompt must be non-empty".to_string(),
+    ))
+}
+
+/// An intermediate file or directory that is removed when dropped, so error paths don't
+/// leak it.
+#[derive(Debug)]
+struct ScratchFile(PathBuf);
+
+impl ScratchFile {
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Move the file to `destination` instead of deleting it, copying when the rename crosses
+    /// filesystems.
+    async fn persist(self, destination: &Path) -> Result<(), SoraError> {
+        if fs::rename(&self.0, destination).await.is_err() {
+            fs::copy(&self.0, destination).await?;
+        }
+        Ok(())
+    }
+
+    /// Leave the file in place instead of deleting it.
+    fn keep(self) -> PathBuf {
+        let mut this = std::mem::ManuallyDrop::new(self);
+        std::mem::take(&mut this.0)
+    }
+}
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        if self.0.is_dir() {
+            let _ = std::fs::remove_dir_all(&self.0);
+        } else {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+}
+
+fn unix_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Unix time in milliseconds for [`VideoMetadata::local_created_at`], nudged forward so clips
+/// recorded by this process never share a value and always list in the order they were saved.
+fn recording_millis() -> i64 {
+    static LAST: AtomicI64 = AtomicI64::new(0);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or_default();
+    let previous = LAST
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or_default();
+    now.max(previous + 1)
+}
+
+fn quick_local_id() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    format!("quick-{millis}-{:04x}", fastrand::u16(..))
+}
+
+/// Render a clip from a prompt and return the path of the MP4: prompt in, file out.
+///
+/// Builds a default configuration from the environment, so `OPENAI_API_KEY` (or the usual Veo
+/// settings) must be available.
+pub async fn quick_create(prompt: &str, opts: QuickOpts) -> Result<PathBuf, SoraError> {
+    let (config, mut request) = opts.into_parts();
+    request.prompt = prompt.to_string();
+    let manager = VideoManager::new(config)?;
+    Ok(manager.create_video(request).await?.file_path)
+}
+
+/// Continue from the last frame of any video file and return the path of the new MP4.
+pub async fn quick_continue(
+    parent_path: &Path,
+    prompt: &str,
+    opts: QuickOpts,
+) -> Result<PathBuf, SoraError> {
+    let (config, mut request) = opts.into_parts();
+    request.prompt = prompt.to_string();
+    let manager = VideoManager::new(config)?;
+    Ok(manager
+        .create_from_video_file(parent_path, request)
+        .await?
+        .file_path)
+}
+
+/// Where [`VideoManager`] keeps clip metadata, one [`VideoMetadata`] record per local id.
+///
+/// The default, [`JsonFileStore`], writes `<id>.json` files into the data directory; plug in a
+/// database-backed store with [`VideoManager::with_store`] when several processes share a
+/// project. Videos themselves always live in the data directory.
+#[async_trait]
+pub trait MetadataStore: Send + Sync + std::fmt::Debug {
+    /// Write a clip's record, replacing any earlier record with the same id.
+    async fn save(&self, metadata: &VideoMetadata) -> Result<(), SoraError>;
+
+    /// Write a new clip's record, failing with [`SoraError::InvalidConfig`] when one already
+    /// exists. The default checks then saves; override it when the store can claim the id
+    /// atomically, so two processes saving the same new id can't both succeed.
+    async fn insert(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
+        if self.exists(&metadata.local_id).await? {
+            return Err(taken_id(&metadata.local_id));
+        }
+        self.save(metadata).await
+    }
+
+    /// Read a clip's record, or fail with [`SoraError::MetadataNotFound`] when there is none.
+    async fn load(&self, local_id: &str) -> Result<VideoMetadata, SoraError>;
+
+    /// Ids of every stored record, sorted.
+    async fn list(&self) -> Result<Vec<String>, SoraError>;
+
+    /// Remove a clip's record. Removing one that doesn't exist is not an error.
+    async fn delete(&self, local_id: &str) -> Result<(), SoraError>;
+
+    /// Whether a record exists for `local_id`. The default loads it; override this when the
+    /// store can answer more cheaply.
+    async fn exists(&self, local_id: &str) -> Result<bool, SoraError> {
+        match self.load(local_id).await {
+            Ok(_) => Ok(true),
+            Err(SoraError::MetadataNotFound(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn taken_id(local_id: &str) -> SoraError {
+    SoraError::InvalidConfig(format!("local id '{local_id}' already exists"))
+}
+
+/// Default [`MetadataStore`]: one pretty-printed `<id>.json` file per clip in a directory.
+#[derive(Debug, Clone)]
+pub struct JsonFileStore {
+    dir: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, local_id: &str) -> PathBuf {
+        self.dir.join(format!("{local_id}.json"))
+    }
+}
+
+#[async_trait]
+impl MetadataStore for JsonFileStore {
+    /// Writes through a temporary file so readers never see a partial record.
+    async fn save(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
+        let path = self.path(&metadata.local_id);
+        let staging = path.with_extension("json.partial");
+        let data = serde_json::to_vec_pretty(metadata)?;
+        write_file(&staging, data).await?;
+        if let Err(err) = fs::rename(&staging, &path).await {
+            let _ = fs::remove_file(&staging).await;
+            return Err(SoraError::from(err).writing(&path));
+        }
+        Ok(())
+    }
+
+    /// Claims the id by creating the record file exclusively, then fills it in as `save` does.
+    async fn insert(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
+        let path = self.path(&metadata.local_id);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+        {
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                return Err(taken_id(&metadata.local_id));
+            }
+            Err(err) => return Err(SoraError::from(err).writing(&path)),
+        }
+        let saved = self.save(metadata).await;
+        if saved.is_err() {
+            let _ = fs::remove_file(&path).await;
+        }
+        saved
+    }
+
+    async fn load(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        let bytes = fs::read(self.path(local_id))
+            .await
+            .map_err(|_| SoraError::MetadataNotFound(local_id.to_string()))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    async fn list(&self) -> Result<Vec<String>, SoraError> {
+        // Read-only, so a missing or read-only directory just lists what's there.
+        let mut dir = match fs::read_dir(&self.dir).await {
+            Ok(dir) => dir,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut local_ids = Vec::new();
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            {
+                local_ids.push(stem.to_string());
+            }
+        }
+        local_ids.sort();
+        Ok(local_ids)
+    }
+
+    async fn delete(&self, local_id: &str) -> Result<(), SoraError> {
+        match fs::remove_file(self.path(local_id)).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// A record counts even when it can't be parsed, so its id isn't reused.
+    async fn exists(&self, local_id: &str) -> Result<bool, SoraError> {
+        Ok(fs::try_exists(self.path(local_id)).await?)
+    }
+}
+
+/// Primary entry point for managing videos and continuations.
+pub struct VideoManager {
+    backend: Backend,
+    store: Arc<dyn MetadataStore>,
+    data_dir: PathBuf,
+    temp_dir: PathBuf,
+    poll_interval: Duration,
+    frame_extractor: Arc<dyn FrameExtractor>,
+    duration_tolerance: f64,
+    strict_duration: bool,
+    frame_options: FrameOptions,
+    thumbnail_size: Option<(u32, u32)>,
+    audio_crossfade: Option<Duration>,
+    checksums: bool,
+    print_ffmpeg: bool,
+    probe_concurrency: usize,
+    stitch_chunk_size: Option<usize>,
+    encode: EncodeSettings,
+    audio_sample_rate: Option<u32>,
+    audio_channels: Option<u32>,
+    upload_command: Option<String>,
+    skip_duration_validation: bool,
+    keep_seed_frame: bool,
+    keep_manifest: bool,
+    include_model_in_filename: bool,
+    include_backend_in_filename: bool,
+    strict_provider: bool,
+    render_retries: u32,
+    prompt_prefix: Option<String>,
+    prompt_suffix: Option<String>,
+    /// Set once the data directory has been created and shown to be writable.
+    data_dir_ready: AtomicBool,
+}
+
+impl VideoManager {
+    /// Build a new manager from high-level configuration.
+    pub fn new(config: ContinuatorConfig) -> Result<Self, SoraError> {
+        let resolved = config.expand_env()?.resolve()?;
+        Ok(Self {
+            backend: resolved.backend,
+            store: Arc::new(JsonFileStore::new(resolved.data_dir.clone())),
+            data_dir: resolved.data_dir,
+            temp_dir: resolved.temp_dir,
+            poll_interval: resolved.poll_interval,
+            frame_extractor: Arc::new(
+                FfmpegFrameExtractor::new(resolved.frame_extraction)
+                    .with_print_ffmpeg(resolved.print_ffmpeg),
+            ),
+            duration_tolerance: resolved.duration_tolerance,
+            strict_duration: resolved.strict_duration,
+            frame_options: resolved.frame_options,
+            thumbnail_size: resolved.thumbnail_size,
+            audio_crossfade: resolved.audio_crossfade,
+            checksums: resolved.checksums,
+            print_ffmpeg: resolved.print_ffmpeg,
+            probe_concurrency: resolved.probe_concurrency,
+            stitch_chunk_size: resolved.stitch_chunk_size,
+            encode: resolved.encode,
+            audio_sample_rate: resolved.audio_sample_rate,
+            audio_channels: resolved.audio_channels,
+            upload_command: resolved.upload_command,
+            skip_duration_validation: resolved.skip_duration_validation,
+            keep_seed_frame: resolved.keep_seed_frame,
+            keep_manifest: resolved.keep_manifest,
+            include_model_in_filename: resolved.include_model_in_filename,
+            include_backend_in_filename: resolved.include_backend_in_filename,
+            strict_provider: resolved.strict_provider,
+            render_retries: resolved.render_retries,
+            prompt_prefix: resolved.prompt_prefix,
+            prompt_suffix: resolved.prompt_suffix,
+            data_dir_ready: AtomicBool::new(false),
+        })
+    }
+
+    /// Quota the provider reported in its most recent response, for pacing a large batch.
+    /// `None` until a response carries `x-ratelimit-*` headers (Veo and the mock never do).
+    pub fn last_rate_limit(&self) -> Option<RateLimitInfo> {
+        match &self.backend {
+            Backend::Sora(backend) => backend.client.policy.last_rate_limit(),
+            Backend::Veo(backend) => backend.client.policy.last_rate_limit(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => None,
+        }
+    }
+
+    /// Replace the frame extractor used for continuation seed frames.
+    pub fn with_frame_extractor(mut self, extractor: impl FrameExtractor + 'static) -> Self {
+        self.frame_extractor = Arc::new(extractor);
+        self
+    }
+
+    /// Keep clip metadata in `store` instead of `<id>.json` files in the data directory.
+    /// Videos are still written to the data directory.
+    pub fn with_store(mut self, store: impl MetadataStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Ensure the data directory exists on disk. The first call also writes a probe file, so a
+    /// read-only mount or unwritable directory is reported before any render is paid for.
+    async fn ensure_data_dir(&self) -> Result<(), SoraError> {
+        if self.data_dir_ready.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let dir = &self.data_dir;
+        fs::create_dir_all(dir).await.map_err(|err| {
+            if err.kind() == std::io::ErrorKind::NotADirectory {
+                SoraError::InvalidConfig(format!(
+                    "data_dir exists and is not a directory: {}",
+                    dir.display()
+                ))
+            } else {
+                SoraError::Io(err).writing(dir)
+            }
+        })?;
+        let probe = dir.join(".write-check");
+        fs::write(&probe, b"")
+            .await
+            .map_err(|err| SoraError::Io(err).writing(dir))?;
+        let _ = fs::remove_file(&probe).await;
+        self.data_dir_ready.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Reserve a path in the temp directory that is deleted when the guard drops.
+    async fn scratch_file(&self, name: &str) -> Result<ScratchFile, SoraError> {
+        fs::create_dir_all(&self.temp_dir).await?;
+        Ok(ScratchFile(self.temp_dir.join(name)))
+    }
+
+    /// Join the configured prefix and suffix onto a prompt with spaces. Returns the prompt to
+    /// send and, when it differs, the original.
+    fn wrap_prompt(&self, prompt: &str, skip: bool) -> (String, Option<String>) {
+        if skip || (self.prompt_prefix.is_none() && self.prompt_suffix.is_none()) {
+            return (prompt.to_string(), None);
+        }
+        let parts = [
+            self.prompt_prefix.as_deref(),
+            Some(prompt),
+            self.prompt_suffix.as_deref(),
+        ];
+        let wrapped = parts
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if wrapped == prompt {
+            (prompt.to_string(), None)
+        } else {
+            (wrapped, Some(prompt.to_string()))
+        }
+    }
+
+    fn poll_interval_for(&self, override_ms: Option<u64>) -> Duration {
+        override_ms.map_or(self.poll_interval, Duration::from_millis)
+    }
+
+    /// Where a new clip's video is written: `<id>.mp4`, with the provider and model inserted
+    /// before the extension when those filename options are on. Existing clips are always
+    /// found through the path recorded in their metadata.
+    fn video_path(&self, local_id: &str, backend: ProviderKind, model: &str) -> PathBuf {
+        let mut name = local_id.to_string();
+        if self.include_backend_in_filename {
+            name.push('.');
+            name.push_str(backend.as_str());
+        }
+        if self.include_model_in_filename && !model.is_empty() {
+            name.push('.');
+            name.push_str(&model.replace(['/', '\\'], "_"));
+        }
+        self.data_dir.join(format!("{name}.mp4"))
+    }
+
+    /// Check a render's size and duration against the provider's capability table, filling
+    /// unset values from the manager's defaults. Useful for rejecting a multi-clip plan before
+    /// any of it is submitted.
+    pub fn validate_render(
+        &self,
+        model: Option<&str>,
+        size: Option<&str>,
+        seconds: Option<u32>,
+    ) -> Result<(), SoraError> {
+        let defaults = self.backend.defaults();
+        let model = model.unwrap_or(&defaults.model);
+        let size = match size {
+            Some(size) => size.to_string(),
+            None => defaults.size_for(self.backend.kind(), model),
+        };
+        self.check_render(model, &size, seconds.unwrap_or(defaults.seconds))
+    }
+
+    /// A per-request storage prefix only means something to Veo, and only as a `gs://` URI.
+    fn check_storage_uri(&self, storage_uri: Option<&str>) -> Result<(), SoraError> {
+        let Some(uri) = storage_uri else {
+            return Ok(());
+        };
+        if self.backend.kind() != ProviderKind::Veo {
+            return Err(SoraError::UnsupportedOperation(format!(
+                "storage_uri is only supported by Veo, not {:?}",
+                self.backend.kind()
+            )));
+        }
+        check_gs_uri("storage_uri", uri)
+    }
+
+    fn check_render(&self, model: &str, size: &str, seconds: u32) -> Result<(), SoraError> {
+        let provider = self.backend.kind();
+        provider.check_size(size)?;
+        if !self.skip_duration_validation {
+            provider.check_seconds(model, seconds)?;
+        }
+        Ok(())
+    }
+
+    /// Run the configured upload command for a saved clip. Failures leave the clip and its
+    /// metadata in place and are reported as [`SoraError::UploadFailed`].
+    async fn upload(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
+        let Some(template) = &self.upload_command else {
+            return Ok(());
+        };
+        let failed = |message: String| SoraError::UploadFailed {
+            local_id: metadata.local_id.clone(),
+            path: metadata.file_path.clone(),
+            message,
+        };
+        debug!(id = %metadata.local_id, "uploading clip");
+        // The values travel as positional arguments, never as script text, so nothing in a
+        // path or id can be run by the shell.
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(upload_script(template))
+            .arg("sh")
+            .arg(&metadata.file_path)
+            .arg(&metadata.local_id)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|err| failed(format!("unable to run upload command: {err}")))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(failed(format!(
+                "upload command exited with {}: {}",
+                output.status,
+                stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Persist a clip's metadata, first recording its checksum when checksums are enabled.
+    ///
+    /// The checksum is taken once, when the video is produced: a record that already has one
+    /// keeps it, so resaving never approves bytes that changed on disk since. Code that rewrites
+    /// a clip's video clears `sha256` before saving.
+    async fn save_metadata(&self, metadata: &mut VideoMetadata) -> Result<(), SoraError> {
+        self.record_checksum(metadata).await?;
+        self.store.save(metadata).await
+    }
+
+    /// [`VideoManager::save_metadata`] for a clip this call produced. Unless `overwrite`, the
+    /// id is claimed with [`MetadataStore::insert`], so a clip saved meanwhile under the same
+    /// id (by another process, say) is never replaced.
+    async fn save_new_metadata(
+        &self,
+        metadata: &mut VideoMetadata,
+        overwrite: bool,
+    ) -> Result<(), SoraError> {
+        if overwrite {
+            return self.save_metadata(metadata).await;
+        }
+        self.record_checksum(metadata).await?;
+        self.store.insert(metadata).await
+    }
+
+    /// Hash a clip's video when checksums are on and it hasn't been hashed yet. A pending
+    /// record has no video yet; it is hashed once finish_pending downloads it.
+    async fn record_checksum(&self, metadata: &mut VideoMetadata) -> Result<(), SoraError> {
+        if self.checksums && !metadata.pending && metadata.sha256.is_none() {
+            metadata.sha256 = Some(sha256_file(&metadata.file_path).await?);
+        }
+        Ok(())
+    }
+
+    /// Apply a [`MetadataPatch`] to a stored clip and save the result. The recorded checksum is
+    /// left alone, since only the metadata changes.
+    pub async fn update_metadata(
+        &self,
+        local_id: &str,
+        patch: MetadataPatch,
+    ) -> Result<VideoMetadata, SoraError> {
+        if patch.is_empty() {
+            return Err(SoraError::InvalidConfig(
+                "metadata patch changes nothing".to_string(),
+            ));
+        }
+        let mut metadata = self.load_metadata(local_id).await?;
+        if let Some(prompt) = patch.prompt {
+            check_prompt(&prompt, metadata.parent.is_some())?;
+            metadata.prompt = prompt;
+        }
+        if let Some(model) = patch.model {
+            if model.trim().is_empty() {
+                return Err(SoraError::InvalidConfig(
+                    "model must not be empty".to_string(),
+                ));
+            }
+            metadata.model = model;
+        }
+        if let Some(backend) = patch.backend {
+            metadata.backend = backend;
+        }
+        if let Some(notes) = patch.notes {
+            let notes = notes.trim();
+            metadata.notes = (!notes.is_empty()).then(|| notes.to_string());
+        }
+        for tag in patch.add_tags {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                return Err(SoraError::InvalidConfig(
+                    "tags must not be empty".to_string(),
+                ));
+            }
+            if !metadata.tags.iter().any(|existing| existing == tag) {
+                metadata.tags.push(tag.to_string());
+            }
+        }
+        metadata.tags.retain(|tag| {
+            !patch
+                .remove_tags
+                .iter()
+                .any(|removed| removed.trim() == tag)
+        });
+        check_extra(&patch.set_extra)?;
+        metadata.extra.extend(patch.set_extra);
+        for key in &patch.remove_extra {
+            metadata.extra.remove(key);
+        }
+        self.store.save(&metadata).await?;
+        Ok(metadata)
+    }
+
+    async fn load_metadata(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        self.store.load(local_id).await
+    }
+
+    /// Under [`ContinuatorConfig::print_ffmpeg`], end an operation once its ffmpeg commands are
+    /// printed: their outputs don't exist, so nothing after this point may save, upload, or
+    /// call the provider.
+    fn stop_if_printing(&self) -> Result<(), SoraError> {
+        if self.print_ffmpeg {
+            return Err(SoraError::CommandsPrinted);
+        }
+        Ok(())
+    }
+
+    /// Metadata for a clip whose video is on disk, rejecting one still waiting on its render.
+    async fn load_finished(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        let metadata = self.load_metadata(local_id).await?;
+        if metadata.pending {
+            return Err(SoraError::InvalidConfig(format!(
+                "clip '{local_id}' is still pending; collect it with finish_pending first"
+            )));
+        }
+        Ok(metadata)
+    }
+
+    /// Fetch the metadata for a given local identifier.
+    pub async fn get_metadata(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        self.load_metadata(local_id).await
+    }
+
+    /// Download a variant of the rendered asset (video, thumbnail, spritesheet).
+    ///
+    /// Relative output paths resolve under the data directory, like every other file the
+    /// manager writes; absolute paths are used as-is. Returns the path actually written.
+    pub async fn download_asset(
+        &self,
+        local_id: &str,
+        variant: VideoVariant,
+        output_path: &Path,
+    ) -> Result<PathBuf, SoraError> {
+        self.download_asset_with(local_id, variant, output_path, &DownloadOptions::default())
+            .await
+    }
+
+    /// [`VideoManager::download_asset`] with per-call options.
+    ///
+    /// Provider downloads are checked against the server's Content-Length and fail with
+    /// [`SoraError::IncompleteDownload`] when short, leaving the partial file for a resume.
+    pub async fn download_asset_with(
+        &self,
+        local_id: &str,
+        variant: VideoVariant,
+        output_path: &Path,
+        options: &DownloadOptions,
+    ) -> Result<PathBuf, SoraError> {
+        let metadata = self.load_metadata(local_id).await?;
+        let output_path = &self.data_dir.join(output_path);
+        if let Some(parent) = output_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).await?;
+        }
+
+        if metadata.backend == ProviderKind::Veo && matches!(variant, VideoVariant::Video) {
+            fs::copy(&metadata.file_path, output_path).await?;
+            return Ok(output_path.clone());
+        }
+
+        self.backend
+            .download(&metadata.remote_id, variant, output_path, options.resume)
+            .await?;
+
+        if let (VideoVariant::Thumbnail, Some((width, height))) = (variant, self.thumbnail_size) {
+            resize_image(output_path, width, height, self.print_ffmpeg).await?;
+            self.stop_if_printing()?;
+        }
+        Ok(output_path.clone())
+    }
+
+    /// Stream a variant of the rendered asset into `writer` instead of a file.
+    ///
+    /// The main video is served from the local copy; other variants are streamed straight from
+    /// the provider.
+    pub async fn download_asset_to_writer<W>(
+        &self,
+        local_id: &str,
+        variant: VideoVariant,
+        writer: &mut W,
+    ) -> Result<(), SoraError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let metadata = self.load_metadata(local_id).await?;
+        if matches!(variant, VideoVariant::Video) && metadata.file_path.exists() {
+            return copy_file_to_writer(&metadata.file_path, writer).await;
+        }
+
+        self.backend
+            .download_to_writer(&metadata.remote_id, variant, writer)
+            .await
+    }
+
+    /// Like [`VideoManager::create_video`], but also streams the finished MP4 into `writer`.
+    /// Metadata and the local copy are still persisted.
+    pub async fn create_video_to_writer<W>(
+        &self,
+        request: CreateVideoRequest,
+        writer: &mut W,
+    ) -> Result<VideoMetadata, SoraError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let metadata = self.create_video(request).await?;
+        copy_file_to_writer(&metadata.file_path, writer).await?;
+        Ok(metadata)
+    }
+
+    /// Like [`VideoManager::continue_video`], but also streams the finished MP4 into `writer`.
+    pub async fn continue_video_to_writer<W>(
+        &self,
+        request: ContinueVideoRequest,
+        writer: &mut W,
+    ) -> Result<VideoMetadata, SoraError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let metadata = self.continue_video(request).await?;
+        copy_file_to_writer(&metadata.file_path, writer).await?;
+        Ok(metadata)
+    }
+
+    /// Generate a brand-new clip using the configured backend and persist the results locally.
+    pub async fn create_video(
+        &self,
+        mut request: CreateVideoRequest,
+    ) -> Result<VideoMetadata, SoraError> {
+        let reference = request.reference.take();
+        if let Some(reference) = &reference {
+            reference.validate()?;
+        }
+        self.create_with_first_frame(request, reference.as_ref())
+            .await
+    }
+
+    /// Create a clip seeded with the last frame of an arbitrary, untracked video file.
+    async fn create_from_video_file(
+        &self,
+        video_path: &Path,
+        request: CreateVideoRequest,
+    ) -> Result<VideoMetadata, SoraError> {
+        if !video_path.exists() {
+            return Err(SoraError::VideoNotFound(video_path.display().to_string()));
+        }
+        self.ensure_data_dir().await?;
+        let frame = self
+            .extract_seed_frame(video_path, &request.local_id, FrameTime::Last)
+            .await?;
+        let reference = ReferenceSource::Path(frame.path().to_path_buf());
+        self.create_with_first_frame(request, Some(&reference))
+            .await
+    }
+
+    /// Render a throwaway clip for prompt testing and return where it landed, under
+    /// `<temp_dir>/scratch/`. No metadata is written and ids aren't checked, so the project's
+    /// clips are untouched; `local_id` is optional and only names the file.
+    pub async fn create_scratch(
+        &self,
+        mut request: CreateVideoRequest,
+    ) -> Result<PathBuf, SoraError> {
+        let reference = request.reference.take();
+        if let Some(reference) = &reference {
+            reference.validate()?;
+        }
+        check_prompt(&request.prompt, reference.is_some())?;
+        self.check_storage_uri(request.storage_uri.as_deref())?;
+        let (prompt, _) = self.wrap_prompt(&request.prompt, request.no_prompt_wrap);
+        let (model, size, seconds) = self.create_settings(&request)?;
+
+        let scratch_dir = self.temp_dir.join("scratch");
+        fs::create_dir_all(&scratch_dir).await?;
+        let stem = if request.local_id.is_empty() {
+            quick_local_id()
+        } else {
+            request.local_id.clone()
+        };
+        let video_path = scratch_dir.join(format!("{stem}.mp4"));
+        self.stop_if_printing()?;
+        self.backend
+            .render(RenderContext {
+                prompt: &prompt,
+                model: &model,
+                seconds,
+                size: &size,
+                poll_interval: self.poll_interval_for(request.poll_interval_ms),
+                output_path: &video_path,
+                first_frame: reference.as_ref(),
+                storage_uri: request.storage_uri.as_deref(),
+            })
+            .await?;
+        Ok(video_path)
+    }
+
+    /// What [`VideoManager::create_video`] would submit for `request`: the final prompt and
+    /// the model, size, and duration after defaults, with a rough cost. Nothing is sent.
+    pub fn plan_create(&self, request: &CreateVideoRequest) -> Result<RenderPlan, SoraError> {
+        check_prompt(&request.prompt, request.reference.is_some())?;
+        let (prompt, _) = self.wrap_prompt(&request.prompt, request.no_prompt_wrap);
+        Ok(RenderPlan::new(
+            self.backend.kind(),
+            prompt,
+            self.create_settings(request)?,
+            None,
+        ))
+    }
+
+    /// What [`VideoManager::continue_video`] would submit for `request`, with settings the
+    /// request leaves unset taken from the parent clip. Nothing is sent.
+    pub async fn plan_continue(
+        &self,
+        request: &ContinueVideoRequest,
+    ) -> Result<RenderPlan, SoraError> {
+        check_prompt(&request.prompt, true)?;
+        let (prompt, _) = self.wrap_prompt(&request.prompt, request.no_prompt_wrap);
+        let parent = self.load_metadata(&request.parent_local_id).await?;
+        Ok(RenderPlan::new(
+            self.backend.kind(),
+            prompt,
+            self.continue_settings(request, &parent)?,
+            Some(parent.local_id),
+        ))
+    }
+
+    /// Model, size, and duration for a continuation: the request's, else the parent's, else
+    /// the manager's defaults, checked against the provider.
+    fn continue_settings(
+        &self,
+        request: &ContinueVideoRequest,
+        parent: &VideoMetadata,
+    ) -> Result<(String, String, u32), SoraError> {
+        let defaults = self.backend.defaults();
+        let model = request
+            .model
+            .as_ref()
+            .or(Some(&parent.model))
+            .unwrap_or(&defaults.model)
+            .to_string();
+        let size = request
+            .size
+            .as_ref()
+            .or(Some(&parent.size))
+            .unwrap_or(&defaults.size)
+            .to_string();
+        let seconds = request
+            .seconds
+            .or(Some(parent.seconds))
+            .unwrap_or(defaults.seconds);
+        self.check_render(&model, &size, seconds)?;
+        Ok((model, size, seconds))
+    }
+
+    /// Model, size, and duration for a fresh render, with unset values taken from the
+    /// manager's defaults and the result checked against the provider.
+    fn create_settings(
+        &self,
+        request: &CreateVideoRequest,
+    ) -> Result<(String, String, u32), SoraError> {
+        let defaults = self.backend.defaults();
+        let model = request
+            .model
+            .as_deref()
+            .unwrap_or(&defaults.model)
+            .to_string();
+        let size = request
+            .size
+            .clone()
+            .unwrap_or_else(|| defaults.size_for(self.backend.kind(), &model));
+        let seconds = request.seconds.unwrap_or(defaults.seconds);
+        self.check_render(&model, &size, seconds)?;
+        Ok((model, size, seconds))
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(local_id = %request.local_id, backend = self.backend.kind().as_str(), remote_id)
+    )]
+    async fn create_with_first_frame(
+        &self,
+        request: CreateVideoRequest,
+        first_frame: Option<&ReferenceSource>,
+    ) -> Result<VideoMetadata, SoraError> {
+        check_local_id(&request.local_id)?;
+        check_prompt(&request.prompt, first_frame.is_some())?;
+        check_extra(&request.extra)?;
+        self.check_storage_uri(request.storage_uri.as_deref())?;
+        let (prompt, original_prompt) = self.wrap_prompt(&request.prompt, request.no_prompt_wrap);
+        self.ensure_data_dir().await?;
+        if self.store.exists(&request.local_id).await? {
+            return Err(SoraError::InvalidConfig(format!(
+                "local id '{}' already exists",
+                request.local_id
+            )));
+        }
+
+        let (model, size, seconds) = self.create_settings(&request)?;
+
+        let video_path = self.video_path(&request.local_id, self.backend.kind(), &model);
+        let outcome = self
+            .render_or_submit(
+                RenderContext {
+                    prompt: &prompt,
+                    model: &model,
+                    seconds,
+                    size: &size,
+                    poll_interval: self.poll_interval_for(request.poll_interval_ms),
+                    output_path: &video_path,
+                    first_frame,
+                    storage_uri: request.storage_uri.as_deref(),
+                },
+                request.no_wait,
+            )
+            .await?;
+        tracing::Span::current().record("remote_id", outcome.remote_id.as_str());
+        let duration_delta = if request.no_wait {
+            None
+        } else {
+            self.measure_duration_delta(&video_path, seconds).await
+        };
+
+        let mut metadata = VideoMetadata {
+            local_id: request.local_id,
+            remote_id: outcome.remote_id,
+            prompt,
+            model: outcome.model,
+            seconds: outcome.seconds,
+            size: outcome.size,
+            created_at: outcome.created_at,
+            created_at_source: outcome.created_at_source,
+            seconds_source: outcome.seconds_source,
+            file_path: video_path,
+            parent: None,
+            backend: self.backend.kind(),
+            duration_delta,
+            sources: Vec::new(),
+            remix_of: None,
+            sha256: None,
+            seed_source: None,
+            tags: Vec::new(),
+            seed_frame: None,
+            samples: outcome.samples,
+            storage_uris: outcome.storage_uris,
+            inline_bytes: outcome.inline_bytes,
+            local_created_at: recording_millis(),
+            carried_audio: None,
+            pending: request.no_wait,
+            notes: request.notes,
+            original_prompt,
+            extra: request.extra,
+        };
+
+        self.save_new_metadata(&mut metadata, false).await?;
+        if !metadata.pending {
+            self.enforce_duration(&metadata, seconds)?;
+            self.upload(&metadata).await?;
+        }
+        Ok(metadata)
+    }
+
+    async fn render_or_submit(
+        &self,
+        ctx: RenderContext<'_>,
+        no_wait: bool,
+    ) -> Result<RenderOutcome, SoraError> {
+        self.stop_if_printing()?;
+        if no_wait {
+            return self.backend.submit(ctx).await;
+        }
+        let mut attempt = 1;
+        loop {
+            match self.backend.render(ctx).await {
+                Err(err) if err.is_render_failure() && attempt <= self.render_retries => {
+                    warn!(
+                        attempt,
+                        retries = self.render_retries,
+                        error = %err,
+                        "render failed on the provider, resubmitting"
+                    );
+                    attempt += 1;
+                }
+                Err(err) if attempt > 1 => return Err(err.after_attempts(attempt)),
+                result => return result,
+            }
+        }
+    }
+
+    /// Wait for a clip saved with `no_wait`, download its video, and clear the pending flag.
+    /// Duration checks and uploads that a waiting render would have run happen here instead.
+    #[tracing::instrument(
+        skip_all,
+        fields(local_id = %local_id, backend = self.backend.kind().as_str(), remote_id)
+    )]
+    pub async fn finish_pending(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        let mut metadata = self.load_metadata(local_id).await?;
+        if !metadata.pending {
+            return Err(SoraError::InvalidConfig(format!(
+                "clip '{local_id}' is not pending"
+            )));
+        }
+        if metadata.backend != self.backend.kind() {
+            return Err(SoraError::InvalidConfig(format!(
+                "clip '{local_id}' was submitted to {:?}, not {:?}",
+                metadata.backend,
+                self.backend.kind()
+            )));
+        }
+
+        tracing::Span::current().record("remote_id", metadata.remote_id.as_str());
+        let requested_seconds = metadata.seconds;
+        let outcome = self
+            .backend
+            .resume(
+                &metadata.remote_id,
+                self.poll_interval,
+                &metadata.file_path,
+                metadata.seconds,
+                &metadata.size,
+            )
+            .await?;
+        metadata.model = outcome.model;
+        metadata.seconds = outcome.seconds;
+        metadata.seconds_source = outcome.seconds_source;
+        metadata.size = outcome.size;
+        // Keep the submission time unless the provider reports its own.
+        if outcome.created_at_source == ValueSource::Provider {
+            metadata.created_at = outcome.created_at;
+            metadata.created_at_source = outcome.created_at_source;
+        }
+        metadata.samples = outcome.samples;
+        metadata.storage_uris = outcome.storage_uris;
+        metadata.inline_bytes = outcome.inline_bytes;
+        metadata.duration_delta = self
+            .measure_duration_delta(&metadata.file_path, requested_seconds)
+            .await;
+        metadata.pending = false;
+
+        self.save_metadata(&mut metadata).await?;
+        self.enforce_duration(&metadata, requested_seconds)?;
+        self.upload(&metadata).await?;
+        Ok(metadata)
+    }
+
+    /// Create a continuation using the last frame of an existing clip as an image reference.
+    #[tracing::instrument(
+        skip_all,
+        fields(local_id = %request.local_id, backend = self.backend.kind().as_str(), remote_id)
+    )]
+    pub async fn continue_video(
+        &self,
+        request: ContinueVideoRequest,
+    ) -> Result<VideoMetadata, SoraError> {
+        check_local_id(&request.local_id)?;
+        check_prompt(&request.prompt, true)?;
+        check_extra(&request.extra)?;
+        self.check_storage_uri(request.storage_uri.as_deref())?;
+        let (prompt, original_prompt) = self.wrap_prompt(&request.prompt, request.no_prompt_wrap);
+        self.ensure_data_dir().await?;
+        if self.store.exists(&request.local_id).await? {
+            return Err(SoraError::InvalidConfig(format!(
+                "local id '{}' already exists",
+                request.local_id
+            )));
+        }
+        let parent = self.load_finished(&request.parent_local_id).await?;
+        self.check_provider(&parent)?;
+        let seed_id = request
+            .seed_from
+            .clone()
+            .unwrap_or_else(|| request.parent_local_id.clone());
+        let seed_video_path = if seed_id == parent.local_id {
+            parent.file_path.clone()
+        } else {
+            self.load_finished(&seed_id).await?.file_path
+        };
+
+        if !seed_video_path.exists() {
+            return Err(SoraError::VideoNotFound(seed_id));
+        }
+
+        let (model, size, seconds) = self.continue_settings(&request, &parent)?;
+        let seed_time = match request.seed_at {
+            Some(spec) => FrameTime::At(resolve_time(&seed_video_path, spec).await?),
+            None => FrameTime::Last,
+        };
+        let last_frame = self
+            .extract_seed_frame(&seed_video_path, &request.local_id, seed_time)
+            .await?;
+        let tail_audio = if request.carry_audio {
+            self.extract_tail_audio(&parent, &request.local_id).await?
+        } else {
+            None
+        };
+
+        let video_path = self.video_path(&request.local_id, self.backend.kind(), &model);
+        let reference = ReferenceSource::Path(last_frame.path().to_path_buf());
+        let outcome = self
+            .render_or_submit(
+                RenderContext {
+                    prompt: &prompt,
+                    model: &model,
+                    seconds,
+                    size: &size,
+                    poll_interval: self.poll_interval_for(request.poll_interval_ms),
+                    output_path: &video_path,
+                    first_frame: Some(&reference),
+                    storage_uri: request.storage_uri.as_deref(),
+                },
+                request.no_wait,
+            )
+            .await?;
+        tracing::Span::current().record("remote_id", outcome.remote_id.as_str());
+        let duration_delta = if request.no_wait {
+            None
+        } else {
+            self.measure_duration_delta(&video_path, seconds).await
+        };
+        let seed_frame = if self.keep_seed_frame {
+            let extension = last_frame
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or(self.frame_options.format.extension());
+            let path = self
+                .data_dir
+                .join(format!("{}.seed.{extension}", request.local_id));
+            last_frame.persist(&path).await?;
+            Some(path)
+        } else {
+            None
+        };
+        let carried_audio = match tail_audio {
+            Some(tail) => {
+                let path = self
+                    .data_dir
+                    .join(format!("{}.carry.m4a", request.local_id));
+                tail.persist(&path).await?;
+                Some(path)
+            }
+            None => None,
+        };
+
+        let mut metadata = VideoMetadata {
+            local_id: request.local_id,
+            remote_id: outcome.remote_id,
+            prompt,
+            model: outcome.model,
+            seconds: outcome.seconds,
+            size: outcome.size,
+            created_at: outcome.created_at,
+            created_at_source: outcome.created_at_source,
+            seconds_source: outcome.seconds_source,
+            file_path: video_path,
+            parent: Some(parent.local_id),
+            backend: self.backend.kind(),
+            duration_delta,
+            sources: Vec::new(),
+            remix_of: None,
+            sha256: None,
+            seed_source: Some(seed_id),
+            tags: Vec::new(),
+            seed_frame,
+            samples: outcome.samples,
+            storage_uris: outcome.storage_uris,
+            inline_bytes: outcome.inline_bytes,
+            local_created_at: recording_millis(),
+            carried_audio,
+            pending: request.no_wait,
+            notes: request.notes,
+            original_prompt,
+            extra: request.extra,
+        };
+
+        self.save_new_metadata(&mut metadata, false).await?;
+        if !metadata.pending {
+            self.enforce_duration(&metadata, seconds)?;
+            self.upload(&metadata).await?;
+        }
+        Ok(metadata)
+    }
+
+    /// Check a whole flow without rendering anything, collecting every problem instead of
+    /// stopping at the first: each beat's model, size, duration, and prompt, the beat ids, the
+    /// starting clip, and that each beat's parent will be on disk when the beat is reached
+    /// (a beat that already exists is only usable when resuming).
+    pub async fn validate_flow(&self, request: &FlowRequest) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let mut flag =
+            |beat: Option<usize>, error: SoraError| issues.push(ValidationIssue { beat, error });
+        if request.beats.is_empty() {
+            flag(
+                None,
+                SoraError::InvalidConfig("flow requires at least one beat".to_string()),
+            );
+        }
+
+        let mut has_parent = false;
+        if let Some(start) = &request.start_from {
+            match self.load_metadata(start).await {
+                Ok(metadata) if !metadata.file_path.exists() => {
+                    flag(None, SoraError::VideoNotFound(start.clone()))
+                }
+                Ok(metadata) => {
+                    has_parent = true;
+                    if let Err(error) = self.check_provider(&metadata) {
+                        flag(None, error);
+                    }
+                }
+                Err(error) => flag(None, error),
+            }
+        }
+
+        let beat_ids = match flow_beat_ids(request) {
+            Ok(ids) => ids.into_iter().map(Some).collect(),
+            Err(error) => {
+                flag(None, error);
+                vec![None; request.beats.len()]
+            }
+        };
+        let known_ids: Vec<String> = beat_ids.iter().flatten().cloned().collect();
+        if known_ids.len() == request.beats.len()
+            && let Err(error) = flow_graph_parents(request, &known_ids)
+        {
+            flag(None, error);
+        }
+        for (index, (beat, local_id)) in request.beats.iter().zip(beat_ids).enumerate() {
+            let number = Some(index + 1);
+            if let Some(depends_on) = &beat.depends_on
+                && !known_ids.contains(depends_on)
+                && !self.store.exists(depends_on).await.unwrap_or(false)
+            {
+                flag(number, SoraError::MetadataNotFound(depends_on.clone()));
+            }
+            if let Err(error) =
+                self.validate_render(beat.model.as_deref(), beat.size.as_deref(), beat.seconds)
+            {
+                flag(number, error);
+            }
+            if let Err(error) = check_prompt(&beat.prompt, has_parent) {
+                flag(number, error);
+            }
+            has_parent = true;
+
+            let Some(local_id) = local_id else {
+                continue;
+            };
+            match self.load_metadata(&local_id).await {
+                Ok(_) if !request.resume => flag(
+                    number,
+                    SoraError::InvalidConfig(format!(
+                        "local id '{local_id}' already exists (pass resume to reuse it)"
+                    )),
+                ),
+                // The next beat continues from this one's video.
+                Ok(metadata) if !metadata.file_path.exists() => {
+                    flag(number, SoraError::VideoNotFound(local_id))
+                }
+                Ok(_) | Err(SoraError::MetadataNotFound(_)) => {}
+                Err(error) => flag(number, error),
+            }
+        }
+        issues
+    }
+
+    /// Render each beat of a flow as a continuation of the one before, then stitch them.
+    ///
+    /// Every beat is validated before the first is submitted. A failing beat stops the flow
+    /// without discarding earlier ones: they are reported, stitched into `<id>-partial`, and
+    /// can be reused by rerunning with [`FlowRequest::resume`]. `on_beat` is called as each beat
+    /// completes. With [`FlowRequest::skip_stitch`] the beats are only rendered and saved.
+    pub async fn run_flow(
+        &self,
+        mut request: FlowRequest,
+        mut on_beat: impl FnMut(&VideoMetadata) + Send,
+    ) -> Result<FlowReport, SoraError> {
+        let beat_ids = self.check_flow_beats(&request)?;
+
+        let mut report = FlowReport::default();
+        let mut previous = request.start_from.clone();
+        let beats = std::mem::take(&mut request.beats);
+        for (index, (beat, local_id)) in beats.into_iter().zip(beat_ids).enumerate() {
+            let result = self
+                .render_beat(&request, beat, local_id.clone(), previous.clone())
+                .await;
+            match result {
+                Ok(metadata) => {
+                    on_beat(&metadata);
+                    previous = Some(metadata.local_id.clone());
+                    report.completed.push(metadata);
+                }
+                Err(error) => {
+                    report.failure = Some(FlowFailure {
+                        beat: index + 1,
+                        local_id,
+                        error,
+                    });
+                    break;
+                }
+            }
+        }
+
+        self.stitch_flow(&request, &mut report).await;
+        Ok(report)
+    }
+
+    /// Render a branching flow: each beat continues its [`FlowBeat::depends_on`] clip (the
+    /// starting clip when unset, or nothing without one), and beats whose parents are ready
+    /// render concurrently, up to [`FlowRequest::concurrency`] at once. A beat started while
+    /// others are rendering first waits a short random delay, so submissions don't arrive in a
+    /// burst.
+    ///
+    /// Dependencies outside the flow must name saved clips, and cycles are rejected, before
+    /// anything renders. Once a beat fails no new beats start, though those already rendering
+    /// finish. Reporting, resuming, and stitching work as in [`VideoManager::run_flow`], with
+    /// beats stitched in the order they are listed.
+    pub async fn run_flow_graph(
+        &self,
+        mut request: FlowRequest,
+        mut on_beat: impl FnMut(&VideoMetadata) + Send,
+    ) -> Result<FlowReport, SoraError> {
+        let beat_ids = self.check_flow_beats(&request)?;
+        let parents = flow_graph_parents(&request, &beat_ids)?;
+        for beat in &request.beats {
+            if let Some(depends_on) = &beat.depends_on
+                && !beat_ids.contains(depends_on)
+                && !self.store.exists(depends_on).await?
+            {
+                return Err(SoraError::MetadataNotFound(depends_on.clone()));
+            }
+        }
+        let concurrency = request.concurrency.unwrap_or(DEFAULT_FLOW_CONCURRENCY);
+        if concurrency == 0 {
+            return Err(SoraError::InvalidConfig(
+                "flow concurrency must be at least 1".to_string(),
+            ));
+        }
+
+        let mut pending: Vec<Option<FlowBeat>> = std::mem::take(&mut request.beats)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut finished: Vec<Option<VideoMetadata>> = vec![None; pending.len()];
+        let mut failure = None;
+        let mut running = futures_util::stream::FuturesUnordered::new();
+        loop {
+            for index in 0..pending.len() {
+                if failure.is_some() || running.len() >= concurrency {
+                    break;
+                }
+                if parents[index].is_some_and(|parent| finished[parent].is_none()) {
+                    continue;
+                }
+                let Some(beat) = pending[index].take() else {
+                    continue;
+                };
+                let parent = beat
+                    .depends_on
+                    .clone()
+                    .or_else(|| request.start_from.clone());
+                let delay = if running.is_empty() {
+                    Duration::ZERO
+                } else {
+                    Duration::from_millis(fastrand::u64(..=FLOW_START_JITTER_MS))
+                };
+                let local_id = beat_ids[index].clone();
+                running.push(self.start_flow_beat(&request, index, delay, beat, local_id, parent));
+            }
+            let Some((index, result)) = running.next().await else {
+                break;
+            };
+            match result {
+                Ok(metadata) => {
+                    on_beat(&metadata);
+                    finished[index] = Some(metadata);
+                }
+                Err(error) if failure.is_none() => {
+                    failure = Some(FlowFailure {
+                        beat: index + 1,
+                        local_id: beat_ids[index].clone(),
+                        error,
+                    });
+                }
+                Err(error) => {
+                    warn!(beat = index + 1, error = %error, "another flow beat failed");
+                }
+            }
+        }
+
+        let mut report = FlowReport {
+            completed: finished.into_iter().flatten().collect(),
+            failure,
+            ..FlowReport::default()
+        };
+        self.stitch_flow(&request, &mut report).await;
+        Ok(report)
+    }
+
+    /// Create every clip listed in a batch file (see [`BatchJob`]), rendering up to
+    /// `concurrency` at once, and write a results file beside it (see
+    /// [`BatchReport::results_path`]).
+    ///
+    /// Safe to re-run after a partial failure: rows whose clip is already saved are skipped,
+    /// and ones submitted without waiting are collected. A failed row doesn't stop the others.
+    /// The file itself is checked first, including for duplicate ids, so a malformed row fails
+    /// the batch before anything renders.
+    pub async fn run_batch(
+        &self,
+        path: &Path,
+        concurrency: usize,
+    ) -> Result<BatchReport, SoraError> {
+        if concurrency == 0 {
+            return Err(SoraError::InvalidConfig(
+                "batch concurrency must be at least 1".to_string(),
+            ));
+        }
+        let contents = fs::read_to_string(path).await.map_err(|err| {
+            SoraError::InvalidConfig(format!("cannot read batch {}: {err}", path.display()))
+        })?;
+        let jobs = parse_batch(path, &contents)?;
+        for (line, job) in &jobs {
+            self.validate_render(job.model.as_deref(), job.size.as_deref(), job.seconds)
+                .map_err(|error| {
+                    SoraError::InvalidConfig(format!(
+                        "{}:{line}: '{}': {error}",
+                        path.display(),
+                        job.id
+                    ))
+                })?;
+        }
+
+        // Rows finish in any order; a slow one shouldn't hold a slot once the rest are done.
+        let mut results: Vec<(usize, BatchResult)> =
+            futures_util::stream::iter(jobs.into_iter().map(|(_, job)| job).enumerate())
+                .map(|(index, job)| async move {
+                    let id = job.id.clone();
+                    let (status, result) = match self.store.exists(&id).await {
+                        Ok(true) => match self.store.load(&id).await {
+                            Ok(metadata) if !metadata.pending => (BatchStatus::Skipped, Ok(())),
+                            Ok(_) => (
+                                BatchStatus::Rendered,
+                                self.finish_pending(&id).await.map(drop),
+                            ),
+                            Err(error) => (BatchStatus::Failed, Err(error)),
+                        },
+                        Ok(false) => (BatchStatus::Rendered, self.render_batch_job(job).await),
+                        Err(error) => (BatchStatus::Failed, Err(error)),
+                    };
+                    let result = match result {
+                        Ok(()) => BatchResult {
+                            id,
+                            status,
+                            error: None,
+                        },
+                        Err(error) => {
+                            warn!(id = %id, error = %error, "batch job failed");
+                            BatchResult {
+                                id,
+                                status: BatchStatus::Failed,
+                                error: Some(error.to_string()),
+                            }
+                        }
+                    };
+                    (index, result)
+                })
+                .buffer_unordered(concurrency)
+                .collect()
+                .await;
+        results.sort_by_key(|(index, _)| *index);
+        let results: Vec<BatchResult> = results.into_iter().map(|(_, result)| result).collect();
+
+        let jsonl = is_jsonl(path);
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("batch");
+        let results_path = path.with_file_name(format!(
+            "{stem}.results.{}",
+            if jsonl { "jsonl" } else { "csv" }
+        ));
+        fs::write(&results_path, format_batch_results(jsonl, &results)?)
+            .await
+            .map_err(|err| SoraError::Io(err).writing(&results_path))?;
+        Ok(BatchReport {
+            results,
+            results_path,
+        })
+    }
+
+    async fn render_batch_job(&self, job: BatchJob) -> Result<(), SoraError> {
+        self.create_video(CreateVideoRequest {
+            local_id: job.id,
+            prompt: job.prompt,
+            model: job.model,
+            size: job.size,
+            seconds: job.seconds,
+            ..CreateVideoRequest::default()
+        })
+        .await
+        .map(drop)
+    }
+
+    /// Validate every beat of a flow before any renders, returning the beat ids.
+    fn check_flow_beats(&self, request: &FlowRequest) -> Result<Vec<String>, SoraError> {
+        if request.beats.is_empty() {
+            return Err(SoraError::InvalidConfig(
+                "flow requires at least one beat".to_string(),
+            ));
+        }
+        for beat in &request.beats {
+            self.validate_render(beat.model.as_deref(), beat.size.as_deref(), beat.seconds)?;
+        }
+        flow_beat_ids(request)
+    }
+
+    /// Render one flow beat as a continuation of `parent`, or from scratch without one. A resumed
+    /// flow reuses the beat instead if it was saved by an earlier run, as long as the saved clip
+    /// has the same parent and prompt; a clip that doesn't match is an error rather than
+    /// silently standing in for the beat.
+    async fn render_beat(
+        &self,
+        request: &FlowRequest,
+        beat: FlowBeat,
+        local_id: String,
+        parent: Option<String>,
+    ) -> Result<VideoMetadata, SoraError> {
+        if request.resume && self.store.exists(&local_id).await? {
+            let saved = self.load_metadata(&local_id).await?;
+            let saved_prompt = saved.original_prompt.as_deref().unwrap_or(&saved.prompt);
+            if saved.parent != parent {
+                return Err(SoraError::InvalidConfig(format!(
+                    "cannot resume with '{local_id}': it continues {}, but this beat continues {}",
+                    saved.parent.as_deref().unwrap_or("nothing"),
+                    parent.as_deref().unwrap_or("nothing"),
+                )));
+            }
+            if saved_prompt != beat.prompt {
+                return Err(SoraError::InvalidConfig(format!(
+                    "cannot resume with '{local_id}': it was rendered from a different prompt \
+                     ('{saved_prompt}')"
+                )));
+            }
+            debug!(id = %local_id, "reusing beat from an earlier run");
+            return Ok(saved);
+        }
+        match parent {
+            Some(parent_local_id) => {
+                self.continue_video(ContinueVideoRequest {
+                    parent_local_id,
+                    local_id,
+                    prompt: beat.prompt,
+                    model: beat.model,
+                    size: beat.size,
+                    seconds: beat.seconds,
+                    poll_interval_ms: request.poll_interval_ms,
+                    ..ContinueVideoRequest::default()
+                })
+                .await
+            }
+            None => {
+                self.create_video(CreateVideoRequest {
+                    local_id,
+                    prompt: beat.prompt,
+                    model: beat.model,
+                    size: beat.size,
+                    seconds: beat.seconds,
+                    poll_interval_ms: request.poll_interval_ms,
+                    reference: None,
+                    no_wait: false,
+                    notes: None,
+                    extra: serde_json::Map::new(),
+                    no_prompt_wrap: false,
+                    storage_uri: None,
+                })
+                .await
+            }
+        }
+    }
+
+    /// A graph flow beat, after its start delay, tagged with its index.
+    async fn start_flow_beat(
+        &self,
+        request: &FlowRequest,
+        index: usize,
+        delay: Duration,
+        beat: FlowBeat,
+        local_id: String,
+        parent: Option<String>,
+    ) -> (usize, Result<VideoMetadata, SoraError>) {
+        if !delay.is_zero() {
+            self.backend.sleeper().sleep(delay).await;
+        }
+        let result = self.render_beat(request, beat, local_id, parent).await;
+        (index, result)
+    }
+
+    /// Stitch a flow's starting clip and completed beats into `<id>`, or `<id>-partial` after a
+    /// failure, unless the flow skips stitching or nothing completed.
+    async fn stitch_flow(&self, request: &FlowRequest, report: &mut FlowReport) {
+        if report.completed.is_empty() || request.skip_stitch {
+            return;
+        }
+        let mut clips: Vec<String> = request.start_from.iter().cloned().collect();
+        clips.extend(report.completed.iter().map(|clip| clip.local_id.clone()));
+        let output_id = if report.failure.is_some() {
+            format!("{}-partial", request.id)
+        } else {
+            request.id.clone()
+        };
+        let mut owned = flow_beat_ids(request).unwrap_or_default();
+        owned.extend(clips.iter().cloned());
+        // A resumed run must replace this flow's last stitch, but never an unrelated clip
+        // that happens to share its id.
+        let result = match self.load_metadata(&output_id).await {
+            Ok(existing)
+                if existing.sources.is_empty()
+                    || !existing.sources.iter().all(|source| owned.contains(source)) =>
+            {
+                Err(SoraError::InvalidConfig(format!(
+                    "local id '{output_id}' already exists and wasn't stitched from this flow's \
+                     beats; rename the flow or remove the clip"
+                )))
+            }
+            Ok(_) | Err(SoraError::MetadataNotFound(_)) => {
+                let options = StitchOptions {
+                    overwrite: true,
+                    ..StitchOptions::default()
+                };
+                self.stitch_videos_with(&output_id, &clips, &options).await
+            }
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(path) => report.stitched = Some(path),
+            Err(error) => report.stitch_error = Some(error),
+        }
+    }
+
+    /// Clips of a flow in stitch order, found from their ids: every saved clip the beat name
+    /// template (the default when `None`) produces for `base`, sorted by beat number, preceded
+    /// by the first beat's parent when it is not itself a beat (the flow's starting clip).
+    /// Gaps in the numbering are logged and skipped.
+    pub async fn flow_clips(
+        &self,
+        base: &str,
+        beat_name_template: Option<&str>,
+    ) -> Result<Vec<String>, SoraError> {
+        let template = beat_name_template.unwrap_or(DEFAULT_BEAT_NAME_TEMPLATE);
+        let mut beats = Vec::new();
+        for local_id in self.local_ids().await? {
+            let numbers = local_id
+                .split(|c: char| !c.is_ascii_digit())
+                .filter_map(|digits| digits.parse::<usize>().ok());
+            for number in numbers {
+                if number > 0 && render_beat_name(template, base, number)? == local_id {
+                    beats.push((number, local_id));
+                    break;
+                }
+            }
+        }
+        beats.sort();
+        let Some((_, first)) = beats.first() else {
+            return Err(SoraError::InvalidConfig(format!(
+                "no beats of flow '{base}' found (expected ids like '{}')",
+                render_beat_name(template, base, 1)?
+            )));
+        };
+
+        let mut expected = 1;
+        for (number, local_id) in &beats {
+            if *number != expected {
+                warn!(
+                    flow = base,
+                    first_missing = expected,
+                    last_missing = number - 1,
+                    resumes_at = %local_id,
+                    "flow beats are not contiguous"
+                );
+            }
+            expected = number + 1;
+        }
+
+        let mut clips = Vec::with_capacity(beats.len() + 1);
+        let parent = self.load_metadata(first).await?.parent;
+        if let Some(parent) = parent.filter(|parent| beats.iter().all(|(_, id)| id != parent)) {
+            clips.push(parent);
+        }
+        clips.extend(beats.into_iter().map(|(_, local_id)| local_id));
+        Ok(clips)
+    }
+
+    /// Remix an existing clip with a new prompt, producing a variation of the same shot rather
+    /// than an extension. Only supported by Sora.
+    pub async fn remix_video(
+        &self,
+        source_local_id: &str,
+        local_id: &str,
+        prompt: &str,
+    ) -> Result<VideoMetadata, SoraError> {
+        check_local_id(local_id)?;
+        check_prompt(prompt, false)?;
+        self.ensure_data_dir().await?;
+        if self.store.exists(local_id).await? {
+            return Err(SoraError::InvalidConfig(format!(
+                "local id '{local_id}' already exists"
+            )));
+        }
+        let source = self.load_metadata(source_local_id).await?;
+
+        let video_path = self.video_path(local_id, self.backend.kind(), &source.model);
+        self.stop_if_printing()?;
+        let outcome = self
+            .backend
+            .remix(
+                &source.remote_id,
+                prompt,
+                self.poll_interval,
+                &video_path,
+                source.seconds,
+                &source.size,
+            )
+            .await?;
+        let duration_delta = self
+            .measure_duration_delta(&video_path, outcome.seconds)
+            .await;
+
+        let mut metadata = VideoMetadata {
+            local_id: local_id.to_string(),
+            remote_id: outcome.remote_id,
+            prompt: prompt.to_string(),
+            model: outcome.model,
+            seconds: outcome.seconds,
+            size: outcome.size,
+            created_at: outcome.created_at,
+            created_at_source: outcome.created_at_source,
+            seconds_source: outcome.seconds_source,
+            file_path: video_path,
+            parent: Some(source.local_id),
+            backend: self.backend.kind(),
+            duration_delta,
+            sources: Vec::new(),
+            remix_of: Some(source.remote_id),
+            sha256: None,
+            seed_source: None,
+            tags: Vec::new(),
+            seed_frame: None,
+            samples: outcome.samples,
+            storage_uris: outcome.storage_uris,
+            inline_bytes: outcome.inline_bytes,
+            local_created_at: recording_millis(),
+            carried_audio: None,
+            pending: false,
+            notes: None,
+            original_prompt: None,
+            extra: serde_json::Map::new(),
+        };
+
+        self.save_new_metadata(&mut metadata, false).await?;
+        self.upload(&metadata).await?;
+        Ok(metadata)
+    }
+
+    /// Recover a render from its remote id alone: poll it to completion, download the video,
+    /// and record fresh metadata under `local_id`.
+    ///
+    /// Sora takes the video id (`video_...`); Veo takes the full operation name as returned by
+    /// `predictLongRunning`.
+    pub async fn adopt_remote(
+        &self,
+        local_id: &str,
+        remote_id: &str,
+    ) -> Result<VideoMetadata, SoraError> {
+        check_local_id(local_id)?;
+        self.ensure_data_dir().await?;
+        if self.store.exists(local_id).await? {
+            return Err(SoraError::InvalidConfig(format!(
+                "local id '{local_id}' already exists"
+            )));
+        }
+
+        let video_path = self.video_path(
+            local_id,
+            self.backend.kind(),
+            &self.backend.defaults().model,
+        );
+        let outcome = self
+            .backend
+            .resume(
+                remote_id,
+                self.poll_interval,
+                &video_path,
+                self.backend.defaults().seconds,
+                &self.backend.defaults().size,
+            )
+            .await?;
+        let duration_delta = self
+            .measure_duration_delta(&video_path, outcome.seconds)
+            .await;
+
+        let mut metadata = VideoMetadata {
+            local_id: local_id.to_string(),
+            remote_id: outcome.remote_id,
+            prompt: String::new(),
+            model: outcome.model,
+            seconds: outcome.seconds,
+            size: outcome.size,
+            created_at: outcome.created_at,
+            created_at_source: outcome.created_at_source,
+            seconds_source: outcome.seconds_source,
+            file_path: video_path,
+            parent: None,
+            backend: self.backend.kind(),
+            duration_delta,
+            sources: Vec::new(),
+            remix_of: None,
+            sha256: None,
+            seed_source: None,
+            tags: Vec::new(),
+            seed_frame: None,
+            samples: outcome.samples,
+            storage_uris: outcome.storage_uris,
+            inline_bytes: outcome.inline_bytes,
+            local_created_at: recording_millis(),
+            carried_audio: None,
+            pending: false,
+            notes: None,
+            original_prompt: None,
+            extra: serde_json::Map::new(),
+        };
+
+        self.save_new_metadata(&mut metadata, false).await?;
+        Ok(metadata)
+    }
+
+    /// Cut an existing video into clips of about `chunk_seconds` each, saved as `<base>-01`,
+    /// `<base>-02`, ... with each clip's parent set to the one before, so the footage can be
+    /// continued, restyled, or stitched back together (`stitch --flow <base>`) like rendered
+    /// beats.
+    ///
+    /// Streams are copied rather than re-encoded, so each cut lands on the next keyframe and
+    /// chunks can run slightly long. Size and length are probed from each chunk.
+    pub async fn split(
+        &self,
+        source_path: &Path,
+        base_id: &str,
+        chunk_seconds: f32,
+    ) -> Result<Vec<VideoMetadata>, SoraError> {
+        if !chunk_seconds.is_finite() || chunk_seconds <= 0.0 {
+            return Err(SoraError::InvalidConfig(format!(
+                "chunk_seconds must be a positive number (got {chunk_seconds})"
+            )));
+        }
+        if !source_path.exists() {
+            return Err(SoraError::VideoNotFound(source_path.display().to_string()));
+        }
+        check_local_id(base_id)?;
+        self.ensure_data_dir().await?;
+
+        // Check the ids the cut should produce before paying for it; keyframe cuts can only
+        // make fewer chunks than this, and the exact ids are checked again afterwards.
+        let duration = probe_media(source_path)
+            .await?
+            .duration_seconds
+            .unwrap_or_default();
+        let expected = ((duration / f64::from(chunk_seconds)).ceil() as usize).max(1);
+        self.split_ids(base_id, expected).await?;
+
+        // A directory per run, removed with everything in it however the split ends, so a
+        // failed run's segments never leak into the next one.
+        let scratch = self
+            .scratch_file(&format!("split-{base_id}-{}", quick_local_id()))
+            .await?;
+        fs::create_dir_all(scratch.path()).await?;
+        let chunk_path = |index: &str| scratch.path().join(format!("{index}.mp4"));
+        let status = Command::new("ffmpeg")
+            .arg("-v")
+            .arg("error")
+            .arg("-y")
+            .arg("-i")
+            .arg(source_path)
+            .arg("-map")
+            .arg("0")
+            .arg("-c")
+            .arg("copy")
+            .arg("-f")
+            .arg("segment")
+            .arg("-segment_time")
+            .arg(chunk_seconds.to_string())
+            .arg("-reset_timestamps")
+            .arg("1")
+            .arg(chunk_path("%03d"))
+            .run_media(self.print_ffmpeg)
+            .await?;
+        if !status.success() {
+            return Err(SoraError::FfmpegFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+        self.stop_if_printing()?;
+
+        let mut chunks = Vec::new();
+        loop {
+            let path = chunk_path(&format!("{:03}", chunks.len()));
+            if !path.exists() {
+                break;
+            }
+            chunks.push(path);
+        }
+        let ids = self.split_ids(base_id, chunks.len()).await?;
+        // Probe every chunk before saving any, so a bad chunk leaves no partial split behind.
+        let mut infos = Vec::with_capacity(chunks.len());
+        for chunk in &chunks {
+            infos.push(probe_media(chunk).await?);
+        }
+
+        let backend = self.backend.kind();
+        let mut clips: Vec<VideoMetadata> = Vec::with_capacity(chunks.len());
+        for ((chunk, info), local_id) in chunks.into_iter().zip(infos).zip(ids) {
+            let size = match (info.width, info.height) {
+                (Some(width), Some(height)) => format!("{width}x{height}"),
+                _ => String::new(),
+            };
+            let file_path = self.video_path(&local_id, backend, "");
+            ScratchFile(chunk).persist(&file_path).await?;
+            let mut metadata = VideoMetadata {
+                local_id,
+                remote_id: String::new(),
+                prompt: String::new(),
+                model: String::new(),
+                seconds: info.duration_seconds.unwrap_or_default().round() as u32,
+                size,
+                created_at: None,
+                created_at_source: ValueSource::Local,
+                seconds_source: ValueSource::Local,
+                file_path,
+                parent: clips.last().map(|previous| previous.local_id.clone()),
+                backend,
+                duration_delta: None,
+                sources: Vec::new(),
+                remix_of: None,
+                sha256: None,
+                seed_source: None,
+                tags: Vec::new(),
+                seed_frame: None,
+                samples: Vec::new(),
+                storage_uris: Vec::new(),
+                inline_bytes: None,
+                local_created_at: recording_millis(),
+                carried_audio: None,
+                pending: false,
+                notes: Some(format!("split from {}", source_path.display())),
+                original_prompt: None,
+                extra: serde_json::Map::new(),
+            };
+            self.save_new_metadata(&mut metadata, false).await?;
+            clips.push(metadata);
+        }
+        Ok(clips)
+    }
+
+    /// Ids for `count` split chunks of `base_id`, refusing any that are already taken.
+    async fn split_ids(&self, base_id: &str, count: usize) -> Result<Vec<String>, SoraError> {
+        let mut ids = Vec::with_capacity(count);
+        for number in 1..=count {
+            let id = render_beat_name(DEFAULT_BEAT_NAME_TEMPLATE, base_id, number)?;
+            if self.store.exists(&id).await? {
+                return Err(SoraError::InvalidConfig(format!(
+                    "local id '{id}' already exists"
+                )));
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Sum the duration and on-disk size of the given clips.
+    ///
+    /// Durations come from the recorded probe delta when available, then a fresh probe, and
+    /// finally the requested length.
+    pub async fn aggregate(&self, local_ids: &[String]) -> Result<Aggregate, SoraError> {
+        let sizes = self
+            .map_clips(local_ids.to_vec(), |local_id| async move {
+                let metadata = self.load_finished(&local_id).await?;
+                let file = fs::metadata(&metadata.file_path)
+                    .await
+                    .map_err(|_| SoraError::VideoNotFound(local_id))?;
+                Ok((file.len(), self.clip_duration(&metadata).await))
+            })
+            .await;
+
+        let mut aggregate = Aggregate::default();
+        for (_, result) in sizes {
+            let (bytes, seconds) = result?;
+            aggregate.total_bytes += bytes;
+            aggregate.total_seconds += seconds;
+            aggregate.clip_count += 1;
+        }
+        Ok(aggregate)
+    }
+
+    /// Run `work` for each clip with bounded concurrency, returning results in input order.
+    async fn map_clips<T, F, Fut>(
+        &self,
+        local_ids: Vec<String>,
+        work: F,
+    ) -> Vec<(String, Result<T, SoraError>)>
+    where
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = Result<T, SoraError>>,
+    {
+        let mut results: Vec<_> = futures_util::stream::iter(local_ids.into_iter().enumerate())
+            .map(|(index, local_id)| {
+                let task = work(local_id.clone());
+                async move { (index, local_id, task.await) }
+            })
+            .buffer_unordered(self.probe_concurrency)
+            .collect()
+            .await;
+        results.sort_by_key(|(index, ..)| *index);
+        results
+            .into_iter()
+            .map(|(_, local_id, result)| (local_id, result))
+            .collect()
+    }
+
+    async fn clip_duration(&self, metadata: &VideoMetadata) -> f64 {
+        let requested = f64::from(metadata.seconds);
+        if let Some(delta) = metadata.duration_delta {
+            return requested + delta;
+        }
+        match probe_media(&metadata.file_path).await {
+            Ok(MediaInfo {
+                duration_seconds: Some(actual),
+                ..
+            }) => actual,
+            _ => requested,
+        }
+    }
+
+    /// Enumerate all locally stored clips, oldest first by when they were recorded here (then
+    /// by id).
+    ///
+    /// Records that cannot be read are logged and skipped, and pending renders, which have no
+    /// video yet, are left to [`VideoManager::list_pending`].
+    pub async fn list_videos(&self) -> Result<Vec<VideoMetadata>, SoraError> {
+        let local_ids = self.local_ids().await?;
+        let mut entries = Vec::with_capacity(local_ids.len());
+        for (local_id, result) in self
+            .map_clips(local_ids, |local_id| async move {
+                self.load_metadata(&local_id).await
+            })
+            .await
+        {
+            match result {
+                Ok(metadata) if metadata.pending => {}
+                Ok(metadata) => entries.push(metadata),
+                Err(err) => warn!(local_id, error = %err, "skipping unreadable metadata"),
+            }
+        }
+        entries.sort_by(|a, b| {
+            (a.local_created_at, &a.local_id).cmp(&(b.local_created_at, &b.local_id))
+        });
+        Ok(entries)
+    }
+
+    /// Records saved with `no_wait` whose video hasn't been collected yet, sorted by id.
+    pub async fn list_pending(&self) -> Result<Vec<VideoMetadata>, SoraError> {
+        let mut pending = Vec::new();
+        for local_id in self.local_ids().await? {
+            match self.load_metadata(&local_id).await {
+                Ok(metadata) if metadata.pending => pending.push(metadata),
+                Ok(_) => {}
+                Err(err) => warn!(local_id, error = %err, "skipping unreadable metadata"),
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Sorted ids of every stored metadata record.
+    async fn local_ids(&self) -> Result<Vec<String>, SoraError> {
+        self.store.list().await
+    }
+
+    /// Package clips and their metadata into a tar archive with a manifest.
+    ///
+    /// Exports every recorded clip when `ids` is `None`. Returns the manifest that was written.
+    pub async fn export_bundle(
+        &self,
+        ids: Option<&[String]>,
+        output: &Path,
+    ) -> Result<BundleManifest, SoraError> {
+        let clips = match ids {
+            Some(ids) => {
+                let mut clips = Vec::with_capacity(ids.len());
+                for id in ids {
+                    clips.push(self.load_finished(id).await?);
+                }
+                clips
+            }
+            None => self.list_videos().await?,
+        };
+        let mut entries = Vec::with_capacity(clips.len());
+        let mut records = Vec::with_capacity(clips.len());
+        for clip in &clips {
+            if !clip.file_path.exists() {
+                return Err(SoraError::VideoNotFound(clip.local_id.clone()));
+            }
+            entries.push((format!("{}.mp4", clip.local_id), clip.file_path.clone()));
+            records.push((
+                format!("{}.json", clip.local_id),
+                serde_json::to_vec_pretty(clip)?,
+            ));
+        }
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            clips: clips.into_iter().map(|clip| clip.local_id).collect(),
+        };
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        if let Some(parent) = output.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let output = output.to_path_buf();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut builder = tar::Builder::new(std::fs::File::create(&output)?);
+            let mut append_bytes = |name: &str, bytes: &[u8]| {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(bytes.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append_data(&mut header, name, bytes)
+            };
+            append_bytes(BUNDLE_MANIFEST, &manifest_bytes)?;
+            for (name, bytes) in records {
+                append_bytes(&name, &bytes)?;
+            }
+            for (name, path) in entries {
+                builder.append_path_with_name(path, name)?;
+            }
+            builder.into_inner()?.sync_all()
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        Ok(manifest)
+    }
+
+    /// Unpack a bundle written by [`VideoManager::export_bundle`] and register its clips.
+    ///
+    /// Clips whose ids are already taken are imported under `<id>-2`, `<id>-3`, and so on, with
+    /// `parent` and `sources` links inside the bundle rewritten to match. Returns the imported
+    /// metadata.
+    pub async fn import_bundle(&self, bundle: &Path) -> Result<Vec<VideoMetadata>, SoraError> {
+        self.ensure_data_dir().await?;
+        let staging = self.data_dir.join(format!(".import-{}", quick_local_id()));
+        fs::create_dir_all(&staging).await?;
+        let result = self.import_staged(bundle, &staging).await;
+        let _ = fs::remove_dir_all(&staging).await;
+        result
+    }
+
+    async fn import_staged(
+        &self,
+        bundle: &Path,
+        staging: &Path,
+    ) -> Result<Vec<VideoMetadata>, SoraError> {
+        let (archive_path, unpack_to) = (bundle.to_path_buf(), staging.to_path_buf());
+        tokio::task::spawn_blocking(move || {
+            tar::Archive::new(std::fs::File::open(archive_path)?).unpack(unpack_to)
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        let manifest_bytes = fs::read(staging.join(BUNDLE_MANIFEST))
+            .await
+            .map_err(|_| SoraError::InvalidBundle(format!("{BUNDLE_MANIFEST} missing")))?;
+        let manifest: BundleManifest = serde_json::from_slice(&manifest_bytes)?;
+        if manifest.version != BUNDLE_VERSION {
+            return Err(SoraError::InvalidBundle(format!(
+                "unsupported bundle version {}",
+                manifest.version
+            )));
+        }
+
+        // Read and check every record before anything lands in the data dir, so a crafted
+        // bundle can't name files outside it.
+        let mut records = Vec::with_capacity(manifest.clips.len());
+        for id in &manifest.clips {
+            check_local_id(id).map_err(|_| {
+                SoraError::InvalidBundle(format!("manifest lists unusable clip id '{id}'"))
+            })?;
+            let bytes = fs::read(staging.join(format!("{id}.json")))
+                .await
+                .map_err(|_| SoraError::InvalidBundle(format!("metadata for '{id}' missing")))?;
+            let metadata: VideoMetadata = serde_json::from_slice(&bytes)?;
+            for linked in metadata.parent.iter().chain(&metadata.sources) {
+                check_local_id(linked).map_err(|_| {
+                    SoraError::InvalidBundle(format!("clip '{id}' links to unusable id '{linked}'"))
+                })?;
+            }
+            records.push(metadata);
+        }
+
+        let mut renames = std::collections::HashMap::new();
+        for id in &manifest.clips {
+            let mut candidate = id.clone();
+            let mut suffix = 2;
+            while self.store.exists(&candidate).await?
+                || renames.values().any(|taken| taken == &candidate)
+            {
+                candidate = format!("{id}-{suffix}");
+                suffix += 1;
+            }
+            renames.insert(id.clone(), candidate);
+        }
+        let remap = |id: &String| renames.get(id).cloned().unwrap_or_else(|| id.clone());
+
+        let mut imported = Vec::with_capacity(manifest.clips.len());
+        for (id, mut metadata) in manifest.clips.iter().zip(records) {
+            metadata.local_id = remap(id);
+            metadata.parent = metadata.parent.as_ref().map(remap);
+            metadata.sources = metadata.sources.iter().map(remap).collect();
+            metadata.file_path =
+                self.video_path(&metadata.local_id, metadata.backend, &metadata.model);
+            // Seed frames, extra samples, and carried audio aren't bundled, so their recorded
+            // paths would point at the old machine.
+            metadata.seed_frame = None;
+            metadata.samples.clear();
+            metadata.carried_audio = None;
+            fs::rename(staging.join(format!("{id}.mp4")), &metadata.file_path)
+                .await
+                .map_err(|_| SoraError::InvalidBundle(format!("video for '{id}' missing")))?;
+            self.save_new_metadata(&mut metadata, false).await?;
+            imported.push(metadata);
+        }
+        Ok(imported)
+    }
+
+    /// Recompute the checksum of every clip that has one recorded and report the mismatches.
+    ///
+    /// Clips saved without checksums are skipped. Clips are hashed concurrently, and a clip that
+    /// cannot be read is reported in [`VerifyReport::errors`] rather than aborting the scan.
+    pub async fn verify_checksums(&self) -> Result<VerifyReport, SoraError> {
+        let results = self
+            .map_clips(self.local_ids().await?, |local_id| async move {
+                let metadata = self.load_metadata(&local_id).await?;
+                let Some(expected) = metadata.sha256 else {
+                    return Ok(None);
+                };
+                let actual = if fs::try_exists(&metadata.file_path).await? {
+                    Some(sha256_file(&metadata.file_path).await?)
+                } else {
+                    None
+                };
+                Ok(Some(ChecksumMismatch {
+                    local_id,
+                    expected,
+                    actual,
+                }))
+            })
+            .await;
+
+        let mut report = VerifyReport::default();
+        for (local_id, result) in results {
+            match result {
+                Ok(None) => {}
+                Ok(Some(check)) => {
+                    report.checked += 1;
+                    if check.actual.as_deref() != Some(check.expected.as_str()) {
+                        report.mismatches.push(check);
+                    }
+                }
+                Err(error) => report.errors.push(ClipError { local_id, error }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Query the provider for the status of several clips at once, with bounded concurrency
+    /// (requests still go through the rate limiter). With `None`, every clip that has a remote
+    /// render on this manager's backend is checked (stitched and other local-only clips are
+    /// skipped), and so is every unfinished job the provider lists with no local record, such
+    /// as one submitted from another machine; those are reported under their remote id. Results
+    /// come back in the order of `local_ids` (or sorted by id, then the provider's jobs).
+    pub async fn remote_status_many(
+        &self,
+        local_ids: Option<&[String]>,
+    ) -> Result<Vec<(String, Result<RemoteStatus, SoraError>)>, SoraError> {
+        let explicit = local_ids.is_some();
+        let ids = match local_ids {
+            Some(ids) => ids.to_vec(),
+            None => self.local_ids().await?,
+        };
+        let results = self
+            .map_clips(ids, |local_id| async move {
+                let metadata = if explicit {
+                    self.remote_clip(&local_id).await?
+                } else {
+                    match self.listed_remote_clip(&local_id).await? {
+                        Some(metadata) => metadata,
+                        None => return Ok(None),
+                    }
+                };
+                let (status, progress) = self.backend.remote_status(&metadata.remote_id).await?;
+                Ok(Some(RemoteStatus {
+                    local_id,
+                    remote_id: metadata.remote_id,
+                    status,
+                    progress,
+                }))
+            })
+            .await;
+        let unknown = if explicit {
+            Vec::new()
+        } else {
+            let known: std::collections::HashSet<&str> = results
+                .iter()
+                .filter_map(|(_, result)| Some(result.as_ref().ok()?.as_ref()?.remote_id.as_str()))
+                .collect();
+            self.backend
+                .unfinished_jobs()
+                .await?
+                .into_iter()
+                .filter(|remote_id| !known.contains(remote_id.as_str()))
+                .collect()
+        };
+        let remote = self
+            .map_clips(unknown, |remote_id| async move {
+                let (status, progress) = self.backend.remote_status(&remote_id).await?;
+                Ok(Some(RemoteStatus {
+                    local_id: String::new(),
+                    remote_id,
+                    status,
+                    progress,
+                }))
+            })
+            .await;
+        Ok(results
+            .into_iter()
+            .chain(remote)
+            .filter_map(|(local_id, result)| match result {
+                Ok(Some(status)) => Some((local_id, Ok(status))),
+                Ok(None) => None,
+                Err(error) => Some((local_id, Err(error))),
+            })
+            .collect())
+    }
+
+    /// Poll a remote job until it reaches a terminal status and return that status, calling
+    /// `on_update` with the status and progress reported on every poll. For jobs submitted
+    /// outside the manager that still want its polling: waits use the configured poll interval
+    /// and [`Sleeper`], and requests go through the retry policy and rate limiter.
+    ///
+    /// A failed or canceled job is returned as its status, not an error. Drop the future to
+    /// stop polling; wrap it in `tokio::time::timeout` for a deadline.
+    pub async fn poll_until_terminal(
+        &self,
+        remote_id: &str,
+        mut on_update: impl FnMut(VideoStatus, Option<f64>),
+    ) -> Result<VideoStatus, SoraError> {
+        poll_until(
+            self.backend.kind(),
+            self.backend.sleeper(),
+            self.poll_interval,
+            || self.backend.remote_status(remote_id),
+            |(status, progress)| {
+                on_update(status.clone(), progress);
+                if status.is_terminal() {
+                    return Ok(Some(status));
+                }
+                debug!(id = remote_id, status = ?status, "polling video status");
+                Ok(None)
+            },
+        )
+        .await
+    }
+
+    /// Whether the provider still holds a clip's rendered video, so it can be downloaded again.
+    /// Sora keeps videos for a limited time; `false` means it has expired them and the local
+    /// copy is the only one. Other failures, such as a network error, are returned as errors.
+    pub async fn is_remote_available(&self, local_id: &str) -> Result<bool, SoraError> {
+        let metadata = self.remote_clip(local_id).await?;
+        if metadata.pending {
+            return Err(SoraError::InvalidConfig(format!(
+                "clip '{local_id}' is still pending; collect it before checking its download"
+            )));
+        }
+        self.backend.remote_available(&metadata.remote_id).await
+    }
+
+    /// [`VideoManager::is_remote_available`] for every clip rendered on this manager's
+    /// provider, sorted by id. Pending, stitched, and other local-only clips are skipped. A
+    /// provider that keeps no copies (Veo) is a single [`SoraError::UnsupportedOperation`]
+    /// rather than a failure per clip.
+    pub async fn remote_availability(
+        &self,
+    ) -> Result<Vec<(String, Result<bool, SoraError>)>, SoraError> {
+        self.backend.check_remote_copies()?;
+        let results = self
+            .map_clips(self.local_ids().await?, |local_id| async move {
+                match self.listed_remote_clip(&local_id).await? {
+                    Some(metadata) if !metadata.pending => self
+                        .backend
+                        .remote_available(&metadata.remote_id)
+                        .await
+                        .map(Some),
+                    _ => Ok(None),
+                }
+            })
+            .await;
+        Ok(results
+            .into_iter()
+            .filter_map(|(local_id, result)| Some((local_id, result.transpose()?)))
+            .collect())
+    }
+
+    /// Load a clip the caller named, which must have been rendered on this manager's provider.
+    async fn remote_clip(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        let metadata = self.load_metadata(local_id).await?;
+        self.check_remote_clip(&metadata)?;
+        Ok(metadata)
+    }
+
+    /// [`VideoManager::remote_clip`] for scans over every clip: clips produced locally or by
+    /// another provider are skipped rather than an error.
+    async fn listed_remote_clip(&self, local_id: &str) -> Result<Option<VideoMetadata>, SoraError> {
+        let metadata = self.load_metadata(local_id).await?;
+        Ok(self
+            .check_remote_clip(&metadata)
+            .is_ok()
+            .then_some(metadata))
+    }
+
+    fn check_remote_clip(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
+        let local_id = &metadata.local_id;
+        if metadata.remote_id.is_empty() {
+            return Err(SoraError::UnsupportedOperation(format!(
+                "'{local_id}' was produced locally and has no remote render"
+            )));
+        }
+        if metadata.backend != self.backend.kind() {
+            return Err(SoraError::InvalidConfig(format!(
+                "'{local_id}' was rendered by {:?}, not {:?}",
+                metadata.backend,
+                self.backend.kind()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Cancel a clip's render if the provider still has it queued or in progress. A clip
+    /// submitted without waiting has nothing to collect once canceled, so its record is
+    /// deleted and the id can be used again.
+    pub async fn cancel(&self, local_id: &str) -> Result<CancelOutcome, SoraError> {
+        let metadata = self.remote_clip(local_id).await?;
+        self.cancel_clip(&metadata).await
+    }
+
+    async fn cancel_clip(&self, metadata: &VideoMetadata) -> Result<CancelOutcome, SoraError> {
+        let outcome = self.cancel_remote(&metadata.remote_id).await?;
+        if metadata.pending && outcome == CancelOutcome::Canceled {
+            self.store.delete(&metadata.local_id).await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Delete a clip: its record and the files saved with it (the video, seed frame, extra
+    /// samples, and carried audio). Clips continued or stitched from it keep their own videos.
+    /// A pending clip is refused, since its render is still running; cancel it instead.
+    pub async fn remove_clip(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+        let metadata = self.load_metadata(local_id).await?;
+        if metadata.pending {
+            return Err(SoraError::InvalidConfig(format!(
+                "clip '{local_id}' is still pending; cancel or collect it first"
+            )));
+        }
+        self.store.delete(local_id).await?;
+        let files = std::iter::once(&metadata.file_path)
+            .chain(&metadata.seed_frame)
+            .chain(&metadata.samples)
+            .chain(&metadata.carried_audio);
+        for path in files {
+            match fs::remove_file(path).await {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    warn!(
+                        id = %local_id,
+                        path = %path.display(),
+                        error = %err,
+                        "failed to delete clip file"
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// Cancel every render on this provider that hasn't finished, concurrently. Clips whose
+    /// renders already finished are left out of the result.
+    ///
+    /// Besides the local records, the provider's own job list is checked (where it has one),
+    /// so renders submitted from another machine or a lost process are canceled too; those
+    /// are reported under their remote id, after the local clips.
+    pub async fn cancel_all(
+        &self,
+    ) -> Result<Vec<(String, Result<CancelOutcome, SoraError>)>, SoraError> {
+        let ids = self.local_ids().await?;
+        let local = self
+            .map_clips(ids, |local_id| async move {
+                match self.listed_remote_clip(&local_id).await? {
+                    Some(metadata) => {
+                        let outcome = self.cancel_clip(&metadata).await;
+                        Ok(Some((metadata.remote_id, outcome)))
+                    }
+                    None => Ok(None),
+                }
+            })
+            .await;
+        let known: std::collections::HashSet<&str> = local
+            .iter()
+            .filter_map(|(_, result)| Some(result.as_ref().ok()?.as_ref()?.0.as_str()))
+            .collect();
+        let unknown = self
+            .backend
+            .unfinished_jobs()
+            .await?
+            .into_iter()
+            .filter(|remote_id| !known.contains(remote_id.as_str()))
+            .collect();
+        // The listing already said these are unfinished, so they go straight to the cancel.
+        let remote = self
+            .map_clips(unknown, |remote_id| async move {
+                self.backend.cancel(&remote_id).await.map(Some)
+            })
+            .await;
+        Ok(local
+            .into_iter()
+            .map(|(local_id, result)| {
+                let outcome = match result {
+                    Ok(Some((_, outcome))) => outcome.map(Some),
+                    Ok(None) => Ok(None),
+                    Err(error) => Err(error),
+                };
+                (local_id, outcome)
+            })
+            .chain(remote)
+            .filter_map(|(id, result)| match result {
+                Ok(None | Some(CancelOutcome::AlreadyFinished(_))) => None,
+                Ok(Some(outcome)) => Some((id, Ok(outcome))),
+                Err(error) => Some((id, Err(error))),
+            })
+            .collect())
+    }
+
+    /// Cancel the renders this manager started waiting on but never saw finish, because the
+    /// waiting future was dropped, and delete their partial downloads. Returns each job's
+    /// remote id with the outcome.
+    pub async fn cancel_in_flight(&self) -> Vec<(String, Result<CancelOutcome, SoraError>)> {
+        let mut results = Vec::new();
+        for (remote_id, output_path) in self.backend.in_flight() {
+            let _ = fs::remove_file(&output_path).await;
+            let outcome = self.cancel_remote(&remote_id).await;
+            results.push((remote_id, outcome));
+        }
+        results
+    }
+
+    async fn cancel_remote(&self, remote_id: &str) -> Result<CancelOutcome, SoraError> {
+        let (status, _) = self.backend.remote_status(remote_id).await?;
+        if status.is_terminal() {
+            return Ok(CancelOutcome::AlreadyFinished(status));
+        }
+        self.backend.cancel(remote_id).await
+    }
+
+    /// Concatenate multiple local clips into a single MP4 under the output identifier.
+    ///
+    /// The stitched output is recorded with its own metadata so it can later be extended with
+    /// [`VideoManager::append_to_stitch`].
+    pub async fn stitch_videos(
+        &self,
+        output_local_id: &str,
+        input_local_ids: &[String],
+    ) -> Result<PathBuf, SoraError> {
+        self.stitch_videos_with(output_local_id, input_local_ids, &StitchOptions::default())
+            .await
+    }
+
+    /// [`VideoManager::stitch_videos`] with per-call options.
+    pub async fn stitch_videos_with(
+        &self,
+        output_local_id: &str,
+        input_local_ids: &[String],
+        options: &StitchOptions,
+    ) -> Result<PathBuf, SoraError> {
+        let encode = options.encode.or(&self.encode);
+        encode.validate()?;
+        let sample_rate = options.audio_sample_rate.or(self.audio_sample_rate);
+        let channels = options.audio_channels.or(self.audio_channels);
+        validate_audio_target(sample_rate, channels)?;
+        let chunk_size = options.chunk_size.or(self.stitch_chunk_size);
+        validate_chunk_size(chunk_size)?;
+        if let Some(gap) = &options.gap {
+            gap.validate()?;
+        }
+        if input_local_ids.is_empty() {
+            return Err(SoraError::InvalidConfig(
+                "stitch requires at least one input clip".to_string(),
+            ));
+        }
+        if input_local_ids.iter().any(|id| id == output_local_id) {
+            return Err(SoraError::InvalidConfig(format!(
+                "stitch output '{output_local_id}' is also one of its inputs"
+            )));
+        }
+
+        self.ensure_data_dir().await?;
+        self.check_output_id(output_local_id, options.overwrite)
+            .await?;
+
+        let mut sources = Vec::with_capacity(input_local_ids.len());
+        for id in input_local_ids {
+            let metadata = self.load_finished(id).await?;
+            if !metadata.file_path.exists() {
+                return Err(SoraError::VideoNotFound(id.clone()));
+            }
+            sources.push(metadata);
+        }
+        self.check_mixed_providers(&sources)?;
+        let output_path = self.video_path(output_local_id, sources[0].backend, &sources[0].model);
+
+        let inputs: Vec<PathBuf> = sources.iter().map(|m| m.file_path.clone()).collect();
+        let carried = sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| {
+                let previous = index.checked_sub(1).map(|index| &sources[index].local_id);
+                source
+                    .carried_audio
+                    .clone()
+                    .filter(|path| source.parent.as_ref() == previous && path.exists())
+            })
+            .collect();
+        let shaping = AudioShaping {
+            fade: self.audio_crossfade.filter(|_| inputs.len() > 1),
+            sample_rate,
+            channels,
+            carried,
+        };
+        // Join beside the output and rename it into place only once it's good, so a failed or
+        // interrupted stitch leaves any earlier output untouched.
+        let staging = ScratchFile(
+            self.data_dir
+                .join(format!(".stitching-{output_local_id}.mp4")),
+        );
+        let staging_path = staging.path();
+        // ffmpeg reports its own reason (including a full disk) on stderr.
+        let gap = options.gap.as_ref().filter(|_| inputs.len() > 1);
+        if gap.is_some() {
+            check_gap_stitch(&shaping, chunk_size, inputs.len())?;
+        }
+        match (gap, chunk_size.filter(|size| inputs.len() > *size)) {
+            (Some(gap), _) => {
+                concat_with_gaps(
+                    &inputs,
+                    gap,
+                    (sample_rate, channels),
+                    &encode,
+                    staging_path,
+                    self.print_ffmpeg,
+                )
+                .await?
+            }
+            (None, Some(chunk_size)) => {
+                self.concat_chunked(
+                    output_local_id,
+                    &inputs,
+                    &shaping,
+                    &encode,
+                    chunk_size,
+                    staging_path,
+                )
+                .await?
+            }
+            (None, None) => {
+                self.concat_with_audio_graph(
+                    output_local_id,
+                    &inputs,
+                    &shaping,
+                    &encode,
+                    "aac",
+                    staging_path,
+                )
+                .await?;
+            }
+        }
+        self.stop_if_printing()?;
+        if options.verify {
+            verify_playable(staging_path).await?;
+        }
+        staging.persist(&output_path).await?;
+
+        let mut metadata = self.stitched_metadata(output_local_id, &sources, output_path.clone());
+        if gap.is_some() {
+            // The pauses make the sum of the sources' lengths wrong; measure the result.
+            let duration = probe_media(&output_path).await?.duration_seconds;
+            if let Some(duration) = duration {
+                metadata.seconds = duration.round() as u32;
+            }
+        }
+        self.save_new_metadata(&mut metadata, options.overwrite)
+            .await?;
+        self.upload(&metadata).await?;
+
+        Ok(output_path)
+    }
+
+    /// Append a clip to a running stitched master without re-concatenating every source.
+    ///
+    /// When the master does not exist yet the clip is copied into place. Otherwise the two files
+    /// are joined with the concat demuxer if their streams match, or re-encoded through the
+    /// concat filter if they do not. A master that isn't a stitched clip (one with `sources`)
+    /// is only extended with `overwrite`, since its video and metadata are replaced in place.
+    pub async fn append_to_stitch(
+        &self,
+        master_local_id: &str,
+        new_local_id: &str,
+        overwrite: bool,
+    ) -> Result<VideoMetadata, SoraError> {
+        check_local_id(master_local_id)?;
+        self.ensure_data_dir().await?;
+
+        let clip = self.load_finished(new_local_id).await?;
+        if !clip.file_path.exists() {
+            return Err(SoraError::VideoNotFound(new_local_id.to_string()));
+        }
+
+        let existing = match self.load_finished(master_local_id).await {
+            Ok(master) if master.sources.is_empty() && !overwrite => {
+                return Err(SoraError::InvalidConfig(format!(
+                    "'{master_local_id}' is not a stitched clip (pass overwrite to append to it \
+                     anyway)"
+                )));
+            }
+            Ok(master) if master.file_path.exists() => Some(master),
+            Ok(_) | Err(SoraError::MetadataNotFound(_)) => None,
+            Err(err) => return Err(err),
+        };
+
+        let Some(mut master) = existing else {
+            let master_path = self.video_path(master_local_id, clip.backend, &clip.model);
+            fs::copy(&clip.file_path, &master_path).await?;
+            let mut metadata =
+                self.stitched_metadata(master_local_id, std::slice::from_ref(&clip), master_path);
+            self.save_metadata(&mut metadata).await?;
+            return Ok(metadata);
+        };
+
+        let staging_path = self.data_dir.join(format!(".append-{master_local_id}.mp4"));
+        let master_info = probe_media(&master.file_path).await?;
+        let clip_info = probe_media(&clip.file_path).await?;
+        let inputs = [master.file_path.clone(), clip.file_path.clone()];
+        let result = if master_info.concat_compatible(&clip_info) {
+            self.concat_demux(
+                master_local_id,
+                &inputs,
+                &EncodeSettings::default(),
+                true,
+                &staging_path,
+            )
+            .await
+        } else {
+            debug!(
+                master = master_local_id,
+                clip = new_local_id,
+                "stream parameters differ; re-encoding append"
+            );
+            concat_filter(
+                &inputs,
+                &master_info,
+                &clip_info,
+                &self.encode,
+                &staging_path,
+                self.print_ffmpeg,
+            )
+            .await
+        };
+        if let Err(err) = result {
+            let _ = fs::remove_file(&staging_path).await;
+            return Err(err);
+        }
+        self.stop_if_printing()?;
+        fs::rename(&staging_path, &master.file_path).await?;
+
+        master.seconds += clip.seconds;
+        master.sources.push(clip.local_id);
+        master.sha256 = None;
+        self.save_metadata(&mut master).await?;
+        Ok(master)
+    }
+
+    /// Burn text (by default the clip's local id) onto a clip, producing a derived clip.
+    pub async fn annotate(
+        &self,
+        source_local_id: &str,
+        output_local_id: &str,
+        spec: AnnotationSpec,
+    ) -> Result<VideoMetadata, SoraError> {
+        let source = self
+            .derivation_source("annotate", source_local_id, output_local_id, spec.overwrite)
+            .await?;
+
+        // Feeding the text through a file sidesteps drawtext's escaping rules for the text itself.
+        let text = spec.text.unwrap_or_else(|| source.local_id.clone());
+        let text_file = self
+            .scratch_file(&format!("annotate-{output_local_id}.txt"))
+            .await?;
+        let text_path = text_file.path();
+        fs::write(text_path, text).await?;
+
+        let (x, y) = spec.position.drawtext_coordinates();
+        let mut filter = format!(
+            "drawtext=textfile='{}':x={x}:y={y}:fontsize={}:fontcolor=white",
+            escape_filter_path(text_path),
+            spec.font_size.unwrap_or(DEFAULT_ANNOTATION_FONT_SIZE)
+        );
+        if let Some(font) = resolve_font(spec.font_file.as_deref()) {
+            filter.push_str(&format!(":fontfile='{}'", escape_filter_path(&font)));
+        }
+        if spec.boxed {
+            filter.push_str(":box=1:boxcolor=black@0.5:boxborderw=12");
+        }
+
+        let output_path = self.video_path(output_local_id, source.backend, &source.model);
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-v")
+            .arg("error")
+            .arg("-y")
+            .arg("-i")
+            .arg(&source.file_path)
+            .arg("-vf")
+            .arg(filter);
+        self.encode.apply_video(&mut command);
+        self.encode.apply_audio(&mut command, "copy");
+        let status = command
+            .arg(&output_path)
+            .run_media(self.print_ffmpeg)
+            .await?;
+        drop(text_file);
+
+        if !status.success() {
+            return Err(SoraError::FfmpegFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+        self.stop_if_printing()?;
+
+        let mut metadata = self.derived_metadata(&source, output_local_id, output_path);
+        self.save_new_metadata(&mut metadata, spec.overwrite)
+            .await?;
+        Ok(metadata)
+    }
+
+    /// Produce a clip that plays `source` forward and then backward, producing a derived clip
+    /// of twice the length.
+    pub async fn boomerang(
+        &self,
+        source_local_id: &str,
+        output_local_id: &str,
+        options: BoomerangOptions,
+    ) -> Result<VideoMetadata, SoraError> {
+        let source = self
+            .derivation_source(
+                "boomerang",
+                source_local_id,
+                output_local_id,
+                options.overwrite,
+            )
+            .await?;
+
+        let with_audio = !options.strip_audio && probe_media(&source.file_path).await?.has_audio();
+        let mut graph =
+            String::from("[0:v]split[vf][vr];[vr]reverse[vrr];[vf][vrr]concat=n=2:v=1:a=0[v]");
+        if with_audio {
+            graph.push_str(";[0:a]asplit[af][ar];[ar]areverse[arr];[af][arr]concat=n=2:v=0:a=1[a]");
+        }
+
+        let output_path = self.video_path(output_local_id, source.backend, &source.model);
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-v")
+            .arg("error")
+            .arg("-y")
+            .arg("-i")
+            .arg(&source.file_path)
+            .arg("-filter_complex")
+            .arg(graph)
+            .arg("-map")
+            .arg("[v]");
+        self.encode.apply_video(&mut command);
+        if with_audio {
+            command.arg("-map").arg("[a]");
+            self.encode.apply_audio(&mut command, "aac");
+        } else {
+            command.arg("-an");
+        }
+        let status = command
+            .arg(&output_path)
+            .run_media(self.print_ffmpeg)
+            .await?;
+
+        if !status.success() {
+            return Err(SoraError::FfmpegFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+        self.stop_if_printing()?;
+
+        let mut metadata = self.derived_metadata(&source, output_local_id, output_path);
+        metadata.seconds = source.seconds * 2;
+        self.save_new_metadata(&mut metadata, options.overwrite)
+            .await?;
+        Ok(metadata)
+    }
+
+    /// Burn an SRT or WebVTT subtitle file onto a clip, producing a derived clip.
+    pub async fn burn_subtitles(
+        &self,
+        source_local_id: &str,
+        output_local_id: &str,
+        subtitle_path: &Path,
+        overwrite: bool,
+    ) -> Result<VideoMetadata, SoraError> {
+        let extension = subtitle_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        if !matches!(extension.as_deref(), Some("srt" | "vtt")) {
+            return Err(SoraError::InvalidConfig(format!(
+                "subtitle file {} must be .srt or .vtt",
+                subtitle_path.display()
+            )));
+        }
+        if !subtitle_path.is_file() {
+            return Err(SoraError::InvalidConfig(format!(
+                "subtitle file {} does not exist",
+                subtitle_path.display()
+            )));
+        }
+        let source = self
+            .derivation_source("subtitles", source_local_id, output_local_id, overwrite)
+            .await?;
+
+        let output_path = self.video_path(output_local_id, source.backend, &source.model);
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-v")
+            .arg("error")
+            .arg("-y")
+            .arg("-i")
+            .arg(&source.file_path)
+            .arg("-vf")
+            .arg(format!("subtitles='{}'", escape_filter_path(subtitle_path)));
+        self.encode.apply_video(&mut command);
+        self.encode.apply_audio(&mut command, "copy");
+        let status = command
+            .arg(&output_path)
+            .run_media(self.print_ffmpeg)
+            .await?;
+
+        if !status.success() {
+            return Err(SoraError::FfmpegFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+        self.stop_if_printing()?;
+
+        let mut metadata = self.derived_metadata(&source, output_local_id, output_path);
+        self.save_new_metadata(&mut metadata, overwrite).await?;
+        Ok(metadata)
+    }
+
+    /// Load the source of a derived clip, checking that `output_local_id` is distinct from it
+    /// and free to write (see [`Self::check_output_id`]).
+    async fn derivation_source(
+        &self,
+        operation: &str,
+        source_local_id: &str,
+        output_local_id: &str,
+        overwrite: bool,
+    ) -> Result<VideoMetadata, SoraError> {
+        if source_local_id == output_local_id {
+            return Err(SoraError::InvalidConfig(format!(
+                "{operation} output '{output_local_id}' must differ from its source"
+            )));
+        }
+        self.ensure_data_dir().await?;
+        self.check_output_id(output_local_id, overwrite).await?;
+        let source = self.load_finished(source_local_id).await?;
+        if !source.file_path.exists() {
+            return Err(SoraError::VideoNotFound(source_local_id.to_string()));
+        }
+        Ok(source)
+    }
+
+    /// Flag a clip rendered by a provider other than the active one; continuing it seeds that
+    /// provider's frame into a different model.
+    fn check_provider(&self, clip: &VideoMetadata) -> Result<(), SoraError> {
+        let active = self.backend.kind();
+        if clip.backend == active {
+            return Ok(());
+        }
+        if self.strict_provider {
+            return Err(SoraError::ProviderMismatch {
+                local_id: clip.local_id.clone(),
+                recorded: clip.backend,
+                expected: active,
+            });
+        }
+        warn!(
+            id = %clip.local_id,
+            recorded = ?clip.backend,
+            active = ?active,
+            "continuing a clip rendered by a different provider"
+        );
+        Ok(())
+    }
+
+    /// Flag stitches that mix providers, whose clips often differ in codec settings.
+    fn check_mixed_providers(&self, sources: &[VideoMetadata]) -> Result<(), SoraError> {
+        let first = &sources[0];
+        let Some(other) = sources.iter().find(|clip| clip.backend != first.backend) else {
+            return Ok(());
+        };
+        if self.strict_provider {
+            return Err(SoraError::ProviderMismatch {
+                local_id: other.local_id.clone(),
+                recorded: other.backend,
+                expected: first.backend,
+            });
+        }
+        warn!(
+            first = %first.local_id,
+            other = %other.local_id,
+            "stitching clips from different providers ({:?} and {:?})",
+            first.backend,
+            other.backend
+        );
+        Ok(())
+    }
+
+    /// Refuse to write a locally produced clip over an existing one unless `overwrite` is set,
+    /// matching how renders refuse an existing local id.
+    async fn check_output_id(&self, local_id: &str, overwrite: bool) -> Result<(), SoraError> {
+        check_local_id(local_id)?;
+        if overwrite {
+            return Ok(());
+        }
+        if self.store.exists(local_id).await?
+            || fs::try_exists(self.data_dir.join(format!("{local_id}.mp4"))).await?
+        {
+            return Err(SoraError::InvalidConfig(format!(
+                "local id '{local_id}' already exists (pass overwrite to replace it)"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Metadata for a clip produced locally from `source` (annotations, edits, and the like).
+    fn derived_metadata(
+        &self,
+        source: &VideoMetadata,
+        local_id: &str,
+        file_path: PathBuf,
+    ) -> VideoMetadata {
+        VideoMetadata {
+            local_id: local_id.to_string(),
+            remote_id: String::new(),
+            file_path,
+            parent: Some(source.local_id.clone()),
+            duration_delta: None,
+            sources: Vec::new(),
+            remix_of: None,
+            sha256: None,
+            seed_source: None,
+            tags: Vec::new(),
+            seed_frame: None,
+            samples: Vec::new(),
+            storage_uris: Vec::new(),
+            inline_bytes: None,
+            local_created_at: recording_millis(),
+            carried_audio: None,
+            pending: false,
+            notes: None,
+            extra: serde_json::Map::new(),
+            ..source.clone()
+        }
+    }
+
+    fn stitched_metadata(
+        &self,
+        local_id: &str,
+        sources: &[VideoMetadata],
+        file_path: PathBuf,
+    ) -> VideoMetadata {
+        let first = &sources[0];
+        VideoMetadata {
+            local_id: local_id.to_string(),
+            remote_id: String::new(),
+            prompt: String::new(),
+            model: first.model.clone(),
+            seconds: sources.iter().map(|source| source.seconds).sum(),
+            size: first.size.clone(),
+            created_at: None,
+            file_path,
+            parent: None,
+            backend: first.backend,
+            created_at_source: ValueSource::Local,
+            seconds_source: ValueSource::Local,
+            duration_delta: None,
+            sources: sources
+                .iter()
+                .map(|source| source.local_id.clone())
+                .collect(),
+            remix_of: None,
+            sha256: None,
+            seed_source: None,
+            tags: Vec::new(),
+            seed_frame: None,
+            samples: Vec::new(),
+            storage_uris: Vec::new(),
+            inline_bytes: None,
+            local_created_at: recording_millis(),
+            carried_audio: None,
+            pending: false,
+            notes: None,
+            original_prompt: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Join files with ffmpeg's concat demuxer, stream-copying whatever `encode` leaves alone.
+    /// Audio is dropped when `with_audio` is false.
+    async fn concat_demux(
+        &self,
+        output_local_id: &str,
+        inputs: &[PathBuf],
+        encode: &EncodeSettings,
+        with_audio: bool,
+        output_path: &Path,
+    ) -> Result<(), SoraError> {
+        let manifest_file = self
+            .scratch_file(&format!("concat-{output_local_id}.txt"))
+            .await?;
+
+        let mut manifest = String::new();
+        for input in inputs {
+            let abs_path = fs::canonicalize(input).await?;
+            manifest.push_str(&concat_manifest_entry(&abs_path));
+        }
+
+        fs::write(manifest_file.path(), manifest).await?;
+
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-y")
+            .arg("-f")
+            .arg("concat")
+            .arg("-safe")
+            .arg("0")
+            .arg("-i")
+            .arg(manifest_file.path());
+        if encode.touches_video() {
+            encode.apply_video(&mut command);
+        } else {
+            command.arg("-c:v").arg("copy");
+        }
+        if with_audio {
+            encode.apply_audio(&mut command, "copy");
+        } else {
+            command.arg("-an");
+        }
+        let status = command
+            .arg(output_path)
+            .run_media(self.print_ffmpeg)
+            .await?;
+        if self.keep_manifest {
+            let path = manifest_file.keep();
+            info!(path = %path.display(), "kept concat manifest");
+        } else {
+            drop(manifest_file);
+        }
+
+        if !status.success() {
+            return Err(SoraError::FfmpegConcatFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Join long input lists in two passes so no single ffmpeg call sees them all: each run of
+    /// `chunk_size` inputs is stitched into a scratch file (several at once), then those are
+    /// stitched into the output. Video is encoded, if at all, in the first pass only; audio is
+    /// copied or kept lossless there and encoded once, in the second, where the fades and
+    /// carried audio at the chunk boundaries are applied.
+    async fn concat_chunked(
+        &self,
+        output_local_id: &str,
+        inputs: &[PathBuf],
+        shaping: &AudioShaping,
+        encode: &EncodeSettings,
+        chunk_size: usize,
+        output_path: &Path,
+    ) -> Result<(), SoraError> {
+        let ranges: Vec<_> = (0..inputs.len())
+            .step_by(chunk_size)
+            .map(|start| start..(start + chunk_size).min(inputs.len()))
+            .collect();
+        let mut parts = Vec::with_capacity(ranges.len());
+        for index in 0..ranges.len() {
+            // Matroska, since rebuilt chunk audio is FLAC.
+            parts.push(
+                self.scratch_file(&format!("chunk-{output_local_id}-{index}.mkv"))
+                    .await?,
+            );
+        }
+        let chunk_encode = &chunk_pass_encode(encode);
+        debug!(
+            id = output_local_id,
+            chunks = ranges.len(),
+            "stitching in chunks"
+        );
+
+        let results: Vec<Result<bool, SoraError>> =
+            futures_util::stream::iter(ranges.iter().zip(&parts).enumerate())
+                .map(|(index, (range, part))| async move {
+                    let mut carried = shaping.carried[range.clone()].to_vec();
+                    // The chunk's first join is made in the second pass.
+                    carried[0] = None;
+                    let chunk_shaping = AudioShaping {
+                        fade: shaping.fade.filter(|_| range.len() > 1),
+                        sample_rate: shaping.sample_rate,
+                        channels: shaping.channels,
+                        carried,
+                    };
+                    self.concat_with_audio_graph(
+                        &format!("{output_local_id}-chunk{index}"),
+                        &inputs[range.clone()],
+                        &chunk_shaping,
+                        chunk_encode,
+                        "flac",
+                        part.path(),
+                    )
+                    .await
+                })
+                .buffer_unordered(self.probe_concurrency)
+                .collect()
+                .await;
+        let rebuilt = results
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+            .contains(&true);
+
+        let part_paths: Vec<PathBuf> = parts.iter().map(|part| part.path().to_path_buf()).collect();
+        let join_shaping = AudioShaping {
+            fade: shaping.fade,
+            sample_rate: shaping.sample_rate,
+            channels: shaping.channels,
+            carried: ranges
+                .iter()
+                .map(|range| shaping.carried[range.start].clone())
+                .collect(),
+        };
+        self.concat_with_audio_graph(
+            output_local_id,
+            &part_paths,
+            &join_shaping,
+            &join_pass_encode(encode, rebuilt),
+            "aac",
+            output_path,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Concatenate with the demuxer, rebuilding the audio track when it needs fades at the joins
+    /// or when the inputs' sample rates and channel layouts disagree (which the demuxer would
+    /// mangle). Video is stream-copied (or encoded once, in the first pass, when settings are
+    /// given), and fades (unlike an overlapping crossfade) keep audio the same length so it
+    /// stays in sync. Falls back to a plain demuxer concat when inputs can't be probed or some
+    /// lack audio. A rebuilt track uses `audio_codec` unless `encode` names one; returns
+    /// whether the track was rebuilt.
+    async fn concat_with_audio_graph(
+        &self,
+        output_local_id: &str,
+        inputs: &[PathBuf],
+        shaping: &AudioShaping,
+        encode: &EncodeSettings,
+        audio_codec: &str,
+        output_path: &Path,
+    ) -> Result<bool, SoraError> {
+        let mut infos = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            match probe_media(input).await {
+                Ok(info) if info.has_audio() && info.duration_seconds.is_some() => infos.push(info),
+                Ok(_) => {
+                    debug!(path = %input.display(), "clip lacks audio or duration; copying audio");
+                    break;
+                }
+                Err(err) => {
+                    debug!(path = %input.display(), error = %err, "unable to probe; copying audio");
+                    break;
+                }
+            }
+        }
+        let carries = shaping.carried.iter().any(Option::is_some);
+        let mut format = pick_audio_format(&infos, shaping.sample_rate, shaping.channels);
+        if carries && format.is_none() {
+            // acrossfade needs both sides in one format, and the inputs already share theirs.
+            format = infos.first().and_then(|info| {
+                Some(AudioFormat {
+                    sample_rate: info.sample_rate?,
+                    channels: info.channels?,
+                })
+            });
+        }
+        let carries = carries && format.is_some();
+        if infos.len() < inputs.len() || (shaping.fade.is_none() && format.is_none()) {
+            self.concat_demux(output_local_id, inputs, encode, true, output_path)
+                .await?;
+            return Ok(false);
+        }
+
+        let staging = self
+            .scratch_file(&format!("stitch-{output_local_id}.mp4"))
+            .await?;
+        let staging_path = staging.path();
+        let video_only = EncodeSettings {
+            acodec: None,
+            abitrate: None,
+            ..encode.clone()
+        };
+        self.concat_demux(output_local_id, inputs, &video_only, false, staging_path)
+            .await?;
+
+        let last = inputs.len() - 1;
+        // A carried join is already smoothed by its crossfade, so it skips the plain fades.
+        let carried_into = |index: usize| {
+            carries
+                && shaping.carried.get(index).is_some_and(Option::is_some)
+                && infos[index].duration_seconds.unwrap_or_default() > CARRY_AUDIO_SECONDS
+        };
+        let mut tails = Vec::new();
+        let mut graph = String::new();
+        for (index, info) in infos.iter().enumerate() {
+            let mut filters = Vec::new();
+            if let Some(format) = format {
+                filters.push(format.filter());
+            }
+            if let Some(fade) = shaping.fade.map(|fade| fade.as_secs_f64()) {
+                if index > 0 && !carried_into(index) {
+                    filters.push(format!("afade=t=in:st=0:d={fade}"));
+                }
+                if index < last && !carried_into(index + 1) {
+                    let duration = info.duration_seconds.unwrap_or_default();
+                    let start = (duration - fade).max(0.0);
+                    filters.push(format!("afade=t=out:st={start}:d={fade}"));
+                }
+            }
+            if filters.is_empty() {
+                filters.push("anull".to_string());
+            }
+            if carried_into(index)
+                && let (Some(format), Some(Some(tail))) = (format, shaping.carried.get(index))
+            {
+                // Starts on the parent's tail and crossfades into the clip, keeping its length.
+                let tail_input = inputs.len() + 1 + tails.len();
+                tails.push(tail.clone());
+                graph.push_str(&format!(
+                    "[{tail_input}:a]{}[t{index}];[{}:a]{}[c{index}];\
+                     [t{index}][c{index}]acrossfade=d={CARRY_AUDIO_SECONDS}[a{index}];",
+                    format.filter(),
+                    index + 1,
+                    filters.join(",")
+                ));
+            } else {
+                graph.push_str(&format!(
+                    "[{}:a]{}[a{index}];",
+                    index + 1,
+                    filters.join(",")
+                ));
+            }
+        }
+        for index in 0..inputs.len() {
+            graph.push_str(&format!("[a{index}]"));
+        }
+        graph.push_str(&format!("concat=n={}:v=0:a=1[a]", inputs.len()));
+
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-v")
+            .arg("error")
+            .arg("-y")
+            .arg("-i")
+            .arg(staging_path);
+        for input in inputs.iter().chain(&tails) {
+            command.arg("-i").arg(input);
+        }
+        command
+            .arg("-filter_complex")
+            .arg(graph)
+            .arg("-map")
+            .arg("0:v")
+            .arg("-map")
+            .arg("[a]")
+            .arg("-c:v")
+            .arg("copy");
+        encode.apply_audio(&mut command, audio_codec);
+        let status = command
+            .arg(output_path)
+            .run_media(self.print_ffmpeg)
+            .await?;
+        drop(staging);
+
+        if !status.success() {
+            return Err(SoraError::FfmpegConcatFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+
+        Ok(true)
+    }
+
+    /// Probe a freshly rendered clip and return how far its duration strays from the request.
+    async fn measure_duration_delta(&self, video_path: &Path, requested: u32) -> Option<f64> {
+        match probe_media(video_path).await {
+            Ok(MediaInfo {
+                duration_seconds: Some(actual),
+                ..
+            }) => Some(actual - f64::from(requested)),
+            Ok(_) => {
+                warn!(path = %video_path.display(), "ffprobe reported no duration");
+                None
+            }
+            Err(err) => {
+                warn!(path = %video_path.display(), error = %err, "unable to probe clip duration");
+                None
+            }
+        }
+    }
+
+    /// Warn about (or, in strict mode, reject) clips whose duration is outside the tolerance.
+    fn enforce_duration(&self, metadata: &VideoMetadata, requested: u32) -> Result<(), SoraError> {
+        let Some(delta) = metadata.duration_delta else {
+            return Ok(());
+        };
+        if delta.abs() <= self.duration_tolerance {
+            return Ok(());
+        }
+
+        let actual = f64::from(requested) + delta;
+        if self.strict_duration {
+            return Err(SoraError::DurationMismatch {
+                local_id: metadata.local_id.clone(),
+                requested,
+                actual,
+            });
+        }
+        warn!(
+            id = %metadata.local_id,
+            requested,
+            actual,
+            "rendered clip duration differs from the request"
+        );
+        Ok(())
+    }
+
+    /// Capture a single frame of a clip at `at` and write it to `output_path`, e.g. for a
+    /// poster or thumbnail.
+    ///
+    /// Without `options` the output extension picks the format, and the configured seed frame
+    /// quality carries over when the formats match. Relative output paths resolve under the
+    /// data directory, as with downloads.
+    pub async fn extract_frame(
+        &self,
+        local_id: &str,
+        at: TimeSpec,
+        output_path: &Path,
+        options: Option<FrameOptions>,
+    ) -> Result<PathBuf, SoraError> {
+        let options = options.unwrap_or_else(|| {
+            let format = ImageFormat::from_path(output_path);
+            if format == self.frame_options.format {
+                self.frame_options
+            } else {
+                FrameOptions {
+                    format,
+                    quality: None,
+                }
+            }
+        });
+        options.validate()?;
+        let metadata = self.load_finished(local_id).await?;
+        let seconds = resolve_time(&metadata.file_path, at).await?;
+        let output_path = self.data_dir.join(output_path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        self.frame_extractor
+            .extract_frame(
+                &metadata.file_path,
+                FrameTime::At(seconds),
+                &options,
+                &output_path,
+            )
+            .await
+    }
+
+    /// Save the first frame of each clip as `out_dir/<id>.png`, for comparing branches at a
+    /// glance. With `None`, every clip is exported in recording order. Frames are extracted
+    /// concurrently and results come back in the order of the clips.
+    ///
+    /// A relative `out_dir` resolves under the data directory, as with downloads.
+    pub async fn export_first_frames(
+        &self,
+        local_ids: Option<&[String]>,
+        out_dir: &Path,
+    ) -> Result<Vec<(String, Result<PathBuf, SoraError>)>, SoraError> {
+        let ids = match local_ids {
+            Some(ids) => ids.to_vec(),
+            None => self
+                .list_videos()
+                .await?
+                .into_iter()
+                .map(|metadata| metadata.local_id)
+                .collect(),
+        };
+        let out_dir = self.data_dir.join(out_dir);
+        fs::create_dir_all(&out_dir).await?;
+        let options = FrameOptions {
+            format: ImageFormat::Png,
+            quality: None,
+        };
+        let out_dir = &out_dir;
+        Ok(self
+            .map_clips(ids, |local_id| async move {
+                let metadata = self.load_finished(&local_id).await?;
+                if !fs::try_exists(&metadata.file_path).await? {
+                    return Err(SoraError::VideoNotFound(local_id));
+                }
+                let output_path = out_dir.join(format!("{local_id}.png"));
+                self.frame_extractor
+                    .extract_frame(
+                        &metadata.file_path,
+                        FrameTime::At(0.0),
+                        &options,
+                        &output_path,
+                    )
+                    .await
+            })
+            .await)
+    }
+
+    /// Tile evenly spaced, timestamp-labelled frames from the last `seconds` of a clip into one
+    /// image, for picking a continuation point to pass as [`ContinueVideoRequest::seed_at`].
+    ///
+    /// Relative output paths resolve under the data directory, as with downloads.
+    pub async fn tail_contact_sheet(
+        &self,
+        local_id: &str,
+        seconds: f32,
+        output_path: &Path,
+    ) -> Result<ContactSheet, SoraError> {
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return Err(SoraError::InvalidConfig(format!(
+                "contact sheet window must be a positive number of seconds (got {seconds})"
+            )));
+        }
+        let metadata = self.load_finished(local_id).await?;
+        let duration = probe_media(&metadata.file_path)
+            .await?
+            .duration_seconds
+            .ok_or_else(|| SoraError::FfprobeFailed("clip has no duration".to_string()))?;
+        // Stay a frame short of the end, where a seek can land past the last decodable frame.
+        let end = (duration - 0.04).max(0.0);
+        let start = (end - f64::from(seconds)).max(0.0);
+        let step = (end - start) / f64::from(CONTACT_SHEET_TILES - 1);
+        let timestamps: Vec<f64> = (0..CONTACT_SHEET_TILES)
+            .map(|index| start + step * f64::from(index))
+            .collect();
+
+        let output_path = self.data_dir.join(output_path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let font = resolve_font(None)
+            .map(|font| format!(":fontfile='{}'", escape_filter_path(&font)))
+            .unwrap_or_default();
+        let mut graph = String::new();
+        for (index, time) in timestamps.iter().enumerate() {
+            graph.push_str(&format!(
+                "[{index}:v]trim=end_frame=1,scale={CONTACT_SHEET_TILE_WIDTH}:-2,\
+                 drawtext=text='{time:.3}s':x=8:y=8:fontsize=20:fontcolor=white:\
+                 box=1:boxcolor=black@0.5{font}[f{index}];"
+            ));
+        }
+        for index in 0..timestamps.len() {
+            graph.push_str(&format!("[f{index}]"));
+        }
+        graph.push_str(&format!(
+            "concat=n={}:v=1:a=0,tile={CONTACT_SHEET_COLUMNS}x{}",
+            timestamps.len(),
+            CONTACT_SHEET_TILES.div_ceil(CONTACT_SHEET_COLUMNS)
+        ));
+
+        let mut command = Command::new("ffmpeg");
+        command.arg("-v").arg("error").arg("-y");
+        for time in &timestamps {
+            command
+                .arg("-ss")
+                .arg(format!("{time:.3}"))
+                .arg("-i")
+                .arg(&metadata.file_path);
+        }
+        let status = command
+            .arg("-filter_complex")
+            .arg(graph)
+            .arg("-frames:v")
+            .arg("1")
+            .arg(&output_path)
+            .run_media(self.print_ffmpeg)
+            .await?;
+        if !status.success() {
+            return Err(SoraError::FfmpegFailed(format!(
+                "ffmpeg exited with status {status}"
+            )));
+        }
+
+        Ok(ContactSheet {
+            path: output_path,
+            timestamps,
+            columns: CONTACT_SHEET_COLUMNS,
+        })
+    }
+
+    /// Copy the last [`CARRY_AUDIO_SECONDS`] of `parent`'s audio into a scratch file, or `None`
+    /// when the parent has no audio to carry.
+    async fn extract_tail_audio(
+        &self,
+        parent: &VideoMetadata,
+        local_id: &str,
+    ) -> Result<Option<ScratchFile>, SoraError> {
+        if !probe_media(&parent.file_path).await?.has_audio() {
+            warn!(
+                parent = %parent.local_id,
+                "parent clip has no audio; nothing to carry into {local_id}"
+            );
+            return Ok(None);
+        }
+        let tail = self.scratch_file(&format!("carry-{local_id}.m4a")).await?;
+        let status = Command::new("ffmpeg")
+            .arg("-v")
+            .arg("error")
+            .arg("-y")
+            .arg("-sseof")
+            .arg(format!("-{CARRY_AUDIO_SECONDS}"))
+            .arg("-i")
+            .arg(&parent.file_path)
+            .arg("-vn")
+            .arg("-c:a")
+            .arg("aac")
+            .arg(tail.path())
+            .run_media(self.print_ffmpeg)
+            .await?;
+        if !status.success() {
+            return Err(SoraError::FfmpegFailed(format!(
+                "extracting tail audio exited with status {status}"
+            )));
+        }
+        Ok(Some(tail))
+    }
+
+    async fn extract_seed_frame(
+        &self,
+        video_path: &Path,
+        local_id: &str,
+        at: FrameTime,
+    ) -> Result<ScratchFile, SoraError> {
+        let frame = self
+            .scratch_file(&format!(
+                "{local_id}_seed.{}",
+                self.frame_options.format.extension()
+            ))
+            .await?;
+        let produced = self
+            .frame_extractor
+            .extract_frame(video_path, at, &self.frame_options, frame.path())
+            .await?;
+        if produced == frame.path() {
+            Ok(frame)
+        } else {
+            Ok(ScratchFile(produced))
+        }
+    }
+}
+
+/// Several [`VideoManager`]s built from one configuration, one per provider, for rendering the
+/// same request on each backend side by side.
+///
+/// Each provider keeps its clips in its own subdirectory of the data dir (`videos/sora`,
+/// `videos/veo`), so ids never collide. Provider-specific settings such as `model` and `size`
+/// apply to every provider, so leave them unset to get each provider's default.
+pub struct MultiManager {
+    managers: std::collections::HashMap<ProviderKind, VideoManager>,
+}
+
+impl MultiManager {
+    /// Build a manager for each of `providers`, sharing `config`'s credentials and settings.
+    pub fn new(config: ContinuatorConfig, providers: &[ProviderKind]) -> Result<Self, SoraError> {
+        if providers.is_empty() {
+            return Err(SoraError::InvalidConfig(
+                "MultiManager needs at least one provider".to_string(),
+            ));
+        }
+        let data_dir = config
+            .data_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("videos"));
+        let mut managers = std::collections::HashMap::new();
+        for &provider in providers {
+            let manager = VideoManager::new(ContinuatorConfig {
+                provider: Some(provider),
+                data_dir: Some(data_dir.join(provider.as_str())),
+                ..config.clone()
+            })?;
+            managers.insert(provider, manager);
+        }
+        Ok(Self { managers })
+    }
+
+    /// The manager for `provider`, or an error if it wasn't configured.
+    pub fn manager(&self, provider: ProviderKind) -> Result<&VideoManager, SoraError> {
+        self.managers.get(&provider).ok_or_else(|| {
+            SoraError::UnsupportedOperation(format!(
+                "provider {} is not configured on this MultiManager",
+                provider.as_str()
+            ))
+        })
+    }
+
+    /// Providers this manager can render with.
+    pub fn providers(&self) -> impl Iterator<Item = ProviderKind> + '_ {
+        self.managers.keys().copied()
+    }
+
+    /// Generate a brand-new clip on `provider`; see [`VideoManager::create_video`].
+    pub async fn create_on(
+        &self,
+        provider: ProviderKind,
+        request: CreateVideoRequest,
+    ) -> Result<VideoMetadata, SoraError> {
+        self.manager(provider)?.create_video(request).await
+    }
+
+    /// Continue a clip on `provider`; the parent must live in that provider's directory. See
+    /// [`VideoManager::continue_video`].
+    pub async fn continue_on(
+        &self,
+        provider: ProviderKind,
+        request: ContinueVideoRequest,
+    ) -> Result<VideoMetadata, SoraError> {
+        self.manager(provider)?.continue_video(request).await
+    }
+}
+
+/// Type-safe variants for downloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoVariant {
+    Video,
+    Thumbnail,
+    Spritesheet,
+}
+
+impl VideoVariant {
+    /// Sora's `variant` query value for this asset; empty for the video itself. Override with
+    /// [`ContinuatorConfig::sora_variant_params`].
+    pub fn default_query_value(self) -> &'static str {
+        match self {
+            VideoVariant::Video => "",
+            VideoVariant::Thumbnail => "thumbnail",
+            VideoVariant::Spritesheet => "spritesheet",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VideoJob {
+    pub id: String,
+    pub object: Option<String>,
+    pub created_at: Option<i64>,
+    pub status: VideoStatus,
+    pub model: String,
+    pub progress: Option<f64>,
+    #[serde(deserialize_with = "deserialize_optional_u32", default)]
+    pub seconds: Option<u32>,
+    pub size: Option<String>,
+    pub error: Option<ApiError>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiError {
+    pub message: Option<String>,
+    pub code: Option<String>,
+    /// Request parameter the error is about, e.g. `size`.
+    pub param: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VideoStatus {
+    Queued,
+    InProgress,
+    Completed,
+    Failed,
+    Canceled,
+    Unknown(String),
+}
+
+fn deserialize_optional_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let opt = Option::<serde_json::Value>::deserialize(deserializer)?;
+    if let Some(value) = opt {
+        match value {
+            serde_json::Value::Number(n) => n
+                .as_u64()
+                .map(|n| n as u32)
+                .ok_or_else(|| serde::de::Error::custom("seconds field is not a valid u32"))
+                .map(Some),
+            serde_json::Value::String(s) => s
+                .parse::<u32>()
+                .map(Some)
+                .map_err(|_| serde::de::Error::custom("seconds field string is not a valid u32")),
+            _ => Err(serde::de::Error::custom(
+                "unexpected type for seconds field",
+            )),
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+impl<'de> Deserialize<'de> for VideoStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        let status = match value.as_str() {
+            "queued" => VideoStatus::Queued,
+            "in_progress" => VideoStatus::InProgress,
+            "completed" => VideoStatus::Completed,
+            "failed" => VideoStatus::Failed,
+            "canceled" => VideoStatus::Canceled,
+            other => VideoStatus::Unknown(other.to_string()),
+        };
+        Ok(status)
+    }
+}
+
+impl Serialize for VideoStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl VideoStatus {
+    /// The status as the Sora API spells it.
+    pub fn as_str(&self) -> &str {
+        match self {
+            VideoStatus::Queued => "queued",
+            VideoStatus::InProgress => "in_progress",
+            VideoStatus::Completed => "completed",
+            VideoStatus::Failed => "failed",
+            VideoStatus::Canceled => "canceled",
+            VideoStatus::Unknown(other) => other.as_str(),
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            VideoStatus::Completed | VideoStatus::Failed | VideoStatus::Canceled
+        )
+    }
+}
+
+struct ApiCreateRequest {
+    prompt: String,
+    model: String,
+    seconds: u32,
+    size: String,
+    input_reference: Option<ReferenceSource>,
+    reference_field: String,
+}
+
+impl ApiCreateRequest {
+    fn build_form(&self) -> Result<multipart::Form, SoraError> {
+        let mut form = multipart::Form::new()
+            .text("model", self.model.clone())
+            .text("prompt", self.prompt.clone())
+            .text("seconds", self.seconds.to_string())
+            .text("size", self.size.clone());
+
+        if let Some(reference) = &self.input_reference {
+            let (data, mime, file_name) = reference.load_blocking()?;
+            let part = multipart::Part::bytes(data)
+                .file_name(file_name)
+                .mime_str(&mime)
+                .map_err(SoraError::Request)?;
+            form = form.part(self.reference_field.clone(), part);
+        }
+
+        Ok(form)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SoraClient {
+    http: reqwest::Client,
+    api_key: String,
+    policy: Arc<RequestPolicy>,
+    /// `variant` query value for each downloadable asset; empty sends none.
+    variant_params: std::collections::HashMap<VideoVariant, String>,
+}
+
+impl SoraClient {
+    fn new(
+        http: reqwest::Client,
+        api_key: String,
+        policy: Arc<RequestPolicy>,
+        variant_params: std::collections::HashMap<VideoVariant, String>,
+    ) -> Self {
+        Self {
+            http,
+            api_key,
+            policy,
+            variant_params,
+        }
+    }
+
+    async fn create_video(&self, request: &mut ApiCreateRequest) -> Result<VideoJob, SoraError> {
+        let url = format!("{OPENAI_API_BASE}/videos");
+        let response = self
+            .policy
+            .send(|| {
+                Ok(self
+                    .http
+                    .post(&url)
+                    .bearer_auth(&self.api_key)
+                    .multipart(request.build_form()?))
+            })
+            .await?;
+
+        Self::handle_response(response, None).await
+    }
+
+    async fn remix_video(&self, video_id: &str, prompt: &str) -> Result<VideoJob, SoraError> {
+        let url = format!("{OPENAI_API_BASE}/videos/{video_id}/remix");
+        let body = serde_json::json!({ "prompt": prompt });
+        let response = self
+            .policy
+            .send(|| Ok(self.http.post(&url).bearer_auth(&self.api_key).json(&body)))
+            .await?;
+        Self::handle_response(response, None).await
+    }
+
+    /// Delete a job, which stops it if it is still queued or rendering. Returns `false` when
+    /// the API refuses because the job has already finished or no longer exists.
+    async fn cancel_video(&self, video_id: &str) -> Result<bool, SoraError> {
+        let url = format!("{OPENAI_API_BASE}/videos/{video_id}");
+        let response = self
+            .policy
+            .send(|| Ok(self.http.delete(&url).bearer_auth(&self.api_key)))
+            .await?;
+        let status = response.status();
+        if matches!(status, StatusCode::NOT_FOUND | StatusCode::CONFLICT) {
+            return Ok(false);
+        }
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<no body>".to_string());
+            return Err(sora_api_error(status, text, Some(video_id)));
+        }
+        Ok(true)
+    }
+
+    /// Every video job on the account, newest first, following the list's pagination.
+    async fn list_jobs(&self) -> Result<Vec<VideoJob>, SoraError> {
+        #[derive(Deserialize)]
+        struct Page {
+            data: Vec<VideoJob>,
+            #[serde(default)]
+            has_more: bool,
+        }
+        let url = format!("{OPENAI_API_BASE}/videos");
+        let mut jobs: Vec<VideoJob> = Vec::new();
+        loop {
+            let after = jobs.last().map(|job| job.id.clone());
+            let response = self
+                .policy
+                .send(|| {
+                    let mut request = self
+                        .http
+                        .get(&url)
+                        .bearer_auth(&self.api_key)
+                        .query(&[("limit", "100")]);
+                    if let Some(after) = &after {
+                        request = request.query(&[("after", after)]);
+                    }
+                    Ok(request)
+                })
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                let text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "<no body>".to_string());
+                return Err(sora_api_error(status, text, None));
+            }
+            let page = response.json::<Page>().await?;
+            let done = !page.has_more || page.data.is_empty();
+            jobs.extend(page.data);
+            if done {
+                return Ok(jobs);
+            }
+        }
+    }
+
+    async fn retrieve_video(&self, video_id: &str) -> Result<VideoJob, SoraError> {
+        let url = format!("{OPENAI_API_BASE}/videos/{video_id}");
+        let response = self
+            .policy
+            .send(|| Ok(self.http.get(&url).bearer_auth(&self.api_key)))
+            .await?;
+        Self::handle_response(response, Some(video_id)).await
+    }
+
+    /// Like [`Self::retrieve_video`], but a job the API no longer has (404) is `None` rather
+    /// than an error; any other failure is still an error.
+    async fn find_video(&self, video_id: &str) -> Result<Option<VideoJob>, SoraError> {
+        let url = format!("{OPENAI_API_BASE}/videos/{video_id}");
+        let response = self
+            .policy
+            .send(|| Ok(self.http.get(&url).bearer_auth(&self.api_key)))
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Self::handle_response(response, Some(video_id))
+            .await
+            .map(Some)
+    }
+
+    /// Download a variant to `path`, checking the size against what the server announced.
+    ///
+    /// With `resume`, bytes already in `path` are kept and only the remainder is requested.
+    async fn download_video(
+        &self,
+        video_id: &str,
+        variant: VideoVariant,
+        path: &Path,
+        resume: bool,
+    ) -> Result<(), SoraError> {
+        let offset = match fs::metadata(path).await {
+            Ok(existing) if resume => existing.len(),
+            _ => 0,
+        };
+        let response = self.fetch_content(video_id, variant, offset).await?;
+        let written = match response.status() {
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                // The partial file already holds every byte.
+                Ok(content_range_total(&response).or(Some(offset)))
+            }
+            StatusCode::PARTIAL_CONTENT => {
+                let expected = content_range_total(&response)
+                    .or_else(|| response.content_length().map(|length| length + offset));
+                match fs::OpenOptions::new().append(true).open(path).await {
+                    Ok(mut file) => write_response(response, &mut file).await.map(|_| expected),
+                    Err(err) => Err(err.into()),
+                }
+            }
+            _ => {
+                let expected = response.content_length();
+                match fs::File::create(path).await {
+                    Ok(mut file) => write_response(response, &mut file).await.map(|_| expected),
+                    Err(err) => Err(err.into()),
+                }
+            }
+        };
+        // A dropped connection leaves a partial file worth resuming; a full or unwritable disk
+        // does not, so free the space rather than leave a truncated clip behind.
+        let expected = match written.map_err(|err| err.writing(path)) {
+            Ok(expected) => expected,
+            Err(err @ (SoraError::DiskFull { .. } | SoraError::WriteDenied { .. })) => {
+                let _ = fs::remove_file(path).await;
+                return Err(err);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let actual = fs::metadata(path).await?.len();
+        match expected {
+            Some(expected) if expected != actual => Err(SoraError::IncompleteDownload {
+                path: path.to_path_buf(),
+                expected,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    async fn download_to_writer<W>(
+        &self,
+        video_id: &str,
+        variant: VideoVariant,
+        writer: &mut W,
+    ) -> Result<(), SoraError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let response = self.fetch_content(video_id, variant, 0).await?;
+        let expected = announced_length(&response);
+        let written = write_response(response, writer).await?;
+        check_body_length(expected, written)
+    }
+
+    /// Request a content variant, starting at byte `offset` when it is non-zero.
+    async fn fetch_content(
+        &self,
+        video_id: &str,
+        variant: VideoVariant,
+        offset: u64,
+    ) -> Result<reqwest::Response, SoraError> {
+        let mut url = format!("{OPENAI_API_BASE}/videos/{video_id}/content");
+        let query_value = self.variant_params.get(&variant).map(String::as_str);
+        if let Some(value) = query_value.filter(|value| !value.is_empty()) {
+            url.push_str(&format!("?variant={value}"));
+        }
+
+        let response = self
+            .policy
+            .send(|| {
+                let request = self.http.get(&url).bearer_auth(&self.api_key);
+                Ok(if offset > 0 {
+                    request.header(reqwest::header::RANGE, format!("bytes={offset}-"))
+                } else {
+                    request
+                })
+            })
+            .await?;
+
+        let satisfied_resume = offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE;
+        if !response.status().is_success() && !satisfied_resume {
+            return Err(SoraError::Request(response.error_for_status().unwrap_err()));
+        }
+
+        Ok(response)
+    }
+
+    /// Whether a video's content can still be downloaded: a one-byte ranged request that
+    /// reports 404 or 410 as expired and any other failure as an error.
+    async fn content_available(&self, video_id: &str) -> Result<bool, SoraError> {
+        let url = format!("{OPENAI_API_BASE}/videos/{video_id}/content");
+        let response = self
+            .policy
+            .send(|| {
+                Ok(self
+                    .http
+                    .get(&url)
+                    .bearer_auth(&self.api_key)
+                    .header(reqwest::header::RANGE, "bytes=0-0"))
+            })
+            .await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(true);
+        }
+        if matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE) {
+            return Ok(false);
+        }
+        let text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "<no body>".to_string());
+        Err(sora_api_error(status, text, Some(video_id)))
+    }
+
+    /// Parse a job from `response`. `remote_id` is the job the request was about, if any, so
+    /// failures can name it.
+    async fn handle_response(
+        response: reqwest::Response,
+        remote_id: Option<&str>,
+    ) -> Result<VideoJob, SoraError> {
+        let status = response.status();
+        if status == StatusCode::NO_CONTENT {
+            return Err(SoraError::InvalidConfig(
+                "empty response from server".to_string(),
+            ));
+        }
+
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<no body>".to_string());
+            return Err(sora_api_error(status, text, remote_id));
+        }
+
+        let job = response.json::<VideoJob>().await?;
+        Ok(job)
+    }
+}
+
+/// Error for a rejected Sora API call, keeping the structured `error.code` and `error.param`
+/// from the body when it is the usual JSON envelope.
+fn sora_api_error(status: StatusCode, text: String, remote_id: Option<&str>) -> SoraError {
+    #[derive(Deserialize)]
+    struct Envelope {
+        error: ApiError,
+    }
+    let error = serde_json::from_str::<Envelope>(&text)
+        .map(|envelope| envelope.error)
+        .ok();
+    SoraError::JobFailed {
+        backend: ProviderKind::Sora,
+        remote_id: remote_id.map(str::to_string),
+        code: error.as_ref().and_then(|error| error.code.clone()),
+        param: error.and_then(|error| error.param),
+        status: None,
+        message: format!("API error ({status}): {text}"),
+    }
+}
+
+async fn copy_file_to_writer<W>(path: &Path, writer: &mut W) -> Result<(), SoraError>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut file = fs::File::open(path).await?;
+    tokio::io::copy(&mut file, writer).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Total size from a `Content-Range: bytes a-b/total` (or `bytes */total`) header.
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    parse_content_range_total(
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)?
+            .to_str()
+            .ok()?,
+    )
+}
+
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// Body length the server announced in `Content-Length`.
+fn announced_length(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse().ok())
+        .or_else(|| response.content_length())
+}
+
+/// Stream a response body into a writer chunk by chunk, returning how many bytes were written.
+async fn write_response<W>(response: reqwest::Response, writer: &mut W) -> Result<u64, SoraError>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut written = 0;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        writer.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    writer.flush().await?;
+    Ok(written)
+}
+
+/// Fail when a body streamed somewhere other than a file (which is checked by its size) came
+/// up short of the announced length.
+fn check_body_length(expected: Option<u64>, written: u64) -> Result<(), SoraError> {
+    match expected {
+        Some(expected) if expected != written => Err(SoraError::InvalidResponse(format!(
+            "download ended after {written} of {expected} bytes"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VeoClient {
+    http: reqwest::Client,
+    project: String,
+    location: String,
+    api_version: String,
+    token_source: VeoTokenSource,
+    policy: Arc<RequestPolicy>,
+}
+
+impl VeoClient {
+    fn new(
+        http: reqwest::Client,
+        project: String,
+        location: String,
+        api_version: String,
+        token_source: VeoTokenSource,
+        policy: Arc<RequestPolicy>,
+    ) -> Self {
+        Self {
+            http,
+            project,
+            location,
+            api_version,
+            token_source,
+            policy,
+        }
+    }
+
+    /// Download a Cloud Storage object Veo wrote to `path`, with the same credentials.
+    async fn download_object(&self, uri: &str, path: &Path) -> Result<(), SoraError> {
+        let (bucket, object) = parse_gcs_uri(uri)?;
+        let token = self.token_source.access_token().await?;
+        let url = format!(
+            "https://storage.googleapis.com/storage/v1/b/{bucket}/o/{}?alt=media",
+            percent_encode(&object)
+        );
+        let response = self
+            .policy
+            .send(|| Ok(self.http.get(&url).bearer_auth(&token)))
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<no body>".to_string());
+            return Err(SoraError::InvalidResponse(format!(
+                "downloading {uri} failed ({status}): {text}"
+            )));
+        }
+
+        let expected = response.content_length();
+        let written = match fs::File::create(path).await {
+            Ok(mut file) => write_response(response, &mut file).await.map(drop),
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = written {
+            let _ = fs::remove_file(path).await;
+            return Err(err.writing(path));
+        }
+        let actual = fs::metadata(path).await?.len();
+        match expected {
+            Some(expected) if expected != actual => Err(SoraError::IncompleteDownload {
+                path: path.to_path_buf(),
+                expected,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Vertex AI endpoint for calling `method` on a publisher model.
+    fn model_url(&self, model_id: &str, method: &str) -> String {
+        format!(
+            "https://{location}-aiplatform.googleapis.com/{version}/projects/{project}/locations/{location}/publishers/google/models/{model_id}:{method}",
+            location = self.location,
+            version = self.api_version,
+            project = self.project,
+        )
+    }
+
+    async fn submit_job(
+        &self,
+        model_id: &str,
+        payload: VeoPredictRequest<'_>,
+    ) -> Result<String, SoraError> {
+        let token = self.token_source.access_token().await?;
+        let url = self.model_url(model_id, "predictLongRunning");
+        let response = self
+            .policy
+            .send(|| Ok(self.http.post(&url).bearer_auth(&token).json(&payload)))
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<no body>".to_string());
+            return Err(SoraError::JobFailed {
+                backend: ProviderKind::Veo,
+                remote_id: None,
+                message: format!("predictLongRunning failed ({status}): {body}"),
+                code: None,
+                param: None,
+                status: None,
+            });
+        }
+
+        let envelope: VeoOperationName = response.json().await?;
+        Ok(envelope.name)
+    }
+
+    async fn poll_operation(
+        &self,
+        model_id: &str,
+        operation_name: &str,
+        poll_interval: Duration,
+    ) -> Result<VeoOperationResponse, SoraError> {
+        poll_until(
+            ProviderKind::Veo,
+            self.policy.sleeper.as_ref(),
+            poll_interval,
+            || self.fetch_operation(model_id, operation_name),
+            |status| {
+                if let Some(error) = status.error {
+                    let message = error.message.unwrap_or_else(|| "unknown error".to_string());
+                    return Err(SoraError::JobFailed {
+                        backend: ProviderKind::Veo,
+                        remote_id: Some(operation_name.to_string()),
+                        message,
+                        code: None,
+                        param: None,
+                        status: Some(VideoStatus::Failed),
+                    });
+                }
+                if !status.done.unwrap_or(false) {
+                    return Ok(None);
+                }
+                status.response.map(Some).ok_or_else(|| {
+                    SoraError::InvalidResponse(
+                        "operation completed without response payload".to_string(),
+                    )
+                })
+            },
+        )
+        .await
+    }
+
+    /// Fetch the current state of an operation once.
+    async fn fetch_operation(
+        &self,
+        model_id: &str,
+        operation_name: &str,
+    ) -> Result<VeoFetchResponse, SoraError> {
+        let token = self.token_source.access_token().await?;
+        let url = self.model_url(model_id, "fetchPredictOperation");
+        let body = VeoFetchRequest {
+            operation_name: operation_name.to_string(),
+        };
+        let response = self
+            .policy
+            .send_poll(|| Ok(self.http.post(&url).bearer_auth(&token).json(&body)))
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<no body>".to_string());
+            return Err(SoraError::JobFailed {
+                backend: ProviderKind::Veo,
+                remote_id: Some(operation_name.to_string()),
+                message: format!("fetchPredictOperation failed ({status}): {text}"),
+                code: None,
+                param: None,
+                status: None,
+            });
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum VeoTokenSource {
+    Static(String),
+    Gcloud,
+}
+
+impl VeoTokenSource {
+    async fn access_token(&self) -> Result<String, SoraError> {
+        match self {
+            VeoTokenSource::Static(token) => Ok(token.clone()),
+            VeoTokenSource::Gcloud => {
+                let output = Command::new("gcloud")
+                    .arg("auth")
+                    .arg("print-access-token")
+                    .output()
+                    .await
+                    .map_err(|err| {
+                        if err.kind() == std::io::ErrorKind::NotFound {
+                            SoraError::MissingGcpToken
+                        } else {
+                            SoraError::GcpAuth(err.to_string())
+                        }
+                    })?;
+                if !output.status.success() {
+                    return Err(SoraError::GcpAuth(format!(
+                        "gcloud exited with status {}",
+                        output.status
+                    )));
+                }
+                let token = String::from_utf8(output.stdout)
+                    .map_err(|err| SoraError::GcpAuth(err.to_string()))?
+                    .trim()
+                    .to_string();
+                if token.is_empty() {
+                    return Err(SoraError::MissingGcpToken);
+                }
+                Ok(token)
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct VeoImage {
+    #[serde(rename = "bytesBase64Encoded", skip_serializing_if = "Option::is_none")]
+    bytes_base64_encoded: Option<String>,
+    #[serde(rename = "gcsUri", skip_serializing_if = "Option::is_none")]
+    gcs_uri: Option<String>,
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+}
+
+#[derive(Serialize)]
+struct VeoInstance<'a> {
+    prompt: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<VeoImage>,
+}
+
+#[derive(Serialize)]
+struct VeoParameters<'a> {
+    #[serde(rename = "durationSeconds")]
+    duration_seconds: u32,
+    #[serde(rename = "generateAudio")]
+    generate_audio: bool,
+    #[serde(rename = "storageUri", skip_serializing_if = "Option::is_none")]
+    storage_uri: Option<&'a str>,
+    #[serde(rename = "resolution", skip_serializing_if = "Option::is_none")]
+    resolution: Option<&'a str>,
+    #[serde(rename = "aspectRatio", skip_serializing_if = "Option::is_none")]
+    aspect_ratio: Option<&'a str>,
+    #[serde(rename = "enhancePrompt")]
+    enhance_prompt: bool,
+    #[serde(rename = "sampleCount", skip_serializing_if = "Option::is_none")]
+    sample_count: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct VeoPredictRequest<'a> {
+    instances: Vec<VeoInstance<'a>>,
+    parameters: VeoParameters<'a>,
+}
+
+#[derive(Deserialize)]
+struct VeoOperationName {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct VeoFetchRequest {
+    #[serde(rename = "operationName")]
+    operation_name: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct VeoFetchResponse {
+    name: String,
+    done: Option<bool>,
+    response: Option<VeoOperationResponse>,
+    error: Option<VeoOperationError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VeoOperationError {
+    message: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct VeoOperationResponse {
+    #[serde(rename = "@type")]
+    type_url: Option<String>,
+    #[serde(default)]
+    videos: Vec<VeoGeneratedVideo>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, Clone)]
+struct VeoGeneratedVideo {
+    #[serde(rename = "gcsUri")]
+    gcs_uri: Option<String>,
+    #[serde(rename = "bytesBase64Encoded")]
+    bytes_base64_encoded: Option<String>,
+    #[serde(rename = "mimeType")]
+    mime_type: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_deserialization() {
+        let json = "\"completed\"";
+        let status: VideoStatus = serde_json::from_str(json).unwrap();
+        assert!(matches!(status, VideoStatus::Completed));
+
+        let json = "\"mysterious\"";
+        let status: VideoStatus = serde_json::from_str(json).unwrap();
+        assert!(matches!(status, VideoStatus::Unknown(_)));
+    }
+
+    #[test]
+    fn veo_operation_model() {
+        let name = "projects/p/locations/us-central1/publishers/google/models/veo-3.0-generate-preview/operations/abc";
+        assert_eq!(
+            veo_model_from_operation(name),
+            Some("veo-3.0-generate-preview")
+        );
+        assert_eq!(veo_model_from_operation("video_123"), None);
+    }
+
+    #[test]
+    fn backoff_uses_seeded_full_jitter() {
+        let delays = |seed| {
+            let backoff = Backoff::new(RETRY_BASE_DELAY, RETRY_MAX_DELAY, Some(seed));
+            (0..8)
+                .map(|attempt| backoff.delay(attempt))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(delays(7), delays(7));
+        for (attempt, delay) in delays(7).into_iter().enumerate() {
+            let ceiling = RETRY_BASE_DELAY
+                .saturating_mul(1 << attempt)
+                .min(RETRY_MAX_DELAY);
+            assert!(delay <= ceiling);
+        }
+    }
+
+    #[test]
+    fn frame_options_validation() {
+        assert_eq!(parse_dimensions("320x180").unwrap(), (320, 180));
+        assert!(parse_dimensions("320").is_err());
+        assert!(parse_dimensions("0x180").is_err());
+
+        let jpeg = |quality| FrameOptions {
+            format: ImageFormat::Jpeg,
+            quality: Some(quality),
+        };
+        assert!(jpeg(2).validate().is_ok());
+        assert!(jpeg(40).validate().is_err());
+        assert!(
+            FrameOptions {
+                format: ImageFormat::Png,
+                quality: Some(5),
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    /// Writes a placeholder image so continuations work without ffmpeg.
+    #[derive(Debug)]
+    struct StubExtractor;
+
+    #[async_trait]
+    impl FrameExtractor for StubExtractor {
+        async fn extract_frame(
+            &self,
+            _video_path: &Path,
+            _at: FrameTime,
+            _options: &FrameOptions,
+            output_path: &Path,
+        ) -> Result<PathBuf, SoraError> {
+            fs::write(output_path, MOCK_IMAGE).await?;
+            Ok(output_path.to_path_buf())
+        }
+    }
+
+    async fn mock_manager() -> (VideoManager, PathBuf) {
+        mock_manager_with(ContinuatorConfig::default()).await
+    }
+
+    async fn mock_manager_with(config: ContinuatorConfig) -> (VideoManager, PathBuf) {
+        let (config, data_dir) = mock_config(config).await;
+        let manager = VideoManager::new(config)
+            .unwrap()
+            .with_frame_extractor(StubExtractor);
+        (manager, data_dir)
+    }
+
+    /// `config` pointed at the mock provider, a fresh temp data dir, and a fixture video.
+    async fn mock_config(config: ContinuatorConfig) -> (ContinuatorConfig, PathBuf) {
+        let data_dir = config.data_dir.clone().unwrap_or_else(|| {
+            std::env::temp_dir().join(format!("continuator-test-{}", quick_local_id()))
+        });
+        fs::create_dir_all(&data_dir).await.unwrap();
+        let fixture = data_dir.join("fixture.bin");
+        fs::write(&fixture, b"not really an mp4").await.unwrap();
+        let config = ContinuatorConfig {
+            provider: Some(ProviderKind::Mock),
+            data_dir: Some(data_dir.clone()),
+            poll_interval_ms: Some(0),
+            mock_fixture: Some(fixture),
+            ..config
+        };
+        (config, data_dir)
+    }
+
+    async fn sora_cassette_manager(
+        interactions: Vec<serde_json::Value>,
+    ) -> (VideoManager, PathBuf) {
+        sora_cassette_manager_with(interactions, ContinuatorConfig::default()).await
+    }
+
+    /// A Sora manager in a fresh temp data dir whose requests are answered by `interactions`.
+    /// The cassette sits in `fixtures/`, outside the metadata the manager lists.
+    async fn sora_cassette_manager_with(
+        interactions: Vec<serde_json::Value>,
+        config: ContinuatorConfig,
+    ) -> (VideoManager, PathBuf) {
+        let data_dir = std::env::temp_dir().join(format!("continuator-test-{}", quick_local_id()));
+        let cassette = data_dir.join("fixtures").join("sora.json");
+        fs::create_dir_all(cassette.parent().unwrap())
+            .await
+            .unwrap();
+        let file = serde_json::json!({ "interactions": interactions });
+        fs::write(&cassette, file.to_string()).await.unwrap();
+        let manager = VideoManager::new(ContinuatorConfig {
+            api_key: Some("sk-test".into()),
+            data_dir: Some(data_dir.clone()),
+            poll_interval_ms: config.poll_interval_ms.or(Some(0)),
+            http_cassette: Some(CassetteMode::Replay(cassette)),
+            ..config
+        })
+        .unwrap();
+        (manager, data_dir)
+    }
+
+    /// A recorded Sora answer with a JSON body.
+    fn cassette_exchange(
+        method: &str,
+        path: &str,
+        status: u16,
+        body: serde_json::Value,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "method": method,
+            "url": format!("{OPENAI_API_BASE}{path}"),
+            "status": status,
+            "headers": [["content-type", "application/json"]],
+            "body": BASE64_STANDARD.encode(body.to_string()),
+        })
+    }
+
+    fn sora_job(id: &str, status: &str) -> serde_json::Value {
+        serde_json::json!({ "id": id, "status": status, "model": "sora-2" })
+    }
+
+    #[tokio::test]
+    async fn multi_manager_namespaces_by_provider() {
+        let (config, data_dir) = mock_config(ContinuatorConfig::default()).await;
+        let multi = MultiManager::new(config, &[ProviderKind::Mock]).unwrap();
+        let request = CreateVideoRequest {
+            local_id: "side-by-side".into(),
+            prompt: "a lighthouse at dusk".into(),
+            ..CreateVideoRequest::default()
+        };
+        let metadata = multi
+            .create_on(ProviderKind::Mock, request.clone())
+            .await
+            .unwrap();
+        assert!(metadata.file_path.starts_with(data_dir.join("mock")));
+        assert!(matches!(
+            multi.create_on(ProviderKind::Sora, request).await,
+            Err(SoraError::UnsupportedOperation(_))
+        ));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn sora_lifecycle_replays_from_a_cassette() {
+        let job = |status: &str| {
+            let mut job = sora_job("video_123", status);
+            job["seconds"] = "4".into();
+            job["size"] = "1280x720".into();
+            job
+        };
+        // Large bodies are recorded beside the cassette.
+        let mut content = cassette_exchange(
+            "GET",
+            "/videos/video_123/content",
+            200,
+            serde_json::Value::Null,
+        );
+        content["body"] = "".into();
+        content["body_file"] = "sora.json.bodies/content.bin".into();
+        let (manager, data_dir) = sora_cassette_manager(vec![
+            cassette_exchange("POST", "/videos", 200, job("queued")),
+            cassette_exchange("GET", "/videos/video_123", 200, job("in_progress")),
+            cassette_exchange("GET", "/videos/video_123", 200, job("completed")),
+            content,
+        ])
+        .await;
+        let bodies = data_dir.join("fixtures").join("sora.json.bodies");
+        fs::create_dir_all(&bodies).await.unwrap();
+        fs::write(bodies.join("content.bin"), "mp4 bytes")
+            .await
+            .unwrap();
+
+        let metadata = manager
+            .create_video(CreateVideoRequest {
+                local_id: "replayed".into(),
+                prompt: "a lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(metadata.remote_id, "video_123");
+        assert_eq!(fs::read(&metadata.file_path).await.unwrap(), b"mp4 bytes");
+
+        let unrecorded = manager.cancel_remote("video_456").await;
+        assert!(matches!(unrecorded, Err(SoraError::InvalidResponse(_))));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn clips_list_in_recording_order() {
+        let (manager, data_dir) = mock_manager().await;
+        for id in ["b", "a", "c"] {
+            manager
+                .create_video(CreateVideoRequest {
+                    local_id: id.into(),
+                    prompt: "a lighthouse at dusk".into(),
+                    ..CreateVideoRequest::default()
+                })
+                .await
+                .unwrap();
+        }
+        // Backdate "c" so it predates the others, as an older Sora clip might.
+        let mut older = manager.load_metadata("c").await.unwrap();
+        older.local_created_at -= 60_000;
+        manager.save_metadata(&mut older).await.unwrap();
+
+        let ids: Vec<String> = manager
+            .list_videos()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|clip| clip.local_id)
+            .collect();
+        assert_eq!(ids, ["c", "b", "a"]);
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn split_needs_a_positive_chunk_length() {
+        let (manager, data_dir) = mock_manager().await;
+        let source = data_dir.join("fixture.bin");
+        for chunk_seconds in [0.0, -4.0, f32::NAN] {
+            assert!(matches!(
+                manager.split(&source, "ref", chunk_seconds).await,
+                Err(SoraError::InvalidConfig(_))
+            ));
+        }
+        assert!(matches!(
+            manager
+                .split(&data_dir.join("missing.mp4"), "ref", 4.0)
+                .await,
+            Err(SoraError::VideoNotFound(_))
+        ));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[test]
+    fn config_validation_reports_every_problem() {
+        let config = ContinuatorConfig {
+            provider: Some(ProviderKind::Veo),
+            gcp_project: Some("project".into()),
+            gcp_location: Some("us-central1".into()),
+            gcp_resolution: Some("4k".into()),
+            gcp_storage_uri: Some("s3://bucket".into()),
+            seconds: Some(3),
+            probe_concurrency: Some(0),
+            ..ContinuatorConfig::default()
+        };
+        let problems = config.validate().unwrap_err();
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(
+            problems
+                .iter()
+                .all(|problem| matches!(problem, SoraError::InvalidConfig(_)))
+        );
+
+        let fixed = ContinuatorConfig {
+            gcp_resolution: None,
+            gcp_storage_uri: Some("gs://bucket/renders".into()),
+            seconds: Some(8),
+            probe_concurrency: None,
+            ..config
+        };
+        assert!(fixed.validate().is_ok());
+    }
+
+    #[derive(Debug, Default)]
+    struct MemoryStore(Mutex<std::collections::BTreeMap<String, VideoMetadata>>);
+
+    #[async_trait]
+    impl MetadataStore for MemoryStore {
+        async fn save(&self, metadata: &VideoMetadata) -> Result<(), SoraError> {
+            let mut records = self.0.lock().unwrap();
+            records.insert(metadata.local_id.clone(), metadata.clone());
+            Ok(())
+        }
+
+        async fn load(&self, local_id: &str) -> Result<VideoMetadata, SoraError> {
+            let records = self.0.lock().unwrap();
+            records
+                .get(local_id)
+                .cloned()
+                .ok_or_else(|| SoraError::MetadataNotFound(local_id.to_string()))
+        }
+
+        async fn list(&self) -> Result<Vec<String>, SoraError> {
+            Ok(self.0.lock().unwrap().keys().cloned().collect())
+        }
+
+        async fn delete(&self, local_id: &str) -> Result<(), SoraError> {
+            self.0.lock().unwrap().remove(local_id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn metadata_can_live_in_another_store() {
+        let (manager, data_dir) = mock_manager().await;
+        let manager = manager.with_store(MemoryStore::default());
+        let request = CreateVideoRequest {
+            local_id: "stored".into(),
+            prompt: "a lighthouse at dusk".into(),
+            ..CreateVideoRequest::default()
+        };
+        manager.create_video(request.clone()).await.unwrap();
+        assert!(!data_dir.join("stored.json").exists());
+        assert_eq!(manager.list_videos().await.unwrap().len(), 1);
+        assert!(
+            manager.create_video(request).await.is_err(),
+            "ids stay unique"
+        );
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn polling_stops_at_a_terminal_status() {
+        let (manager, data_dir) = mock_manager().await;
+        let clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: "polled".into(),
+                prompt: "a lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let mut updates = Vec::new();
+        let status = manager
+            .poll_until_terminal(&clip.remote_id, |status, progress| {
+                updates.push((status, progress))
+            })
+            .await
+            .unwrap();
+        assert_eq!(status, VideoStatus::Completed);
+        assert_eq!(updates, [(VideoStatus::Completed, Some(100.0))]);
+        assert!(
+            manager
+                .poll_until_terminal("unknown", |_, _| {})
+                .await
+                .is_err()
+        );
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn scratch_renders_leave_no_metadata() {
+        let (manager, data_dir) = mock_manager().await;
+        let request = CreateVideoRequest {
+            local_id: "try".into(),
+            prompt: "a quick test".into(),
+            ..CreateVideoRequest::default()
+        };
+        let path = manager.create_scratch(request.clone()).await.unwrap();
+        assert_eq!(path, data_dir.join(".tmp/scratch/try.mp4"));
+        assert!(path.exists());
+        // The same id can be reused, and nothing shows up in the project.
+        manager.create_scratch(request).await.unwrap();
+        assert!(manager.list_videos().await.unwrap().is_empty());
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn strict_provider_rejects_foreign_parents() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            strict_provider: true,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "veo-shot".into(),
+                prompt: "rendered elsewhere".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        manager
+            .update_metadata(
+                "veo-shot",
+                MetadataPatch {
+                    backend: Some(ProviderKind::Veo),
+                    ..MetadataPatch::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let err = manager
+            .continue_video(ContinueVideoRequest {
+                parent_local_id: "veo-shot".into(),
+                local_id: "next".into(),
+                prompt: "carry on".into(),
+                ..ContinueVideoRequest::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SoraError::ProviderMismatch {
+                recorded: ProviderKind::Veo,
+                expected: ProviderKind::Mock,
+                ..
+            }
+        ));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[test]
+    fn concat_manifest_paths_escape_quotes() {
+        assert_eq!(
+            concat_manifest_entry(Path::new("/clips/dune's edge.mp4")),
+            "file '/clips/dune'\\''s edge.mp4'\n"
+        );
+    }
+
+    #[test]
+    fn paths_with_spaces_and_quotes_are_escaped() {
+        let dir = Path::new("/tmp/continuator test's");
+        assert_eq!(
+            escape_filter_path(&dir.join("a:b,c.srt")),
+            r"/tmp/continuator test'\\\''s/a\:b,c.srt"
+        );
+        assert_eq!(escape_filter_path(Path::new(r"x\y")), r"x\\y");
+        assert_eq!(
+            concat_manifest_entry(&dir.join("intro.mp4")),
+            "file '/tmp/continuator test'\\''s/intro.mp4'\n"
+        );
+        let mut command = Command::new("ffmpeg");
+        command.arg("-i").arg(dir.join("intro.mp4"));
+        assert_eq!(
+            command_line(&command),
+            r"ffmpeg -i '/tmp/continuator test'\''s/intro.mp4'"
+        );
+    }
+
+    #[tokio::test]
+    async fn flow_validation_reports_every_problem() {
+        let (manager, data_dir) = mock_manager().await;
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "scene-02".into(),
+                prompt: "left over from last time".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let beat = |prompt: &str, size: Option<&str>| FlowBeat {
+            prompt: prompt.into(),
+            model: None,
+            size: size.map(str::to_string),
+            seconds: None,
+            depends_on: None,
+        };
+        let mut request = FlowRequest {
+            id: "scene".into(),
+            start_from: Some("missing".into()),
+            beats: vec![
+                beat(" ", None),
+                beat("the wind picks up", None),
+                beat("night falls", Some("13x37")),
+            ],
+            ..FlowRequest::default()
+        };
+        let issues = manager.validate_flow(&request).await;
+        let beats: Vec<_> = issues.iter().map(|issue| issue.beat).collect();
+        assert_eq!(beats, [None, Some(1), Some(2), Some(3)]);
+        assert!(matches!(issues[0].error, SoraError::MetadataNotFound(_)));
+
+        request.start_from = None;
+        request.resume = true;
+        request.beats = vec![beat("dawn", None), beat("the wind picks up", None)];
+        assert!(manager.validate_flow(&request).await.is_empty());
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn flow_clips_are_found_by_beat_name() {
+        let (manager, data_dir) = mock_manager().await;
+        let create = |local_id: &str| CreateVideoRequest {
+            local_id: local_id.into(),
+            prompt: "a beat".into(),
+            ..CreateVideoRequest::default()
+        };
+        manager.create_video(create("opening")).await.unwrap();
+        for (local_id, parent) in [("scene-01", "opening"), ("scene-03", "scene-01")] {
+            manager
+                .continue_video(ContinueVideoRequest {
+                    parent_local_id: parent.into(),
+                    local_id: local_id.into(),
+                    prompt: "a beat".into(),
+                    ..ContinueVideoRequest::default()
+                })
+                .await
+                .unwrap();
+        }
+        manager.create_video(create("scene-10-alt")).await.unwrap();
+        manager.create_video(create("scene-partial")).await.unwrap();
+
+        assert_eq!(
+            manager.flow_clips("scene", None).await.unwrap(),
+            ["opening", "scene-01", "scene-03"]
+        );
+        assert!(manager.flow_clips("other", None).await.is_err());
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn failed_renders_are_resubmitted() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            render_retries: Some(2),
+            mock_failures: 2,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let request = |local_id: &str| CreateVideoRequest {
+            local_id: local_id.into(),
+            prompt: "flaky".into(),
+            ..CreateVideoRequest::default()
+        };
+        manager.create_video(request("third-time")).await.unwrap();
+
+        let (manager, data_dir_2) = mock_manager_with(ContinuatorConfig {
+            render_retries: Some(1),
+            mock_failures: 2,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let err = manager.create_video(request("gives-up")).await.unwrap_err();
+        assert!(
+            err.to_string().contains("gave up after 2 attempts"),
+            "{err}"
+        );
+
+        let blocked = SoraError::JobFailed {
+            backend: ProviderKind::Sora,
+            remote_id: Some("video_123".into()),
+            message: "blocked".into(),
+            code: Some("moderation_blocked".into()),
+            param: None,
+            status: Some(VideoStatus::Failed),
+        };
+        assert!(!blocked.is_render_failure());
+        let _ = fs::remove_dir_all(&data_dir).await;
+        let _ = fs::remove_dir_all(&data_dir_2).await;
+    }
+
+    #[tokio::test]
+    async fn prompts_get_the_house_style() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            prompt_prefix: Some("Cinematic, 35mm film grain.".into()),
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let wrapped = manager
+            .create_video(CreateVideoRequest {
+                local_id: "styled".into(),
+                prompt: "A lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            wrapped.prompt,
+            "Cinematic, 35mm film grain. A lighthouse at dusk"
+        );
+        assert_eq!(
+            wrapped.original_prompt.as_deref(),
+            Some("A lighthouse at dusk")
+        );
+
+        let plain = manager
+            .create_video(CreateVideoRequest {
+                local_id: "plain".into(),
+                prompt: "A lighthouse at dusk".into(),
+                no_prompt_wrap: true,
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(plain.prompt, "A lighthouse at dusk");
+        assert_eq!(plain.original_prompt, None);
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn no_wait_renders_finish_later() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            checksums: true,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let pending = manager
+            .create_video(CreateVideoRequest {
+                local_id: "later".into(),
+                prompt: "submitted from a script".into(),
+                no_wait: true,
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert!(pending.pending);
+        assert!(manager.load_metadata("later").await.unwrap().pending);
+        assert_eq!(pending.sha256, None);
+        assert!(manager.list_videos().await.unwrap().is_empty());
+        assert_eq!(manager.list_pending().await.unwrap().len(), 1);
+        let stitch = manager
+            .stitch_videos("joined", &["later".to_string()])
+            .await;
+        assert!(matches!(stitch, Err(SoraError::InvalidConfig(_))));
+
+        let finished = manager.finish_pending("later").await.unwrap();
+        assert!(!finished.pending);
+        assert_eq!(finished.remote_id, pending.remote_id);
+        assert!(finished.file_path.exists());
+        assert!(!manager.load_metadata("later").await.unwrap().pending);
+        assert!(finished.sha256.is_some());
+        assert!(manager.finish_pending("later").await.is_err());
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn provenance_filenames() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            include_model_in_filename: true,
+            include_backend_in_filename: true,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let first = manager
+            .create_video(CreateVideoRequest {
+                local_id: "one".into(),
+                prompt: "first".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(first.file_path, data_dir.join("one.mock.mock.mp4"));
+        assert!(first.file_path.exists());
+
+        let second = manager
+            .continue_video(ContinueVideoRequest {
+                parent_local_id: "one".into(),
+                local_id: "two".into(),
+                prompt: "second".into(),
+                ..ContinueVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(second.file_path, data_dir.join("two.mock.mock.mp4"));
+        assert_eq!(manager.list_videos().await.unwrap().len(), 2);
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn mock_backend_create_continue_remix_download() {
+        let (manager, data_dir) = mock_manager().await;
+
+        let blank = manager
+            .create_video(CreateVideoRequest {
+                local_id: "blank".into(),
+                prompt: " \n".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await;
+        assert!(matches!(blank, Err(SoraError::InvalidConfig(_))));
+
+        let first = manager
+            .create_video(CreateVideoRequest {
+                local_id: "one".into(),
+                prompt: "first".into(),
+                seconds: Some(4),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(first.backend, ProviderKind::Mock);
+        assert_eq!(first.seconds, 4);
+        assert!(first.file_path.exists());
+
+        let second = manager
+            .continue_video(ContinueVideoRequest {
+                parent_local_id: "one".into(),
+                local_id: "two".into(),
+                prompt: "second".into(),
+                ..ContinueVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(second.parent.as_deref(), Some("one"));
+        assert_eq!(
+            second.seconds, 4,
+            "continuations inherit the parent's length"
+        );
+        assert_eq!(second.seed_source.as_deref(), Some("one"));
+
+        let match_cut = manager
+            .continue_video(ContinueVideoRequest {
+                parent_local_id: "one".into(),
+                local_id: "cut".into(),
+                prompt: "match cut".into(),
+                seed_from: Some("two".into()),
+                ..ContinueVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(match_cut.parent.as_deref(), Some("one"));
+        assert_eq!(match_cut.seed_source.as_deref(), Some("two"));
+        let mut scratch = fs::read_dir(data_dir.join(".tmp")).await.unwrap();
+        assert!(
+            scratch.next_entry().await.unwrap().is_none(),
+            "seed frames are cleaned up"
+        );
+
+        let remix = manager.remix_video("two", "three", "again").await.unwrap();
+        assert_eq!(remix.remix_of.as_deref(), Some(second.remote_id.as_str()));
+
+        let thumbnail = manager
+            .download_asset("one", VideoVariant::Thumbnail, Path::new("thumb.png"))
+            .await
+            .unwrap();
+        assert_eq!(thumbnail, data_dir.join("thumb.png"));
+        assert_eq!(fs::read(&thumbnail).await.unwrap(), MOCK_IMAGE);
+
+        let ids: Vec<String> = manager
+            .list_videos()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|clip| clip.local_id)
+            .collect();
+        assert_eq!(ids, ["one", "two", "cut", "three"]);
+
+        let clobber = manager.stitch_videos("one", &["two".to_string()]).await;
+        assert!(matches!(clobber, Err(SoraError::InvalidConfig(_))));
+
+        let statuses = manager.remote_status_many(None).await.unwrap();
+        assert!(!statuses.is_empty());
+        for (_, status) in &statuses {
+            assert_eq!(status.as_ref().unwrap().status, VideoStatus::Completed);
+        }
+        assert_eq!(
+            manager.cancel("one").await.unwrap(),
+            CancelOutcome::AlreadyFinished(VideoStatus::Completed)
+        );
+        assert!(manager.cancel_all().await.unwrap().is_empty());
+
+        let edited = manager
+            .update_metadata(
+                "one",
+                MetadataPatch {
+                    prompt: Some("A lighthouse at night".into()),
+                    notes: Some(" best take ".into()),
+                    add_tags: vec!["hero".into(), "hero".into()],
+                    ..MetadataPatch::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.tags, ["hero"]);
+        assert_eq!(edited.remote_id, first.remote_id);
+        let reloaded = manager.get_metadata("one").await.unwrap();
+        assert_eq!(reloaded.prompt, "A lighthouse at night");
+        assert_eq!(reloaded.notes.as_deref(), Some("best take"));
+        let cleared = manager
+            .update_metadata(
+                "one",
+                MetadataPatch {
+                    notes: Some(String::new()),
+                    ..MetadataPatch::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(cleared.notes, None);
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[test]
+    fn audio_format_picks_common_target() {
+        let audio = |sample_rate, channels| MediaInfo {
+            audio_codec: Some("aac".into()),
+            sample_rate: Some(sample_rate),
+            channels: Some(channels),
+            ..MediaInfo::default()
+        };
+        let matched = [audio(48_000, 2), audio(48_000, 2)];
+        assert_eq!(pick_audio_format(&matched, None, None), None);
+        assert_eq!(
+            pick_audio_format(&matched, Some(44_100), None),
+            Some(AudioFormat {
+                sample_rate: 44_100,
+                channels: 2
+            })
+        );
+
+        let mixed = [audio(48_000, 2), audio(24_000, 1)];
+        let format = pick_audio_format(&mixed, None, None).unwrap();
+        assert_eq!((format.sample_rate, format.channels), (48_000, 2));
+        assert_eq!(
+            format.filter(),
+            "aresample=48000,aformat=sample_rates=48000:channel_layouts=stereo"
+        );
+    }
+
+    #[test]
+    fn provider_capabilities_validation() {
+        assert!(
+            ProviderKind::Sora
+                .check_render("sora-2", "1280x720", 12)
+                .is_ok()
+        );
+        assert!(
+            ProviderKind::Sora
+                .check_render("sora-2", "1920x1080", 12)
+                .is_err()
+        );
+        assert!(
+            ProviderKind::Veo
+                .check_render(DEFAULT_VEO_MODEL, "1920x1080", 6)
+                .is_ok()
+        );
+        assert!(
+            ProviderKind::Veo
+                .check_render(DEFAULT_VEO_MODEL, "1280x720", 5)
+                .is_err()
+        );
+        assert!(
+            ProviderKind::Veo
+                .check_render("veo-2.0-generate-001", "1280x720", 5)
+                .is_ok()
+        );
+        assert_eq!(ProviderKind::Sora.default_size("sora-2-pro"), "1792x1024");
+        assert_eq!(ProviderKind::Sora.default_size("sora-3"), DEFAULT_SIZE);
+        for (model, size) in MODEL_SIZES {
+            let provider = if model.starts_with("veo") {
+                ProviderKind::Veo
+            } else {
+                ProviderKind::Sora
+            };
+            assert!(
+                provider.check_size(size).is_ok(),
+                "{model} defaults to {size}"
+            );
+        }
+
+        let (resolution, aspect_ratio) = veo_format(Some("1080p"), None, "720x1280").unwrap();
+        assert_eq!(
+            (resolution.as_str(), aspect_ratio.as_str()),
+            ("1080p", "9:16")
+        );
+        assert_eq!(veo_output_size(&resolution, &aspect_ratio), "1080x1920");
+        assert!(check_veo_format("veo-3.0-generate-001", &resolution, &aspect_ratio).is_ok());
+        assert!(check_veo_format(DEFAULT_VEO_MODEL, &resolution, &aspect_ratio).is_err());
+        assert_eq!(
+            veo_format(None, None, "1920x1080").unwrap(),
+            ("1080p".to_string(), "16:9".to_string())
+        );
+
+        let result = VideoManager::new(ContinuatorConfig {
+            provider: Some(ProviderKind::Veo),
+            gcp_project: Some("project".into()),
+            gcp_location: Some("us-central1".into()),
+            gcp_access_token: Some("token".into()),
+            gcp_resolution: Some("4k".into()),
+            ..ContinuatorConfig::default()
+        });
+        assert!(matches!(result, Err(SoraError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn beat_name_templates() {
+        assert_eq!(
+            render_beat_name(DEFAULT_BEAT_NAME_TEMPLATE, "flow", 3).unwrap(),
+            "flow-03"
+        );
+        assert_eq!(
+            render_beat_name("scene_01_shot_{index:03}", "flow", 12).unwrap(),
+            "scene_01_shot_012"
+        );
+        assert_eq!(render_beat_name("{base}{index}", "b", 7).unwrap(), "b7");
+        assert!(render_beat_name("{base}-{beat}", "b", 1).is_err());
+        assert!(render_beat_name("{base", "b", 1).is_err());
+
+        let request = |template: &str| FlowRequest {
+            id: "flow".into(),
+            beats: vec![FlowBeat::default(), FlowBeat::default()],
+            beat_name_template: Some(template.into()),
+            ..FlowRequest::default()
+        };
+        assert!(flow_beat_ids(&request("{base}-{index}")).is_ok());
+        assert!(flow_beat_ids(&request("{base}-shot")).is_err());
+        assert!(flow_beat_ids(&request("shots/{index}")).is_err());
+    }
+
+    #[test]
+    fn veo_videos_split_inline_and_storage() {
+        let video = |bytes: Option<&str>, uri: Option<&str>| VeoGeneratedVideo {
+            gcs_uri: uri.map(str::to_string),
+            bytes_base64_encoded: bytes.map(str::to_string),
+            mime_type: Some("video/mp4".into()),
+        };
+        let (inline, uris) = split_veo_videos(
+            &[
+                video(None, Some("gs://bucket/a.mp4")),
+                video(Some("b25l"), None),
+                video(Some("dHdv"), None),
+            ],
+            u64::MAX,
+        )
+        .unwrap();
+        assert_eq!(inline, [b"one".to_vec(), b"two".to_vec()]);
+        assert_eq!(uris, ["gs://bucket/a.mp4"]);
+        assert!(split_veo_videos(&[video(Some("!"), None)], 0).is_err());
+    }
+
+    #[test]
+    fn env_var_expansion() {
+        let env = |name: &str| match name {
+            "PROJECT" => Some("demo".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_env_vars("gs://${PROJECT}-videos", &env).unwrap(),
+            "gs://demo-videos"
+        );
+        assert_eq!(
+            expand_env_vars("${UNSET:-videos}/out", &env).unwrap(),
+            "videos/out"
+        );
+        assert_eq!(
+            expand_env_vars("${EMPTY:-fallback}", &env).unwrap(),
+            "fallback"
+        );
+        assert_eq!(
+            expand_env_vars("$HOME is literal", &env).unwrap(),
+            "$HOME is literal"
+        );
+        assert!(expand_env_vars("${UNSET}", &env).is_err());
+        assert!(expand_env_vars("${PROJECT", &env).is_err());
+
+        let config = ContinuatorConfig {
+            gcp_project: Some("${PROJECT}".into()),
+            data_dir: Some("/srv/${PROJECT}/clips".into()),
+            upload_command: Some("gsutil cp {file} gs://${PROJECT}/{id}".into()),
+            ..ContinuatorConfig::default()
+        }
+        .expand_env_with(&env)
+        .unwrap();
+        assert_eq!(config.gcp_project.as_deref(), Some("demo"));
+        assert_eq!(config.data_dir, Some(PathBuf::from("/srv/demo/clips")));
+        assert_eq!(
+            config.upload_command.as_deref(),
+            Some("gsutil cp {file} gs://${PROJECT}/{id}")
+        );
+    }
+
+    /// Sleeper that returns at once but advances its own clock, recording what was asked for.
+    #[derive(Debug)]
+    struct FakeSleeper {
+        start: Instant,
+        slept: Mutex<Duration>,
+    }
+
+    #[async_trait]
+    impl Sleeper for FakeSleeper {
+        async fn sleep(&self, duration: Duration) {
+            *self.slept.lock().unwrap() += duration;
+        }
+
+        fn now(&self) -> Instant {
+            self.start + *self.slept.lock().unwrap()
+        }
+    }
+
+    /// Sleeper for code paths that must never wait on the timer.
+    #[derive(Debug)]
+    struct NoSleeper;
+
+    #[async_trait]
+    impl Sleeper for NoSleeper {
+        async fn sleep(&self, duration: Duration) {
+            panic!("unexpected sleep for {duration:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn zero_poll_interval_never_sleeps() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            sleeper: Some(Arc::new(NoSleeper)),
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: "instant".into(),
+                prompt: "a cached render".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let status = manager
+            .poll_until_terminal(&clip.remote_id, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(status, VideoStatus::Completed);
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_waits_through_injected_sleeper() {
+        let sleeper = FakeSleeper {
+            start: Instant::now(),
+            slept: Mutex::default(),
+        };
+        let limiter = RateLimiter::per_minute(2, sleeper.now());
+        limiter.acquire(&sleeper).await;
+        limiter.acquire(&sleeper).await;
+        assert_eq!(*sleeper.slept.lock().unwrap(), Duration::ZERO);
+
+        // The bucket is empty, so the third request waits for one token: 30s at 2 rpm.
+        limiter.acquire(&sleeper).await;
+        let slept = sleeper.slept.lock().unwrap().as_secs_f64();
+        assert!((slept - 30.0).abs() < 0.5, "slept {slept}s");
+    }
+
+    #[tokio::test]
+    async fn in_memory_reference_images() {
+        let jpeg = ReferenceSource::Bytes {
+            data: vec![0xff, 0xd8, 0xff],
+            mime: "image/jpeg".into(),
+        };
+        jpeg.validate().unwrap();
+        let (data, mime, file_name) = jpeg.load_blocking().unwrap();
+        assert_eq!((data.len(), mime.as_str()), (3, "image/jpeg"));
+        assert_eq!(file_name, "input.jpg");
+        assert_eq!(
+            jpeg.encode_base64().await.unwrap(),
+            ("/9j/".to_string(), "image/jpeg".to_string())
+        );
+
+        let not_image = ReferenceSource::Bytes {
+            data: vec![1],
+            mime: "text/plain".into(),
+        };
+        assert!(matches!(
+            not_image.validate(),
+            Err(SoraError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn data_dir_must_be_a_directory() {
+        let file = std::env::temp_dir().join(format!("continuator-not-a-dir-{}", quick_local_id()));
+        std::fs::write(&file, b"").unwrap();
+        let result = VideoManager::new(ContinuatorConfig {
+            provider: Some(ProviderKind::Mock),
+            data_dir: Some(file.clone()),
+            ..ContinuatorConfig::default()
+        });
+        std::fs::remove_file(&file).unwrap();
+        let Err(SoraError::InvalidConfig(message)) = result else {
+            panic!("expected a configuration error");
+        };
+        assert!(message.starts_with("data_dir exists and is not a directory"));
+    }
+
+    #[test]
+    fn gcs_uris_split_into_bucket_and_object() {
+        let parsed = |uri: &str| parse_gcs_uri(uri).unwrap();
+        let pair = |bucket: &str, object: &str| (bucket.to_string(), object.to_string());
+
+        assert_eq!(parsed("gs://bucket/a.mp4"), pair("bucket", "a.mp4"));
+        assert_eq!(
+            parsed(" gs://my-bucket/renders/2025/sample_0.mp4 "),
+            pair("my-bucket", "renders/2025/sample_0.mp4")
+        );
+        // gs:// names are raw, so a literal `%` stays as it is.
+        assert_eq!(parsed("gs://b/100% take.mp4"), pair("b", "100% take.mp4"));
+        assert_eq!(
+            parsed("https://storage.googleapis.com/my.bucket/clips/take%201%2Bb.mp4"),
+            pair("my.bucket", "clips/take 1+b.mp4")
+        );
+        assert_eq!(
+            parsed("https://storage.googleapis.com/b/%E2%9C%93.mp4?X-Goog-Signature=abc#t=1"),
+            pair("b", "✓.mp4")
+        );
+
+        for bad in [
+            "bucket/a.mp4",
+            "s3://bucket/a.mp4",
+            "gs://",
+            "gs://bucket",
+            "gs://bucket/",
+            "gs://bucket/folder/",
+            "gs:///a.mp4",
+            "gs://Bucket/a.mp4",
+            "https://storage.googleapis.com/b/bad%zz.mp4",
+            "https://storage.googleapis.com/b/truncated%2",
+            "https://example.com/b/a.mp4",
+        ] {
+            assert!(
+                matches!(parse_gcs_uri(bad), Err(SoraError::InvalidConfig(_))),
+                "{bad} should be rejected"
+            );
+        }
+        assert_eq!(
+            percent_encode("renders/clip 1.mp4"),
+            "renders%2Fclip%201.mp4"
+        );
+    }
+
+    #[tokio::test]
+    async fn storage_uris_are_veo_only() {
+        let (manager, data_dir) = mock_manager().await;
+        let err = manager
+            .create_video(CreateVideoRequest {
+                local_id: "final".into(),
+                prompt: "a sweeping dune".into(),
+                storage_uri: Some("gs://finals/".into()),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::UnsupportedOperation(_)));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[test]
+    fn printed_commands_can_be_pasted() {
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-i")
+            .arg("videos/intro 1.mp4")
+            .arg("-vf")
+            .arg("drawtext=text='hi'")
+            .arg("out.mp4");
+        assert_eq!(
+            command_line(&command),
+            r#"ffmpeg -i 'videos/intro 1.mp4' -vf 'drawtext=text='\''hi'\''' out.mp4"#
+        );
+    }
+
+    #[test]
+    fn size_rejections_fall_back_to_the_nearest_size() {
+        assert_eq!(
+            ProviderKind::Sora.nearest_size("sora-2", "1792x1024"),
+            Some("1280x720")
+        );
+        assert_eq!(
+            ProviderKind::Sora.nearest_size("sora-2", "1024x1792"),
+            Some("720x1280")
+        );
+
+        let rejected = sora_api_error(
+            StatusCode::BAD_REQUEST,
+            r#"{"error":{"message":"Invalid value: '1792x1024'","type":"invalid_request_error","param":"size","code":"invalid_value"}}"#.into(),
+            None,
+        );
+        assert!(rejected.is_size_rejection());
+        let other = sora_api_error(
+            StatusCode::BAD_REQUEST,
+            r#"{"error":{"message":"Invalid prompt","param":"prompt","code":"invalid_value"}}"#
+                .into(),
+            None,
+        );
+        assert!(!other.is_size_rejection());
+    }
+
+    #[test]
+    fn job_failures_name_the_job() {
+        let failed = SoraError::JobFailed {
+            backend: ProviderKind::Sora,
+            remote_id: Some("video_123".into()),
+            message: "moderation blocked".into(),
+            code: None,
+            param: None,
+            status: None,
+        };
+        assert_eq!(
+            failed.to_string(),
+            "Sora video generation job failed (video_123): moderation blocked"
+        );
+        let rejected = SoraError::JobFailed {
+            backend: ProviderKind::Veo,
+            remote_id: None,
+            message: "quota exceeded".into(),
+            code: None,
+            param: None,
+            status: None,
+        };
+        assert_eq!(
+            rejected.to_string(),
+            "Veo video generation job failed: quota exceeded"
+        );
+    }
+
+    #[test]
+    fn write_errors_name_the_file() {
+        let path = Path::new("videos/intro.mp4");
+        let full = SoraError::from(std::io::Error::from(std::io::ErrorKind::StorageFull));
+        assert_eq!(
+            full.writing(path).to_string(),
+            "disk full while writing videos/intro.mp4"
+        );
+        let denied = SoraError::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(matches!(
+            denied.writing(path),
+            SoraError::WriteDenied { .. }
+        ));
+        let other = SoraError::from(std::io::Error::from(std::io::ErrorKind::Interrupted));
+        assert!(matches!(other.writing(path), SoraError::Io(_)));
+    }
 
-        let json = "\"mysterious\"";
-        let status: VideoStatus = serde_json::from_str(json).unwrap();
-        assert!(matches!(status, VideoStatus::Unknown(_)));
+    #[test]
+    fn sora_variant_params_must_be_query_safe() {
+        let config = |value: &str| ContinuatorConfig {
+            api_key: Some("sk-test".into()),
+            sora_variant_params: Some([(VideoVariant::Thumbnail, value.to_string())].into()),
+            ..ContinuatorConfig::default()
+        };
+        assert!(VideoManager::new(config("thumb")).is_ok());
+        assert!(matches!(
+            VideoManager::new(config("thumb&x=1")),
+            Err(SoraError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn rate_limit_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(RateLimitInfo::from_headers(&headers), None);
+        headers.insert("x-ratelimit-limit-requests", "50".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "1m30.5s".parse().unwrap());
+        headers.insert("x-ratelimit-reset-tokens", "20ms".parse().unwrap());
+        assert_eq!(
+            RateLimitInfo::from_headers(&headers),
+            Some(RateLimitInfo {
+                limit_requests: Some(50),
+                remaining_requests: Some(0),
+                reset_requests: Some(Duration::from_millis(90_500)),
+                reset_tokens: Some(Duration::from_millis(20)),
+                ..RateLimitInfo::default()
+            })
+        );
+        assert_eq!(parse_reset_duration("2"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_reset_duration("5 days"), None);
+    }
+
+    #[test]
+    fn content_range_total_parsing() {
+        assert_eq!(parse_content_range_total("bytes 100-199/200"), Some(200));
+        assert_eq!(parse_content_range_total("bytes */4096"), Some(4096));
+        assert_eq!(parse_content_range_total("bytes 0-99/*"), None);
+    }
+
+    #[test]
+    fn encode_settings_validation() {
+        let settings = |crf, bitrate: Option<&str>, preset: Option<&str>| EncodeSettings {
+            crf,
+            bitrate: bitrate.map(str::to_string),
+            preset: preset.map(str::to_string),
+            ..EncodeSettings::default()
+        };
+        assert!(settings(Some(18), None, Some("slow")).validate().is_ok());
+        assert!(settings(None, Some("2500k"), None).validate().is_ok());
+        assert!(settings(Some(70), None, None).validate().is_err());
+        assert!(settings(Some(18), Some("8M"), None).validate().is_err());
+        assert!(settings(None, Some("fast"), None).validate().is_err());
+        assert!(settings(None, None, Some("warp")).validate().is_err());
+    }
+
+    #[test]
+    fn time_spec_parsing_and_resolution() {
+        let parse = |value: &str| value.parse::<TimeSpec>().unwrap();
+        assert_eq!(parse("12.5"), TimeSpec::Absolute(12.5));
+        assert_eq!(parse("-2"), TimeSpec::FromEnd(2.0));
+        assert_eq!(parse("50%"), TimeSpec::Fraction(0.5));
+        assert!("abc".parse::<TimeSpec>().is_err());
+        assert!("--1".parse::<TimeSpec>().is_err());
+
+        assert_eq!(parse("-2").resolve(8.0).unwrap(), 6.0);
+        assert_eq!(parse("50%").resolve(8.0).unwrap(), 4.0);
+        assert!(matches!(
+            parse("9").resolve(8.0),
+            Err(SoraError::TimeOutOfRange { .. })
+        ));
+        assert!(parse("-9").resolve(8.0).is_err());
+        assert!(parse("150%").resolve(8.0).is_err());
+    }
+
+    #[tokio::test]
+    async fn upload_failure_keeps_saved_clip() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            upload_command: Some("test -f {file} && echo \"$2\" >&2 && exit 3".into()),
+            ..ContinuatorConfig::default()
+        })
+        .await;
+
+        let err = manager
+            .create_video(CreateVideoRequest {
+                local_id: "it's".into(),
+                prompt: "A lighthouse at dusk".into(),
+                seconds: Some(4),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap_err();
+        match err {
+            SoraError::UploadFailed {
+                local_id, message, ..
+            } => {
+                assert_eq!(local_id, "it's");
+                assert!(message.contains("it's"), "{message}");
+            }
+            other => panic!("expected an upload failure, got {other}"),
+        }
+        assert!(manager.load_metadata("it's").await.is_ok());
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn flow_reports_partial_progress_and_resumes() {
+        let (manager, data_dir) = mock_manager().await;
+        let beat = |prompt: &str| FlowBeat {
+            prompt: prompt.into(),
+            seconds: Some(4),
+            ..FlowBeat::default()
+        };
+        // Occupy the second beat's id so the first run stops there.
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "f-02".into(),
+                prompt: "in the way".into(),
+                seconds: Some(4),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let request = FlowRequest {
+            id: "f".into(),
+            beats: vec![beat("one"), beat("two"), beat("three")],
+            ..FlowRequest::default()
+        };
+
+        let mut seen = 0;
+        let report = manager
+            .run_flow(request.clone(), |_| seen += 1)
+            .await
+            .unwrap();
+        assert_eq!(seen, 1);
+        assert_eq!(report.completed.len(), 1);
+        let failure = report.failure.as_ref().unwrap();
+        assert_eq!((failure.beat, failure.local_id.as_str()), (2, "f-02"));
+        assert!(!report.is_complete());
+
+        let resume = FlowRequest {
+            resume: true,
+            ..request
+        };
+        // The clip in the way isn't this flow's beat, so resuming won't adopt it.
+        let report = manager.run_flow(resume.clone(), |_| {}).await.unwrap();
+        let failure = report.failure.as_ref().unwrap();
+        assert_eq!(failure.beat, 2);
+        assert!(matches!(
+            &failure.error,
+            SoraError::InvalidConfig(message) if message.contains("it continues nothing")
+        ));
+
+        manager.store.delete("f-02").await.unwrap();
+        let report = manager.run_flow(resume, |_| {}).await.unwrap();
+        assert!(report.failure.is_none());
+        let ids: Vec<&str> = report
+            .completed
+            .iter()
+            .map(|clip| clip.local_id.as_str())
+            .collect();
+        assert_eq!(ids, ["f-01", "f-02", "f-03"]);
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn flow_graph_follows_dependencies() {
+        let (manager, data_dir) = mock_manager().await;
+        let beat = |prompt: &str, depends_on: Option<&str>| FlowBeat {
+            prompt: prompt.into(),
+            seconds: Some(4),
+            depends_on: depends_on.map(str::to_string),
+            ..FlowBeat::default()
+        };
+        let request = FlowRequest {
+            id: "g".into(),
+            beats: vec![
+                beat("trunk", None),
+                beat("left", Some("g-01")),
+                beat("right", Some("g-01")),
+                beat("left again", Some("g-02")),
+            ],
+            skip_stitch: true,
+            concurrency: Some(2),
+            ..FlowRequest::default()
+        };
+
+        let report = manager
+            .run_flow_graph(request.clone(), |_| {})
+            .await
+            .unwrap();
+        assert!(report.is_complete());
+        let parents: Vec<(&str, Option<&str>)> = report
+            .completed
+            .iter()
+            .map(|clip| (clip.local_id.as_str(), clip.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            parents,
+            [
+                ("g-01", None),
+                ("g-02", Some("g-01")),
+                ("g-03", Some("g-01")),
+                ("g-04", Some("g-02")),
+            ]
+        );
+
+        let mut cyclic = request;
+        cyclic.id = "h".into();
+        cyclic.beats[0].depends_on = Some("h-02".into());
+        cyclic.beats[1].depends_on = Some("h-01".into());
+        let error = manager.run_flow_graph(cyclic, |_| {}).await.unwrap_err();
+        assert!(matches!(error, SoraError::InvalidConfig(_)), "{error}");
+        assert!(!manager.store.exists("h-01").await.unwrap());
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gallery_saves_a_frame_per_clip() {
+        let (manager, data_dir) = mock_manager().await;
+        for local_id in ["first", "second"] {
+            manager
+                .create_video(CreateVideoRequest {
+                    local_id: local_id.into(),
+                    prompt: "a frame".into(),
+                    seconds: Some(4),
+                    ..CreateVideoRequest::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        let frames = manager
+            .export_first_frames(None, Path::new("gallery"))
+            .await
+            .unwrap();
+        let ids: Vec<&str> = frames.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["first", "second"]);
+        for (local_id, result) in &frames {
+            let path = result.as_ref().unwrap();
+            assert_eq!(
+                path,
+                &data_dir.join("gallery").join(format!("{local_id}.png"))
+            );
+            assert!(path.exists());
+        }
+
+        let missing = manager
+            .export_first_frames(Some(&["nope".to_string()]), Path::new("gallery"))
+            .await
+            .unwrap();
+        assert!(matches!(missing[0].1, Err(SoraError::MetadataNotFound(_))));
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn batches_skip_clips_that_already_rendered() {
+        let (manager, data_dir) = mock_manager().await;
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "done".into(),
+                prompt: "rendered last time".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let batch = data_dir.join("jobs.csv");
+        fs::write(
+            &batch,
+            "id,prompt,model,size,seconds\n\
+             done,\"rendered last time\",,,\n\
+             fresh,\"a new shot, with a comma\",,,4\n\
+             bad,too long,,,99\n",
+        )
+        .await
+        .unwrap();
+
+        let error = manager.run_batch(&batch, 2).await.unwrap_err().to_string();
+        assert!(error.contains("jobs.csv:4: 'bad'"), "{error}");
+        assert!(error.contains("99"), "{error}");
+
+        fs::write(
+            &batch,
+            "id,prompt,seconds\ndone,rendered last time,\nfresh,\"a new shot, with a comma\",4\n",
+        )
+        .await
+        .unwrap();
+        let report = manager.run_batch(&batch, 2).await.unwrap();
+        let statuses: Vec<(&str, BatchStatus)> = report
+            .results
+            .iter()
+            .map(|result| (result.id.as_str(), result.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("done", BatchStatus::Skipped),
+                ("fresh", BatchStatus::Rendered)
+            ]
+        );
+        assert_eq!(report.results_path, data_dir.join("jobs.results.csv"));
+        let written = fs::read_to_string(&report.results_path).await.unwrap();
+        assert_eq!(written, "id,status,error\ndone,skipped,\nfresh,rendered,\n");
+        assert_eq!(
+            manager.load_metadata("fresh").await.unwrap().prompt,
+            "a new shot, with a comma"
+        );
+
+        let jsonl = data_dir.join("jobs.jsonl");
+        fs::write(
+            &jsonl,
+            "{\"id\": \"twice\", \"prompt\": \"a\"}\n{\"id\": \"twice\", \"prompt\": \"b\"}\n",
+        )
+        .await
+        .unwrap();
+        let error = manager.run_batch(&jsonl, 1).await.unwrap_err();
+        assert!(error.to_string().contains("duplicate id"), "{error}");
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[test]
+    fn batch_rows_keep_their_line_numbers() {
+        let path = Path::new("jobs.csv");
+        let jobs = parse_batch(
+            path,
+            "id,prompt\nopen,\"a shot\nover two lines\"\nclose,the end\n",
+        )
+        .unwrap();
+        let lines: Vec<(usize, &str)> = jobs
+            .iter()
+            .map(|(line, job)| (*line, job.id.as_str()))
+            .collect();
+        assert_eq!(lines, [(2, "open"), (4, "close")]);
+
+        let error = parse_batch(path, "id,prompt\nopen,\"a\nb\"\n,no id\n").unwrap_err();
+        assert!(
+            error.to_string().contains("jobs.csv:4: missing id"),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    async fn render_plans_show_what_would_be_sent() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            prompt_prefix: Some("Noir.".into()),
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let request = CreateVideoRequest {
+            local_id: "opener".into(),
+            prompt: "a rainy alley".into(),
+            seconds: Some(8),
+            ..CreateVideoRequest::default()
+        };
+        let plan = manager.plan_create(&request).unwrap();
+        assert_eq!(plan.prompt, "Noir. a rainy alley");
+        assert_eq!((plan.seconds, plan.parent), (8, None));
+        assert!(!manager.store.exists("opener").await.unwrap());
+
+        manager.create_video(request).await.unwrap();
+        let plan = manager
+            .plan_continue(&ContinueVideoRequest {
+                parent_local_id: "opener".into(),
+                local_id: "next".into(),
+                prompt: "the camera follows".into(),
+                ..ContinueVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!((plan.seconds, plan.parent.as_deref()), (8, Some("opener")));
+
+        let priced = RenderPlan::new(
+            ProviderKind::Sora,
+            String::new(),
+            ("sora-2-pro".into(), "1792x1024".into(), 4),
+            None,
+        );
+        assert_eq!(priced.estimated_cost_usd, Some(2.0));
+        let unknown = RenderPlan::new(
+            ProviderKind::Sora,
+            String::new(),
+            ("sora-9".into(), "1280x720".into(), 4),
+            None,
+        );
+        assert_eq!(unknown.estimated_cost_usd, None);
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn frame_options_are_checked_before_extracting() {
+        let (manager, data_dir) = mock_manager().await;
+        let jpeg = |quality| {
+            Some(FrameOptions {
+                format: ImageFormat::Jpeg,
+                quality: Some(quality),
+            })
+        };
+        let error = manager
+            .extract_frame(
+                "missing",
+                TimeSpec::Absolute(1.0),
+                Path::new("p.jpg"),
+                jpeg(40),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SoraError::InvalidConfig(_)), "{error}");
+        let error = manager
+            .extract_frame(
+                "missing",
+                TimeSpec::Absolute(1.0),
+                Path::new("p.jpg"),
+                jpeg(4),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SoraError::MetadataNotFound(_)), "{error}");
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stitch_gaps_are_validated() {
+        let (manager, data_dir) = mock_manager().await;
+        let clips = ["a".to_string(), "b".to_string()];
+        for gap in [
+            GapSpec {
+                seconds: 0.0,
+                ..GapSpec::default()
+            },
+            GapSpec {
+                color: "black[v]".into(),
+                ..GapSpec::default()
+            },
+        ] {
+            let options = StitchOptions {
+                gap: Some(gap),
+                ..StitchOptions::default()
+            };
+            let error = manager
+                .stitch_videos_with("cut", &clips, &options)
+                .await
+                .unwrap_err();
+            assert!(matches!(error, SoraError::InvalidConfig(_)), "{error}");
+        }
+        GapSpec {
+            seconds: 0.5,
+            color: "#202020".into(),
+        }
+        .validate()
+        .unwrap();
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[test]
+    fn gap_filtergraph_fills_silent_clips() {
+        let clip = |audio: bool| MediaInfo {
+            duration_seconds: Some(4.0),
+            width: Some(1280),
+            height: Some(720),
+            frame_rate: Some("24/1".into()),
+            audio_codec: audio.then(|| "aac".into()),
+            sample_rate: audio.then_some(44_100),
+            channels: audio.then_some(2),
+            ..MediaInfo::default()
+        };
+        let gap = GapSpec {
+            seconds: 0.5,
+            color: "black".into(),
+        };
+        let (graph, with_audio) =
+            gap_filtergraph(&[clip(false), clip(true)], &gap, (None, None)).unwrap();
+        assert!(with_audio);
+        assert_eq!(
+            graph,
+            "[0:v]scale=1280:720,setsar=1,fps=24/1,format=yuv420p[v0];\
+             anullsrc=r=44100:cl=stereo,atrim=duration=4[a0];\
+             color=c=black:s=1280x720:r=24/1:d=0.5,setsar=1,format=yuv420p[gv1];\
+             anullsrc=r=44100:cl=stereo,atrim=duration=0.5[ga1];\
+             [1:v]scale=1280:720,setsar=1,fps=24/1,format=yuv420p[v1];\
+             [1:a]aresample=44100,aformat=sample_rates=44100:channel_layouts=stereo[a1];\
+             [v0][a0][gv1][ga1][v1][a1]concat=n=3:v=1:a=1[v][a]"
+        );
+
+        let (graph, with_audio) =
+            gap_filtergraph(&[clip(false), clip(false)], &gap, (None, None)).unwrap();
+        assert!(!with_audio);
+        assert!(graph.ends_with("[v0][gv1][v1]concat=n=3:v=1:a=0[v]"));
+        assert!(!graph.contains("anullsrc"));
+    }
+
+    #[test]
+    fn gap_stitches_refuse_what_they_cannot_honour() {
+        let shaping = |fade: Option<Duration>, carried: Option<PathBuf>| AudioShaping {
+            fade,
+            sample_rate: None,
+            channels: None,
+            carried: vec![None, carried],
+        };
+        assert!(check_gap_stitch(&shaping(None, None), None, 2).is_ok());
+        assert!(check_gap_stitch(&shaping(None, None), Some(4), 3).is_ok());
+        assert!(check_gap_stitch(&shaping(None, None), Some(2), 3).is_err());
+        assert!(
+            check_gap_stitch(&shaping(Some(Duration::from_millis(40)), None), None, 2).is_err()
+        );
+        assert!(check_gap_stitch(&shaping(None, Some("carried.wav".into())), None, 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_availability_tracks_the_provider() {
+        let (manager, data_dir) = mock_manager().await;
+        let clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: "kept".into(),
+                prompt: "a harbour at dawn".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert!(manager.is_remote_available("kept").await.unwrap());
+
+        let mut expired = clip.clone();
+        expired.local_id = "expired".into();
+        expired.remote_id = "mock-purged".into();
+        manager.store.save(&expired).await.unwrap();
+        assert!(!manager.is_remote_available("expired").await.unwrap());
+
+        let availability: Vec<(String, bool)> = manager
+            .remote_availability()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(local_id, result)| (local_id, result.unwrap()))
+            .collect();
+        assert_eq!(
+            availability,
+            [("expired".to_string(), false), ("kept".to_string(), true)]
+        );
+
+        fs::remove_dir_all(data_dir).await.unwrap();
+    }
+
+    #[test]
+    fn gcp_api_version_is_validated() {
+        let with = |version: Option<&str>| ContinuatorConfig {
+            gcp_api_version: version.map(str::to_string),
+            ..ContinuatorConfig::default()
+        };
+        assert_eq!(with(None).gcp_api_version().unwrap(), "v1");
+        assert_eq!(with(Some("v1beta1")).gcp_api_version().unwrap(), "v1beta1");
+        assert_eq!(with(Some("v2alpha")).gcp_api_version().unwrap(), "v2alpha");
+        for bad in ["", "1", "v", "vbeta1", "v1/../v2", "v1gamma"] {
+            assert!(
+                matches!(
+                    with(Some(bad)).gcp_api_version(),
+                    Err(SoraError::InvalidConfig(_))
+                ),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn last_frame_methods_build_ffmpeg_commands() {
+        let line = |method: LastFrameMethod| {
+            let command = frame_command(&FrameOptions::default(), Path::new("seed.png"), |c| {
+                method.apply(c, Path::new("clip.mp4"))
+            });
+            command_line(&command)
+        };
+        assert_eq!(
+            line(LastFrameMethod::SeekFromEnd(SHORT_SEEK_FROM_END)),
+            "ffmpeg -v error -sseof -0.05 -i clip.mp4 -update 1 -y seed.png"
+        );
+        assert_eq!(
+            line(LastFrameMethod::Reverse),
+            "ffmpeg -v error -i clip.mp4 -vf reverse -frames:v 1 -y seed.png"
+        );
+        assert_eq!(
+            line(LastFrameMethod::Select(191)),
+            r"ffmpeg -v error -i clip.mp4 -vf 'select=eq(n\,191)' -frames:v 1 -y seed.png"
+        );
+    }
+
+    #[tokio::test]
+    async fn extra_metadata_is_recorded_and_editable() {
+        let (manager, data_dir) = mock_manager().await;
+        let mut extra = serde_json::Map::new();
+        extra.insert("shot".into(), "12".into());
+        extra.insert("ticket".into(), "VFX-7".into());
+        let clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: "shot12".into(),
+                prompt: "a sweeping dune".into(),
+                extra,
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(clip.extra["shot"], "12");
+
+        let mut set_extra = serde_json::Map::new();
+        set_extra.insert("shot".into(), "12a".into());
+        let edited = manager
+            .update_metadata(
+                "shot12",
+                MetadataPatch {
+                    set_extra,
+                    remove_extra: vec!["ticket".into()],
+                    ..MetadataPatch::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::Value::Object(edited.extra),
+            serde_json::json!({ "shot": "12a" })
+        );
+
+        let mut padded = serde_json::Map::new();
+        padded.insert(" shot".into(), "1".into());
+        let err = manager
+            .update_metadata(
+                "shot12",
+                MetadataPatch {
+                    set_extra: padded,
+                    ..MetadataPatch::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::InvalidConfig(_)));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[test]
+    fn upload_placeholders_become_positional_arguments() {
+        assert_eq!(
+            upload_script("gsutil cp {file} gs://b/{id}.mp4 {x} {"),
+            r#"gsutil cp "$1" gs://b/"$2".mp4 {x} {"#
+        );
+    }
+
+    #[tokio::test]
+    async fn upload_never_runs_substituted_text() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            upload_command: Some("printf '%s' {id} > \"$1.uploaded\"".into()),
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let id = "{id}$(touch pwned)";
+        let clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: id.into(),
+                prompt: "A lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let mut marker = clip.file_path.clone().into_os_string();
+        marker.push(".uploaded");
+        assert_eq!(fs::read_to_string(&marker).await.unwrap(), id);
+        assert!(!Path::new("pwned").exists());
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn import_rejects_ids_that_escape_the_data_dir() {
+        let (manager, data_dir) = mock_manager().await;
+        let bundle = data_dir.join("crafted.tar");
+        let manifest = serde_json::to_vec(&BundleManifest {
+            version: BUNDLE_VERSION,
+            clips: vec!["../escaped".into()],
+        })
+        .unwrap();
+        let mut builder = tar::Builder::new(std::fs::File::create(&bundle).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, BUNDLE_MANIFEST, manifest.as_slice())
+            .unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        let err = manager.import_bundle(&bundle).await.unwrap_err();
+        assert!(matches!(err, SoraError::InvalidBundle(_)), "{err}");
+        assert!(manager.list_videos().await.unwrap().is_empty());
+        let err = manager
+            .create_video(CreateVideoRequest {
+                local_id: "../escaped".into(),
+                prompt: "A lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::InvalidConfig(_)));
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "source".into(),
+                prompt: "A lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let err = manager
+            .remix_video("source", "../../escaped", "At night")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::InvalidConfig(_)));
+        assert!(!data_dir.join("../../escaped.json").exists());
+        let err = manager
+            .adopt_remote("../escaped", "video_123")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::InvalidConfig(_)));
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[test]
+    fn submissions_only_retry_refused_requests() {
+        assert!(should_retry_status(StatusCode::TOO_MANY_REQUESTS, false));
+        assert!(!should_retry_status(StatusCode::BAD_GATEWAY, false));
+        assert!(should_retry_status(StatusCode::BAD_GATEWAY, true));
+        assert!(!should_retry_status(StatusCode::BAD_REQUEST, true));
+    }
+
+    #[tokio::test]
+    async fn append_refuses_ordinary_clips_as_master() {
+        let (manager, data_dir) = mock_manager().await;
+        for id in ["take", "extra"] {
+            manager
+                .create_video(CreateVideoRequest {
+                    local_id: id.into(),
+                    prompt: "A lighthouse at dusk".into(),
+                    ..CreateVideoRequest::default()
+                })
+                .await
+                .unwrap();
+        }
+        let err = manager
+            .append_to_stitch("take", "extra", false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::InvalidConfig(_)), "{err}");
+        assert!(
+            manager
+                .load_metadata("take")
+                .await
+                .unwrap()
+                .sources
+                .is_empty()
+        );
+
+        let master = manager
+            .append_to_stitch("reel", "take", false)
+            .await
+            .unwrap();
+        assert_eq!(master.sources, ["take"]);
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn flow_stitch_keeps_unrelated_clips() {
+        let (manager, data_dir) = mock_manager().await;
+        let unrelated = manager
+            .create_video(CreateVideoRequest {
+                local_id: "reel".into(),
+                prompt: "someone else's take".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let report = manager
+            .run_flow(
+                FlowRequest {
+                    id: "reel".into(),
+                    beats: vec![FlowBeat {
+                        prompt: "opening".into(),
+                        ..FlowBeat::default()
+                    }],
+                    ..FlowRequest::default()
+                },
+                |_| {},
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            report.stitch_error,
+            Some(SoraError::InvalidConfig(_))
+        ));
+        let kept = manager.load_metadata("reel").await.unwrap();
+        assert_eq!(kept.remote_id, unrelated.remote_id);
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn printing_ffmpeg_saves_and_renders_nothing() {
+        let (manager, data_dir) = mock_manager().await;
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "source".into(),
+                prompt: "A lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let (printing, _) = mock_manager_with(ContinuatorConfig {
+            data_dir: Some(data_dir.clone()),
+            print_ffmpeg: true,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+
+        let err = printing
+            .annotate("source", "labelled", AnnotationSpec::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::CommandsPrinted), "{err}");
+        let err = printing
+            .create_video(CreateVideoRequest {
+                local_id: "fresh".into(),
+                prompt: "A lighthouse at dawn".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SoraError::CommandsPrinted), "{err}");
+        let ids: Vec<String> = manager
+            .list_videos()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|clip| clip.local_id)
+            .collect();
+        assert_eq!(ids, ["source"]);
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn resaving_keeps_the_recorded_checksum() {
+        let (manager, data_dir) = mock_manager_with(ContinuatorConfig {
+            checksums: true,
+            ..ContinuatorConfig::default()
+        })
+        .await;
+        let mut clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: "archived".into(),
+                prompt: "A lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let recorded = clip.sha256.clone();
+        fs::write(&clip.file_path, b"bit rot").await.unwrap();
+        manager.save_metadata(&mut clip).await.unwrap();
+        assert_eq!(clip.sha256, recorded);
+
+        let report = manager.verify_checksums().await.unwrap();
+        assert_eq!(report.mismatches.len(), 1);
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn scratch_directories_are_removed_with_their_contents() {
+        let (manager, data_dir) = mock_manager().await;
+        let scratch = manager.scratch_file("split-run").await.unwrap();
+        fs::create_dir_all(scratch.path()).await.unwrap();
+        fs::write(scratch.path().join("000.mp4"), b"chunk")
+            .await
+            .unwrap();
+        let path = scratch.path().to_path_buf();
+        drop(scratch);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn cancel_all_reaches_remote_jobs_and_tolerates_finishing_races() {
+        let list = serde_json::json!({
+            "data": [
+                sora_job("video_race", "in_progress"),
+                sora_job("video_live", "queued"),
+                sora_job("video_done", "completed"),
+            ],
+            "has_more": false,
+        });
+        let (manager, data_dir) = sora_cassette_manager(vec![
+            cassette_exchange("GET", "/videos?limit=100", 200, list),
+            // Finishes between the listing and the cancel.
+            cassette_exchange("DELETE", "/videos/video_race", 409, serde_json::json!({})),
+            cassette_exchange(
+                "GET",
+                "/videos/video_race",
+                200,
+                sora_job("video_race", "completed"),
+            ),
+            cassette_exchange(
+                "DELETE",
+                "/videos/video_live",
+                200,
+                sora_job("video_live", "queued"),
+            ),
+        ])
+        .await;
+        let results = manager.cancel_all().await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "video_live");
+        assert_eq!(*results[0].1.as_ref().unwrap(), CancelOutcome::Canceled);
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn refused_cancels_only_call_missing_jobs_deleted() {
+        let refused = serde_json::json!({});
+        let (manager, data_dir) = sora_cassette_manager(vec![
+            cassette_exchange("DELETE", "/videos/video_gone", 404, refused.clone()),
+            cassette_exchange("GET", "/videos/video_gone", 404, refused.clone()),
+            cassette_exchange("DELETE", "/videos/video_flaky", 409, refused.clone()),
+            cassette_exchange("GET", "/videos/video_flaky", 401, refused),
+        ])
+        .await;
+        assert_eq!(
+            manager.backend.cancel("video_gone").await.unwrap(),
+            CancelOutcome::AlreadyFinished(VideoStatus::Unknown("deleted".into()))
+        );
+        assert!(manager.backend.cancel("video_flaky").await.is_err());
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn abandoned_renders_are_canceled_and_cleaned_up() {
+        let exchange = |method: &str, path: &str, status: &str| {
+            cassette_exchange(method, path, 200, sora_job("video_slow", status))
+        };
+        let (manager, data_dir) = sora_cassette_manager_with(
+            vec![
+                exchange("POST", "/videos", "queued"),
+                // Replayed for every poll, so the render never finishes.
+                exchange("GET", "/videos/video_slow", "in_progress"),
+                exchange("DELETE", "/videos/video_slow", "in_progress"),
+            ],
+            ContinuatorConfig {
+                poll_interval_ms: Some(5),
+                ..ContinuatorConfig::default()
+            },
+        )
+        .await;
+
+        let render = manager.create_video(CreateVideoRequest {
+            local_id: "slow".into(),
+            prompt: "a lighthouse at dusk".into(),
+            ..CreateVideoRequest::default()
+        });
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), render)
+                .await
+                .is_err()
+        );
+        let in_flight = manager.backend.in_flight();
+        assert_eq!(in_flight.len(), 1);
+        let (remote_id, partial) = &in_flight[0];
+        assert_eq!(remote_id, "video_slow");
+        fs::write(partial, b"partial").await.unwrap();
+
+        let results = manager.cancel_in_flight().await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(*results[0].1.as_ref().unwrap(), CancelOutcome::Canceled);
+        assert!(!partial.exists());
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn streamed_downloads_must_match_the_announced_length() {
+        let response = |announced: &str| {
+            reqwest::Response::from(
+                http::Response::builder()
+                    .header("content-length", announced)
+                    .body(b"mp4 bytes".to_vec())
+                    .unwrap(),
+            )
+        };
+        let short = response("100");
+        assert_eq!(announced_length(&short), Some(100));
+        let mut out = Vec::new();
+        let written = write_response(short, &mut out).await.unwrap();
+        assert_eq!((written, out.as_slice()), (9, b"mp4 bytes".as_slice()));
+        assert!(matches!(
+            check_body_length(Some(100), written),
+            Err(SoraError::InvalidResponse(message)) if message.contains("9 of 100")
+        ));
+
+        let whole = response("9");
+        let written = write_response(whole, &mut Vec::new()).await.unwrap();
+        assert!(check_body_length(Some(9), written).is_ok());
+        assert!(check_body_length(None, written).is_ok());
+    }
+
+    #[tokio::test]
+    async fn veo_skips_the_remote_copy_check_up_front() {
+        let data_dir = std::env::temp_dir().join(format!("continuator-test-{}", quick_local_id()));
+        let manager = VideoManager::new(ContinuatorConfig {
+            provider: Some(ProviderKind::Veo),
+            gcp_project: Some("project".into()),
+            gcp_location: Some("us-central1".into()),
+            gcp_access_token: Some("token".into()),
+            data_dir: Some(data_dir.clone()),
+            ..ContinuatorConfig::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            manager.remote_availability().await,
+            Err(SoraError::UnsupportedOperation(_))
+        ));
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn polls_wait_between_answers_until_decided() {
+        let sleeper = FakeSleeper {
+            start: Instant::now(),
+            slept: Mutex::default(),
+        };
+        let mut answers = vec![Ok(3), Ok(2), Ok(1)];
+        let mut seen = Vec::new();
+        let done = poll_until(
+            ProviderKind::Mock,
+            &sleeper,
+            Duration::from_secs(5),
+            || std::future::ready(answers.pop().unwrap()),
+            |answer: u32| {
+                seen.push(answer);
+                Ok((answer == 3).then_some("finished"))
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((done, seen), ("finished", vec![1, 2, 3]));
+        assert_eq!(*sleeper.slept.lock().unwrap(), Duration::from_secs(10));
+
+        let failed: Result<(), _> = poll_until(
+            ProviderKind::Mock,
+            &sleeper,
+            Duration::from_secs(5),
+            || std::future::ready(Ok(())),
+            |()| Err(SoraError::InvalidResponse("gone".into())),
+        )
+        .await;
+        assert!(failed.is_err());
+        assert_eq!(*sleeper.slept.lock().unwrap(), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn json_store_inserts_claim_the_id() {
+        let (manager, data_dir) = mock_manager().await;
+        let clip = manager
+            .create_video(CreateVideoRequest {
+                local_id: "claimed".into(),
+                prompt: "a lighthouse at dusk".into(),
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+        let store = JsonFileStore::new(&data_dir);
+        let mut other = clip.clone();
+        other.prompt = "a different lighthouse".into();
+        assert!(matches!(
+            store.insert(&other).await,
+            Err(SoraError::InvalidConfig(_))
+        ));
+        assert_eq!(store.load("claimed").await.unwrap().prompt, clip.prompt);
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[tokio::test]
+    async fn removing_and_canceling_free_the_id() {
+        let (manager, data_dir) = mock_manager().await;
+        let request = |local_id: &str, no_wait| CreateVideoRequest {
+            local_id: local_id.into(),
+            prompt: "a lighthouse at dusk".into(),
+            no_wait,
+            ..CreateVideoRequest::default()
+        };
+        let clip = manager.create_video(request("gone", false)).await.unwrap();
+        assert!(clip.file_path.exists());
+        manager.remove_clip("gone").await.unwrap();
+        assert!(!clip.file_path.exists());
+        assert!(!manager.store.exists("gone").await.unwrap());
+        manager.create_video(request("gone", false)).await.unwrap();
+
+        let _ = fs::remove_dir_all(&data_dir).await;
+
+        // The mock finishes renders at once, so the pending clip comes from a queued Sora job.
+        let exchange = |method: &str, path: &str| {
+            cassette_exchange(method, path, 200, sora_job("video_queued", "queued"))
+        };
+        let (manager, data_dir) = sora_cassette_manager(vec![
+            exchange("POST", "/videos"),
+            exchange("GET", "/videos/video_queued"),
+            exchange("DELETE", "/videos/video_queued"),
+        ])
+        .await;
+        manager.create_video(request("queued", true)).await.unwrap();
+        assert!(
+            manager.remove_clip("queued").await.is_err(),
+            "still rendering"
+        );
+        assert_eq!(
+            manager.cancel("queued").await.unwrap(),
+            CancelOutcome::Canceled
+        );
+        assert!(!manager.store.exists("queued").await.unwrap());
+        manager.create_video(request("queued", true)).await.unwrap();
+        let _ = fs::remove_dir_all(&data_dir).await;
+    }
+
+    #[test]
+    fn chunked_stitches_encode_audio_once() {
+        let encode = EncodeSettings {
+            vcodec: Some("libx264".into()),
+            crf: Some(20),
+            acodec: Some("libopus".into()),
+            abitrate: Some("160k".into()),
+            ..EncodeSettings::default()
+        };
+        let chunk = chunk_pass_encode(&encode);
+        assert!(chunk.touches_video());
+        assert_eq!((chunk.acodec, chunk.abitrate), (None, None));
+        let join = join_pass_encode(&encode, true);
+        assert!(!join.touches_video());
+        assert_eq!(join.acodec.as_deref(), Some("libopus"));
+        assert_eq!(join.abitrate.as_deref(), Some("160k"));
+
+        // Copied chunk audio stays copied; FLAC chunk audio becomes AAC.
+        let plain = EncodeSettings::default();
+        assert_eq!(join_pass_encode(&plain, false).acodec, None);
+        assert_eq!(
+            join_pass_encode(&plain, true).acodec.as_deref(),
+            Some("aac")
+        );
+    }
+
+    #[tokio::test]
+    async fn status_of_everything_includes_jobs_from_elsewhere() {
+        let list = serde_json::json!({
+            "data": [
+                sora_job("video_local", "queued"),
+                sora_job("video_elsewhere", "in_progress"),
+                sora_job("video_done", "completed"),
+            ],
+            "has_more": false,
+        });
+        let (manager, data_dir) = sora_cassette_manager(vec![
+            cassette_exchange("POST", "/videos", 200, sora_job("video_local", "queued")),
+            cassette_exchange(
+                "GET",
+                "/videos/video_local",
+                200,
+                sora_job("video_local", "queued"),
+            ),
+            cassette_exchange("GET", "/videos?limit=100", 200, list),
+            cassette_exchange(
+                "GET",
+                "/videos/video_elsewhere",
+                200,
+                sora_job("video_elsewhere", "in_progress"),
+            ),
+        ])
+        .await;
+        manager
+            .create_video(CreateVideoRequest {
+                local_id: "local".into(),
+                prompt: "a lighthouse at dusk".into(),
+                no_wait: true,
+                ..CreateVideoRequest::default()
+            })
+            .await
+            .unwrap();
+
+        let statuses = manager.remote_status_many(None).await.unwrap();
+        let ids: Vec<&str> = statuses.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["local", "video_elsewhere"]);
+        let elsewhere = statuses[1].1.as_ref().unwrap();
+        assert_eq!(elsewhere.local_id, "");
+        assert_eq!(elsewhere.status, VideoStatus::InProgress);
+        let _ = fs::remove_dir_all(&data_dir).await;
     }
 }
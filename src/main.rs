@@ -1,11 +1,18 @@
-use std::path::PathBuf;
+use std::{
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use continuator::{
-    ContinueVideoRequest, CreateVideoRequest, ProviderKind, SoraConfig, VideoManager, VideoVariant,
+    AnnotationPosition, AnnotationSpec, BoomerangOptions, CancelOutcome, ContinueVideoRequest,
+    CreateVideoRequest, DEFAULT_BATCH_CONCURRENCY, DownloadOptions, EncodeSettings, FlowBeat,
+    FlowRequest, FrameExtractionStrategy, FrameOptions, GapSpec, ImageFormat, MetadataPatch,
+    ProviderKind, RenderPlan, SoraConfig, StitchOptions, TimeSpec, VideoManager, VideoVariant,
 };
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 
 #[derive(Parser, Debug)]
@@ -15,6 +22,11 @@ struct Cli {
     #[arg(long, global = true, value_enum)]
     provider: Option<ProviderKind>,
 
+    /// Log line format on stderr: compact or json (defaults to $CONTINUATOR_LOG_FORMAT, then
+    /// compact).
+    #[arg(long, global = true, value_enum)]
+    log_format: Option<LogFormat>,
+
     /// Override the OpenAI API key. Defaults to the OPENAI_API_KEY environment variable.
     #[arg(long, global = true)]
     api_key: Option<String>,
@@ -23,7 +35,7 @@ struct Cli {
     #[arg(long, global = true)]
     model: Option<String>,
 
-    /// Default output size (e.g., 1280x720).
+    /// Default output size (e.g., 1280x720); defaults to the model's native size.
     #[arg(long, global = true)]
     size: Option<String>,
 
@@ -55,18 +67,178 @@ struct Cli {
     #[arg(long, global = true)]
     gcp_storage_uri: Option<String>,
 
-    /// Whether Veo should generate audio (defaults to true).
+    /// Vertex AI API version for Veo requests (defaults to v1; e.g. v1beta1).
     #[arg(long, global = true)]
+    gcp_api_version: Option<String>,
+
+    /// Warn when Veo returns an inline video whose base64 payload exceeds this many bytes
+    /// (defaults to 64 MiB).
+    #[arg(long, global = true, value_name = "BYTES")]
+    gcp_inline_warn_bytes: Option<u64>,
+
+    /// Whether Veo should generate audio (defaults to true); `--audio`/`--no-audio` are
+    /// shorter.
+    #[arg(long, global = true, conflicts_with_all = ["audio", "no_audio"])]
     gcp_generate_audio: Option<bool>,
 
-    /// Preferred Veo resolution (720p or 1080p).
+    /// Ask Veo to generate audio (the default).
+    #[arg(long, global = true, conflicts_with = "no_audio")]
+    audio: bool,
+
+    /// Render Veo clips without audio.
+    #[arg(long, global = true)]
+    no_audio: bool,
+
+    /// Preferred Veo resolution (720p or 1080p), independent of --size.
     #[arg(long, global = true)]
     gcp_resolution: Option<String>,
 
-    /// Whether Veo should let Gemini enhance prompts (defaults to true).
+    /// Veo aspect ratio (16:9 or 9:16), independent of --size.
     #[arg(long, global = true)]
+    gcp_aspect_ratio: Option<String>,
+
+    /// Whether Veo should let Gemini enhance prompts (defaults to true);
+    /// `--enhance-prompt`/`--no-enhance-prompt` are shorter.
+    #[arg(long, global = true, conflicts_with_all = ["enhance_prompt", "no_enhance_prompt"])]
     gcp_enhance_prompt: Option<bool>,
 
+    /// Let Gemini enhance prompts sent to Veo (the default).
+    #[arg(long, global = true, conflicts_with = "no_enhance_prompt")]
+    enhance_prompt: bool,
+
+    /// Send prompts to Veo exactly as written.
+    #[arg(long, global = true)]
+    no_enhance_prompt: bool,
+
+    /// Maximum provider requests per minute (submissions and polls share the budget).
+    #[arg(long, global = true)]
+    rate_limit_rpm: Option<u32>,
+
+    /// Multipart field name for Sora reference images (defaults to input_reference).
+    #[arg(long, global = true)]
+    sora_reference_field: Option<String>,
+
+    /// Query value Sora expects for a download variant, as `VARIANT=VALUE` (e.g.
+    /// `thumbnail=thumb`); repeat for each variant to override.
+    #[arg(long, global = true, value_name = "VARIANT=VALUE", value_parser = parse_variant_param)]
+    sora_variant_param: Vec<(VideoVariant, String)>,
+
+    /// Retries for rate-limited or failing provider requests (defaults to 3).
+    #[arg(long, global = true)]
+    max_retries: Option<u32>,
+
+    /// Idle HTTP connections kept per provider host (at least --concurrency for batches).
+    #[arg(long, global = true)]
+    pool_max_idle_per_host: Option<usize>,
+
+    /// Seconds an idle HTTP connection stays pooled (defaults to 90).
+    #[arg(long, global = true)]
+    pool_idle_timeout_secs: Option<u64>,
+
+    /// TCP keep-alive interval in seconds for provider connections.
+    #[arg(long, global = true)]
+    tcp_keepalive_secs: Option<u64>,
+
+    /// How seed frames are extracted from parent clips (fallback, reverse, or seek-from-end).
+    #[arg(long, global = true, value_enum)]
+    frame_extraction: Option<FrameExtractionStrategy>,
+
+    /// Allowed difference in seconds between requested and rendered clip length.
+    #[arg(long, global = true)]
+    duration_tolerance_secs: Option<f64>,
+
+    /// Fail when a rendered clip's length falls outside the tolerance.
+    #[arg(long, global = true)]
+    strict_duration: bool,
+
+    /// Image format for continuation seed frames (png or jpeg).
+    #[arg(long, global = true, value_enum)]
+    seed_frame_format: Option<ImageFormat>,
+
+    /// JPEG quality for seed frames (2 = best, 31 = smallest).
+    #[arg(long, global = true)]
+    seed_frame_quality: Option<u8>,
+
+    /// Resize downloaded thumbnails to WIDTHxHEIGHT (e.g., 320x180).
+    #[arg(long, global = true)]
+    thumbnail_size: Option<String>,
+
+    /// Fade audio out and in over this many milliseconds at each stitch boundary.
+    #[arg(long, global = true)]
+    audio_crossfade_ms: Option<u32>,
+
+    /// Shell command run after each clip is saved, with the clip path and id as `$1` and `$2`
+    /// (`{file}` and `{id}` are shorthand for them).
+    #[arg(long, global = true)]
+    upload_command: Option<String>,
+
+    /// Accept any --seconds value instead of checking it against the model's known durations.
+    #[arg(long, global = true)]
+    skip_duration_validation: bool,
+
+    /// Keep each continuation's seed frame as `<id>.seed.<ext>` in the data dir.
+    #[arg(long, global = true)]
+    keep_seed_frame: bool,
+
+    /// Keep each stitch's concat manifest in the temp dir and log its path.
+    #[arg(long, global = true)]
+    keep_manifest: bool,
+
+    /// Put the model in new clip filenames (`<id>.<model>.mp4`).
+    #[arg(long, global = true)]
+    model_in_filename: bool,
+
+    /// Put the provider in new clip filenames (`<id>.<provider>.mp4`).
+    #[arg(long, global = true)]
+    provider_in_filename: bool,
+
+    /// Print ffmpeg command lines to stderr instead of running them; nothing is saved or
+    /// rendered (ffprobe still runs).
+    #[arg(long, global = true)]
+    print_ffmpeg: bool,
+
+    /// Text prepended to every create and continue prompt (e.g. a house style).
+    #[arg(long, global = true)]
+    prompt_prefix: Option<String>,
+
+    /// Text appended to every create and continue prompt.
+    #[arg(long, global = true)]
+    prompt_suffix: Option<String>,
+
+    /// Retry once at the model's nearest supported size when Sora rejects the requested one.
+    #[arg(long, global = true)]
+    size_fallback: bool,
+
+    /// Fail instead of warning when continuing or stitching clips from another provider.
+    #[arg(long, global = true)]
+    strict_provider: bool,
+
+    /// Resubmit a render that fails on the provider up to this many times before giving up.
+    #[arg(long, global = true, value_name = "N")]
+    render_retries: Option<u32>,
+
+    /// Give up on the whole command after this many seconds, canceling renders still in
+    /// progress and deleting their partial downloads.
+    #[arg(long, global = true, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
+    /// Record a SHA-256 checksum of every clip written (checked by `verify`).
+    #[arg(long, global = true)]
+    checksums: bool,
+
+    /// Clips probed or hashed at once by list, verify, and summaries, and chunks joined at
+    /// once by a chunked stitch (defaults to 8).
+    #[arg(long, global = true)]
+    probe_concurrency: Option<usize>,
+
+    /// Stitch more clips than this in chunks of this size, then join the chunks.
+    #[arg(long, global = true)]
+    stitch_chunk_size: Option<usize>,
+
+    /// Directory for intermediate files such as seed frames (defaults to <data-dir>/.tmp).
+    #[arg(long, global = true)]
+    temp_dir: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -76,10 +248,10 @@ enum Command {
     /// Create a brand-new clip.
     Create {
         /// Local identifier used for filenames (e.g., intro-001).
-        #[arg(long)]
-        id: String,
-        /// Prompt describing the clip.
-        #[arg(long)]
+        #[arg(long, required_unless_present = "scratch")]
+        id: Option<String>,
+        /// Prompt describing the clip (`-` reads it from stdin).
+        #[arg(long, allow_hyphen_values = true)]
         prompt: String,
         /// Override the model for this clip.
         #[arg(long)]
@@ -90,6 +262,38 @@ enum Command {
         /// Override the duration in seconds.
         #[arg(long)]
         seconds: Option<u32>,
+        /// Poll interval in milliseconds for this job's renders only, overriding
+        /// `--poll-interval-ms`.
+        #[arg(long, value_name = "MS")]
+        job_poll_interval_ms: Option<u64>,
+        /// Stream the finished MP4 to stdout when set to `-` (metadata goes to stderr).
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Throwaway render: save under the temp dir without metadata and print its path.
+        #[arg(long)]
+        scratch: bool,
+        /// Also copy the finished clip to this path (parents are created); the tracked copy
+        /// stays in the data dir.
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["scratch", "no_wait"])]
+        also_write: Option<PathBuf>,
+        /// Free-form note to keep with the clip (not sent to the provider).
+        #[arg(long)]
+        notes: Option<String>,
+        /// Key/value pair to record for downstream tools, e.g. `shot=12` (repeatable; not sent
+        /// to the provider).
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_meta)]
+        meta: Vec<(String, String)>,
+        /// Send the prompt as given, without the configured prefix and suffix.
+        #[arg(long)]
+        no_prompt_wrap: bool,
+        /// Wait for the render and print the finished clip (the default).
+        #[arg(long, overrides_with = "no_wait")]
+        wait: bool,
+        /// Submit, print the pending record, and exit without polling; collect it with `resume`.
+        #[arg(long, overrides_with = "wait", conflicts_with_all = ["output", "scratch"])]
+        no_wait: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
     /// Generate a sequence of clips from multiple prompts and stitch them.
     Flow {
@@ -100,15 +304,50 @@ enum Command {
         #[arg(long)]
         start_from: Option<String>,
         /// Override the model for generated clips.
-        #[arg(long)]
+        #[arg(long, conflicts_with = "models")]
         model: Option<String>,
+        /// Comma-separated model per beat; a shorter list repeats its last entry.
+        #[arg(long, value_delimiter = ',')]
+        models: Vec<String>,
         /// Override the size for generated clips.
-        #[arg(long)]
+        #[arg(long, conflicts_with = "sizes")]
         size: Option<String>,
+        /// Comma-separated size per beat; a shorter list repeats its last entry.
+        #[arg(long, value_delimiter = ',')]
+        sizes: Vec<String>,
         /// Override the duration in seconds for generated clips.
-        #[arg(long)]
+        #[arg(long, conflicts_with = "seconds_list")]
         seconds: Option<u32>,
-        /// One or more prompts describing each beat of the flow.
+        /// Comma-separated duration per beat; a shorter list repeats its last entry.
+        #[arg(long, value_delimiter = ',')]
+        seconds_list: Vec<u32>,
+        /// Poll interval in milliseconds for this job's renders only, overriding
+        /// `--poll-interval-ms`.
+        #[arg(long, value_name = "MS")]
+        job_poll_interval_ms: Option<u64>,
+        /// Reuse beats saved by an earlier run of this flow instead of rendering them again.
+        #[arg(long)]
+        resume: bool,
+        /// Template for beat ids, using `{base}`, `{index}`, or zero-padded `{index:03}`
+        /// (defaults to `{base}-{index:02}`).
+        #[arg(long)]
+        beat_name: Option<String>,
+        /// Generate and save the beats without stitching them together.
+        #[arg(long)]
+        no_stitch: bool,
+        /// Comma-separated clip each beat continues (a beat id or saved clip, `-` for the
+        /// starting clip); independent beats then render concurrently.
+        #[arg(long, value_delimiter = ',')]
+        depends_on: Vec<String>,
+        /// Beats to render at once with `--depends-on` (defaults to 4).
+        #[arg(long, requires = "depends_on")]
+        concurrency: Option<usize>,
+        /// Only validate every beat and the starting clip, reporting all problems, without
+        /// rendering anything.
+        #[arg(long)]
+        check: bool,
+        /// One or more prompts describing each beat of the flow, or `-` to read one per line
+        /// from stdin.
         #[arg(required = true)]
         prompts: Vec<String>,
     },
@@ -120,8 +359,8 @@ enum Command {
         /// Local identifier to assign to the new clip.
         #[arg(long)]
         id: String,
-        /// Prompt defining the next beat of the scene.
-        #[arg(long)]
+        /// Prompt defining the next beat of the scene (`-` reads it from stdin).
+        #[arg(long, allow_hyphen_values = true)]
         prompt: String,
         /// Override the model for this clip.
         #[arg(long)]
@@ -132,9 +371,212 @@ enum Command {
         /// Override the duration in seconds.
         #[arg(long)]
         seconds: Option<u32>,
+        /// Poll interval in milliseconds for this job's renders only, overriding
+        /// `--poll-interval-ms`.
+        #[arg(long, value_name = "MS")]
+        job_poll_interval_ms: Option<u64>,
+        /// Seed from the last frame of this clip instead of the parent's (for match cuts).
+        #[arg(long)]
+        seed_from: Option<String>,
+        /// Seed from the frame at this point instead of the last one: seconds (`7.6`), seconds
+        /// from the end (`-0.5`), or a percentage (`95%`). See `contact-sheet`.
+        #[arg(long, allow_hyphen_values = true)]
+        seed_at: Option<TimeSpec>,
+        /// Record the parent's last second of audio and crossfade it into this clip when the
+        /// two are stitched.
+        #[arg(long)]
+        carry_audio: bool,
+        /// Stream the finished MP4 to stdout when set to `-` (metadata goes to stderr).
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Also copy the finished clip to this path (parents are created); the tracked copy
+        /// stays in the data dir.
+        #[arg(long, value_name = "PATH", conflicts_with = "no_wait")]
+        also_write: Option<PathBuf>,
+        /// Free-form note to keep with the clip (not sent to the provider).
+        #[arg(long)]
+        notes: Option<String>,
+        /// Key/value pair to record for downstream tools, e.g. `shot=12` (repeatable; not sent
+        /// to the provider).
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_meta)]
+        meta: Vec<(String, String)>,
+        /// Send the prompt as given, without the configured prefix and suffix.
+        #[arg(long)]
+        no_prompt_wrap: bool,
+        /// Wait for the render and print the finished clip (the default).
+        #[arg(long, overrides_with = "no_wait")]
+        wait: bool,
+        /// Submit, print the pending record, and exit without polling; collect it with `resume`.
+        #[arg(long, overrides_with = "wait", conflicts_with = "output")]
+        no_wait: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
+    },
+    /// Tile labelled frames from the end of a clip to pick a `continue --seed-at` point.
+    ContactSheet {
+        /// Local identifier of the clip.
+        #[arg(long)]
+        id: String,
+        /// How many seconds from the end of the clip to cover.
+        #[arg(long, default_value_t = 1.0)]
+        seconds: f32,
+        /// Image to write; relative paths resolve under the data dir.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Remix an existing Sora clip with a new prompt.
+    Remix {
+        /// Local identifier of the clip to remix.
+        #[arg(long = "from")]
+        source_id: String,
+        /// Local identifier to assign to the remix.
+        #[arg(long)]
+        id: String,
+        /// Prompt describing the variation (`-` reads it from stdin).
+        #[arg(long, allow_hyphen_values = true)]
+        prompt: String,
+    },
+    /// Fetch a render by its remote id and record it locally.
+    Adopt {
+        /// Local identifier to assign to the adopted clip.
+        #[arg(long)]
+        id: String,
+        /// Sora video id or Veo operation name.
+        #[arg(long)]
+        remote_id: String,
+    },
+    /// Wait for a clip submitted with `--no-wait` and download it.
+    Resume {
+        /// Local identifier of the pending clip.
+        #[arg(long)]
+        id: String,
+    },
+    /// Correct the descriptive fields recorded for a clip.
+    Edit {
+        /// Local identifier of the clip to edit.
+        #[arg(long)]
+        id: String,
+        /// Replacement prompt.
+        #[arg(long)]
+        prompt: Option<String>,
+        /// Replacement model identifier.
+        #[arg(long)]
+        model: Option<String>,
+        /// Replacement backend.
+        #[arg(long, value_enum)]
+        backend: Option<ProviderKind>,
+        /// Replacement notes (an empty string clears them).
+        #[arg(long)]
+        notes: Option<String>,
+        /// Tag to add (repeatable).
+        #[arg(long = "add-tag")]
+        add_tags: Vec<String>,
+        /// Tag to remove (repeatable).
+        #[arg(long = "remove-tag")]
+        remove_tags: Vec<String>,
+        /// Key/value pair to set, replacing any existing value (repeatable).
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_meta)]
+        meta: Vec<(String, String)>,
+        /// Metadata key to remove (repeatable).
+        #[arg(long, value_name = "KEY")]
+        unset_meta: Vec<String>,
+    },
+    /// Delete a clip's record and video.
+    Remove {
+        /// Local identifier of the clip to delete.
+        #[arg(long)]
+        id: String,
     },
     /// List locally stored clips and continuations.
-    List,
+    List {
+        /// Print the combined duration and size of all clips after the listing.
+        #[arg(long, conflicts_with = "json")]
+        summary: bool,
+        /// Print the full metadata of every clip as a JSON array.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check stored clips against their recorded checksums.
+    Verify {
+        /// Also ask the provider which clips it can still serve, to archive or re-download
+        /// the rest before they expire.
+        #[arg(long)]
+        remote: bool,
+    },
+    /// Create every clip listed in a CSV or JSONL file, skipping ones already rendered, and
+    /// write `<name>.results.csv` (or `.jsonl`) beside it.
+    Batch {
+        /// Jobs with `id` and `prompt` and optional `model`, `size`, and `seconds`: CSV with a
+        /// header row, or one JSON object per line in a `.jsonl` file.
+        #[arg(long)]
+        file: PathBuf,
+        /// Clips to render at once.
+        #[arg(long, default_value_t = DEFAULT_BATCH_CONCURRENCY)]
+        concurrency: usize,
+    },
+    /// Save a single frame of a clip as an image, e.g. for a poster or thumbnail.
+    Frame {
+        /// Local identifier of the clip.
+        #[arg(long)]
+        id: String,
+        /// Where in the clip: seconds (`2.5`), seconds from the end (`-1`), or a percentage
+        /// (`50%`).
+        #[arg(long, allow_hyphen_values = true)]
+        at: TimeSpec,
+        /// Image path; relative paths land under the data dir.
+        #[arg(long)]
+        output: PathBuf,
+        /// Image format (defaults to the output's extension, else PNG).
+        #[arg(long, value_enum)]
+        format: Option<ImageFormat>,
+        /// JPEG quality on ffmpeg's scale (2 = best, 31 = smallest).
+        #[arg(long)]
+        quality: Option<u8>,
+    },
+    /// Save each clip's first frame as `<id>.png` in one folder for side-by-side review.
+    Gallery {
+        /// Folder for the frames; relative paths land under the data dir.
+        #[arg(long)]
+        out: PathBuf,
+        /// Clip identifiers to export (defaults to every clip).
+        clips: Vec<String>,
+    },
+    /// Ask the provider where renders stand: the named clips, or by default those submitted
+    /// without waiting that haven't been collected yet.
+    Status {
+        /// Query every clip rendered on the selected provider, plus unfinished jobs the
+        /// provider lists that were submitted elsewhere.
+        #[arg(long, conflicts_with = "clips")]
+        all: bool,
+        /// Clip identifiers to query.
+        clips: Vec<String>,
+    },
+    /// Cancel renders that are still queued or in progress on the provider.
+    Cancel {
+        /// Clip identifier whose render to cancel.
+        #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+        id: Option<String>,
+        /// Cancel every unfinished render on the selected provider.
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show the sizes, durations, and features the selected provider supports.
+    Capabilities {
+        /// Print the table as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a shell completion script to stdout (e.g. `continuator completions zsh`).
+    Completions {
+        /// Shell to generate completions for.
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Package clips into a shareable bundle or unpack one.
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommand,
+    },
     /// Download alternate assets (thumbnail or spritesheet) for a clip.
     Download {
         /// Local identifier of the clip.
@@ -143,19 +585,214 @@ enum Command {
         /// Asset variant to download.
         #[arg(long, value_enum)]
         variant: AssetVariant,
-        /// Output path for the asset.
+        /// Output path for the asset; relative paths resolve under the data dir (`-` streams to
+        /// stdout).
         #[arg(long)]
         output: PathBuf,
+        /// Continue a partially downloaded file instead of starting over.
+        #[arg(long, alias = "resume-download")]
+        resume: bool,
     },
     /// Concatenate local clips into a single output MP4.
     Stitch {
-        /// Local identifier to assign to the stitched clip output file.
+        /// Local identifier to assign to the stitched clip output file (defaults to the
+        /// `--flow` base id).
+        #[arg(long, required_unless_present = "flow")]
+        id: Option<String>,
+        /// One or more clip identifiers to concatenate (positional arguments).
+        #[arg(required_unless_present = "flow", conflicts_with = "flow")]
+        clips: Vec<String>,
+        /// Stitch the beats of this flow (`<BASE_ID>-01`, `<BASE_ID>-02`, ...) in order,
+        /// after its starting clip if it had one.
+        #[arg(long, value_name = "BASE_ID")]
+        flow: Option<String>,
+        /// Template the flow's beats were named with (see `flow --beat-name`).
+        #[arg(long, requires = "flow")]
+        beat_name: Option<String>,
+        #[command(flatten)]
+        encode: EncodeArgs,
+        /// Resample every input's audio to this rate (Hz) before joining.
+        #[arg(long)]
+        audio_sample_rate: Option<u32>,
+        /// Convert every input's audio to this many channels (1 = mono, 2 = stereo).
+        #[arg(long)]
+        audio_channels: Option<u32>,
+        /// Replace an existing clip with this id instead of refusing.
+        #[arg(long)]
+        overwrite: bool,
+        /// Join the clips in chunks of this size, then join the chunks (overrides
+        /// `--stitch-chunk-size`).
+        #[arg(long)]
+        chunk_size: Option<usize>,
+        /// Check the joined file with ffprobe before it replaces the output.
+        #[arg(long)]
+        verify: bool,
+        /// Insert a pause of this many seconds between clips (re-encodes the stitch).
+        #[arg(long, value_name = "SECONDS")]
+        gap: Option<f32>,
+        /// Colour of the pause, by name or as hex (defaults to black).
+        #[arg(long, requires = "gap")]
+        gap_color: Option<String>,
+    },
+    /// Burn text (the clip id by default) onto a clip as a new derived clip.
+    Annotate {
+        /// Local identifier of the clip to annotate.
+        #[arg(long = "from")]
+        source_id: String,
+        /// Local identifier to assign to the annotated clip.
         #[arg(long)]
         id: String,
-        /// One or more clip identifiers to concatenate (positional arguments).
-        #[arg(required = true)]
+        /// Text to burn in (defaults to the source clip id).
+        #[arg(long)]
+        text: Option<String>,
+        /// Where to place the text.
+        #[arg(long, value_enum, default_value_t = AnnotationPosition::BottomLeft)]
+        position: AnnotationPosition,
+        /// Font size in pixels.
+        #[arg(long)]
+        font_size: Option<u32>,
+        /// Draw a translucent box behind the text.
+        #[arg(long = "box")]
+        boxed: bool,
+        /// Font file to use (falls back to a system font).
+        #[arg(long)]
+        font_file: Option<PathBuf>,
+        /// Replace an existing clip with this id instead of refusing.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Burn an SRT or WebVTT subtitle file onto a clip.
+    Subtitles {
+        /// Local identifier of the clip to caption.
+        #[arg(long = "from")]
+        source_id: String,
+        /// Local identifier to assign to the captioned clip.
+        #[arg(long)]
+        id: String,
+        /// Subtitle file (.srt or .vtt).
+        #[arg(long)]
+        file: PathBuf,
+        /// Replace an existing clip with this id instead of refusing.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Make a clip that plays forward then backward, reversing the audio too unless
+    /// `--strip-audio` drops it.
+    Boomerang {
+        /// Local identifier of the clip to loop.
+        #[arg(long = "from")]
+        source_id: String,
+        /// Local identifier to assign to the boomerang clip.
+        #[arg(long)]
+        id: String,
+        /// Drop the audio instead of reversing it.
+        #[arg(long)]
+        strip_audio: bool,
+        /// Replace an existing clip with this id instead of refusing.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Append a clip to a running stitched master (created on first use).
+    Append {
+        /// Local identifier of the stitched master to extend.
+        #[arg(long)]
+        master: String,
+        /// Local identifier of the clip to append.
+        #[arg(long)]
+        clip: String,
+        /// Allow appending to a clip that isn't a stitched master, replacing its video.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Cut an existing video into chained clips named `<id>-01`, `<id>-02`, ...
+    Split {
+        /// Video file to cut up.
+        #[arg(long)]
+        file: PathBuf,
+        /// Base identifier for the clips.
+        #[arg(long)]
+        id: String,
+        /// Approximate length of each clip; cuts land on keyframes.
+        #[arg(long)]
+        chunk_seconds: f32,
+    },
+}
+
+/// Confirmation before a paid render is submitted.
+#[derive(Debug, clap::Args)]
+struct ConfirmArgs {
+    /// Show the resolved request (final prompt, model, size, duration, estimated cost) and ask
+    /// before submitting. Skipped when stdin isn't a terminal.
+    #[arg(short, long)]
+    interactive: bool,
+    /// Submit without asking, even with `--interactive`.
+    #[arg(short, long)]
+    yes: bool,
+}
+
+/// Encoder settings; any video setting makes `stitch` re-encode instead of stream-copying.
+#[derive(Debug, clap::Args)]
+struct EncodeArgs {
+    /// Video codec (e.g., libx264).
+    #[arg(long)]
+    vcodec: Option<String>,
+    /// Constant rate factor (lower is higher quality).
+    #[arg(long)]
+    crf: Option<u8>,
+    /// Encoder preset (e.g., slow).
+    #[arg(long)]
+    preset: Option<String>,
+    /// Target video bitrate (e.g., 8M).
+    #[arg(long)]
+    bitrate: Option<String>,
+    /// Output pixel format (e.g., yuv420p).
+    #[arg(long)]
+    pix_fmt: Option<String>,
+    /// Audio codec (e.g., aac).
+    #[arg(long)]
+    acodec: Option<String>,
+    /// Target audio bitrate (e.g., 192k).
+    #[arg(long)]
+    abitrate: Option<String>,
+}
+
+impl From<EncodeArgs> for EncodeSettings {
+    fn from(args: EncodeArgs) -> Self {
+        EncodeSettings {
+            vcodec: args.vcodec,
+            crf: args.crf,
+            preset: args.preset,
+            bitrate: args.bitrate,
+            pix_fmt: args.pix_fmt,
+            acodec: args.acodec,
+            abitrate: args.abitrate,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum BundleCommand {
+    /// Write clips, their metadata, and a manifest to a tar archive.
+    Export {
+        /// Path of the archive to write (e.g., project.tar).
+        #[arg(long)]
+        output: PathBuf,
+        /// Clip identifiers to include (defaults to every clip).
         clips: Vec<String>,
     },
+    /// Unpack a bundle into the data dir, renaming clips whose ids are taken.
+    Import {
+        /// Path of the archive to import.
+        bundle: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum LogFormat {
+    #[default]
+    Compact,
+    /// One JSON object per line, with the clip's span fields (local_id, backend, remote_id).
+    Json,
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -165,11 +802,63 @@ enum AssetVariant {
     Spritesheet,
 }
 
+impl From<AssetVariant> for VideoVariant {
+    fn from(variant: AssetVariant) -> Self {
+        match variant {
+            AssetVariant::Video => VideoVariant::Video,
+            AssetVariant::Thumbnail => VideoVariant::Thumbnail,
+            AssetVariant::Spritesheet => VideoVariant::Spritesheet,
+        }
+    }
+}
+
+/// Parse `--meta shot=12`.
+fn parse_meta(pair: &str) -> Result<(String, String), String> {
+    let (key, value) = pair
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{pair}'"))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("metadata key must not be empty in '{pair}'"));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// `--meta` pairs as [`continuator::VideoMetadata::extra`] entries, with every value stored as a string.
+fn meta_map(pairs: Vec<(String, String)>) -> serde_json::Map<String, serde_json::Value> {
+    pairs
+        .into_iter()
+        .map(|(key, value)| (key, serde_json::Value::String(value)))
+        .collect()
+}
+
+/// Parse `--sora-variant-param thumbnail=thumb`.
+fn parse_variant_param(value: &str) -> Result<(VideoVariant, String), String> {
+    let (variant, query) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected VARIANT=VALUE, got '{value}'"))?;
+    let variant = <AssetVariant as clap::ValueEnum>::from_str(variant.trim(), true)?;
+    Ok((variant.into(), query.to_string()))
+}
+
+/// How long a timed-out command spends canceling the renders it abandoned.
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    setup_tracing();
-
     let cli = Cli::parse();
+    setup_tracing(cli.log_format)?;
+
+    if let Command::Capabilities { json } = cli.command {
+        // Answerable without credentials, so skip constructing the manager.
+        return print_capabilities(cli.provider.unwrap_or(ProviderKind::Sora), json);
+    }
+    if let Command::Completions { shell } = cli.command {
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+        return Ok(());
+    }
 
     let config = SoraConfig {
         provider: cli.provider,
@@ -183,32 +872,160 @@ async fn main() -> Result<()> {
         gcp_location: cli.gcp_location,
         gcp_access_token: cli.gcp_access_token,
         gcp_storage_uri: cli.gcp_storage_uri,
-        gcp_generate_audio: cli.gcp_generate_audio,
+        gcp_api_version: cli.gcp_api_version,
+        gcp_inline_warn_bytes: cli.gcp_inline_warn_bytes,
+        gcp_generate_audio: switch(cli.audio, cli.no_audio).or(cli.gcp_generate_audio),
         gcp_resolution: cli.gcp_resolution,
-        gcp_enhance_prompt: cli.gcp_enhance_prompt,
+        gcp_aspect_ratio: cli.gcp_aspect_ratio,
+        gcp_enhance_prompt: switch(cli.enhance_prompt, cli.no_enhance_prompt)
+            .or(cli.gcp_enhance_prompt),
+        rate_limit_rpm: cli.rate_limit_rpm,
+        sora_reference_field: cli.sora_reference_field,
+        sora_variant_params: (!cli.sora_variant_param.is_empty())
+            .then(|| cli.sora_variant_param.into_iter().collect()),
+        max_retries: cli.max_retries,
+        retry_jitter_seed: None,
+        pool_max_idle_per_host: cli.pool_max_idle_per_host,
+        pool_idle_timeout_secs: cli.pool_idle_timeout_secs,
+        tcp_keepalive_secs: cli.tcp_keepalive_secs,
+        frame_extraction: cli.frame_extraction,
+        duration_tolerance_secs: cli.duration_tolerance_secs,
+        strict_duration: cli.strict_duration,
+        seed_frame_format: cli.seed_frame_format,
+        seed_frame_quality: cli.seed_frame_quality,
+        thumbnail_size: cli.thumbnail_size,
+        audio_crossfade_ms: cli.audio_crossfade_ms,
+        checksums: cli.checksums,
+        print_ffmpeg: cli.print_ffmpeg,
+        probe_concurrency: cli.probe_concurrency,
+        stitch_chunk_size: cli.stitch_chunk_size,
+        encode: EncodeSettings::default(),
+        temp_dir: cli.temp_dir,
+        audio_sample_rate: None,
+        audio_channels: None,
+        upload_command: cli.upload_command,
+        skip_duration_validation: cli.skip_duration_validation,
+        keep_seed_frame: cli.keep_seed_frame,
+        keep_manifest: cli.keep_manifest,
+        include_model_in_filename: cli.model_in_filename,
+        include_backend_in_filename: cli.provider_in_filename,
+        size_fallback: cli.size_fallback,
+        prompt_prefix: cli.prompt_prefix,
+        prompt_suffix: cli.prompt_suffix,
+        strict_provider: cli.strict_provider,
+        render_retries: cli.render_retries,
+        sleeper: None,
+        #[cfg(feature = "mock")]
+        mock_fixture: None,
+        #[cfg(feature = "mock")]
+        mock_failures: 0,
+        #[cfg(feature = "cassette")]
+        http_cassette: None,
     };
 
+    // List every configuration problem at once rather than one per run.
+    if let Err(problems) = config.validate() {
+        for problem in &problems {
+            eprintln!("error: {problem}");
+        }
+        anyhow::bail!("{} configuration problem(s)", problems.len());
+    }
     let manager = VideoManager::new(config).context("failed to construct video manager")?;
+    let timeout = cli.timeout;
+
+    let mut command = cli.command;
+    if let Command::Boomerang { strip_audio, .. } = &mut command
+        && cli.no_audio
+    {
+        warn!("`boomerang --no-audio` is deprecated; use `--strip-audio`");
+        *strip_audio = true;
+    }
+    let work = run(command, &manager);
+
+    let Some(seconds) = timeout else {
+        return printed_is_success(work.await);
+    };
+    match tokio::time::timeout(Duration::from_secs(seconds), work).await {
+        Ok(result) => printed_is_success(result),
+        Err(_) => {
+            // Canceling is best effort; a provider that stops answering mustn't keep a timed-out
+            // command alive.
+            match tokio::time::timeout(CANCEL_TIMEOUT, manager.cancel_in_flight()).await {
+                Ok(results) => {
+                    for (remote_id, outcome) in results {
+                        match outcome {
+                            Ok(outcome) => info!(remote_id, ?outcome, "abandoned render"),
+                            Err(err) => {
+                                warn!(remote_id, error = %err, "failed to cancel abandoned render")
+                            }
+                        }
+                    }
+                }
+                Err(_) => warn!("gave up canceling abandoned renders"),
+            }
+            anyhow::bail!("timed out after {seconds}s")
+        }
+    }
+}
 
-    match cli.command {
+/// Carry out one command. Everything here can be abandoned by `--timeout`, so anything that
+/// must be cleaned up afterwards has to be reachable from the manager.
+async fn run(command: Command, manager: &VideoManager) -> Result<()> {
+    match command {
         Command::Create {
             id,
             prompt,
             model,
             size,
             seconds,
+            job_poll_interval_ms,
+            output,
+            scratch,
+            also_write,
+            no_wait,
+            notes,
+            meta,
+            no_prompt_wrap,
+            confirm,
+            ..
         } => {
-            let metadata = manager
-                .create_video(CreateVideoRequest {
-                    local_id: id.clone(),
-                    prompt,
-                    model,
-                    size,
-                    seconds,
-                })
-                .await?;
-
-            print_metadata(&metadata);
+            let request = CreateVideoRequest {
+                local_id: id.unwrap_or_default(),
+                prompt: read_prompt(prompt)?,
+                model,
+                size,
+                seconds,
+                poll_interval_ms: job_poll_interval_ms,
+                reference: None,
+                no_wait,
+                notes,
+                extra: meta_map(meta),
+                no_prompt_wrap,
+                storage_uri: None,
+            };
+            if wants_confirmation(&confirm) {
+                confirm_render(&manager.plan_create(&request)?)?;
+            }
+            if scratch {
+                let path = manager.create_scratch(request).await?;
+                if stream_to_stdout(output.as_deref())? {
+                    let mut file = tokio::fs::File::open(&path).await?;
+                    tokio::io::copy(&mut file, &mut tokio::io::stdout()).await?;
+                    eprintln!("{}", path.display());
+                } else {
+                    println!("{}", path.display());
+                }
+            } else if stream_to_stdout(output.as_deref())? {
+                let metadata = manager
+                    .create_video_to_writer(request, &mut tokio::io::stdout())
+                    .await?;
+                write_metadata(&mut io::stderr().lock(), &metadata)?;
+                copy_delivery(&metadata, also_write.as_deref()).await?;
+            } else {
+                let metadata = manager.create_video(request).await?;
+                print_metadata(&metadata);
+                copy_delivery(&metadata, also_write.as_deref()).await?;
+            }
         }
         Command::Continue {
             parent_id,
@@ -217,143 +1034,898 @@ async fn main() -> Result<()> {
             model,
             size,
             seconds,
+            job_poll_interval_ms,
+            seed_from,
+            seed_at,
+            carry_audio,
+            output,
+            also_write,
+            no_wait,
+            notes,
+            meta,
+            no_prompt_wrap,
+            confirm,
+            ..
         } => {
-            let metadata = manager
-                .continue_video(ContinueVideoRequest {
-                    parent_local_id: parent_id,
-                    local_id: id.clone(),
-                    prompt,
-                    model,
-                    size,
-                    seconds,
-                })
-                .await?;
-
-            print_metadata(&metadata);
+            let request = ContinueVideoRequest {
+                parent_local_id: parent_id,
+                local_id: id.clone(),
+                prompt: read_prompt(prompt)?,
+                model,
+                size,
+                seconds,
+                poll_interval_ms: job_poll_interval_ms,
+                seed_from,
+                seed_at,
+                carry_audio,
+                no_wait,
+                notes,
+                extra: meta_map(meta),
+                no_prompt_wrap,
+                storage_uri: None,
+            };
+            if wants_confirmation(&confirm) {
+                confirm_render(&manager.plan_continue(&request).await?)?;
+            }
+            if stream_to_stdout(output.as_deref())? {
+                let metadata = manager
+                    .continue_video_to_writer(request, &mut tokio::io::stdout())
+                    .await?;
+                write_metadata(&mut io::stderr().lock(), &metadata)?;
+                copy_delivery(&metadata, also_write.as_deref()).await?;
+            } else {
+                let metadata = manager.continue_video(request).await?;
+                print_metadata(&metadata);
+                copy_delivery(&metadata, also_write.as_deref()).await?;
+            }
         }
         Command::Flow {
             id,
             start_from,
             model,
+            models,
             size,
+            sizes,
             seconds,
+            seconds_list,
+            job_poll_interval_ms,
+            resume,
+            beat_name,
+            no_stitch,
+            depends_on,
+            concurrency,
+            check,
             prompts,
         } => {
-            if prompts.is_empty() {
-                anyhow::bail!("flow requires at least one prompt");
-            }
-
-            let start_clip = start_from.clone();
-            let mut previous = start_from;
-            let mut generated_ids = Vec::new();
-
-            for (index, prompt) in prompts.into_iter().enumerate() {
-                let clip_local_id = format!("{}-{:02}", id, index + 1);
-                let metadata = if let Some(parent_id) = previous.clone() {
-                    manager
-                        .continue_video(ContinueVideoRequest {
-                            parent_local_id: parent_id,
-                            local_id: clip_local_id.clone(),
-                            prompt,
-                            model: model.clone(),
-                            size: size.clone(),
-                            seconds,
-                        })
-                        .await?
-                } else {
-                    manager
-                        .create_video(CreateVideoRequest {
-                            local_id: clip_local_id.clone(),
-                            prompt,
-                            model: model.clone(),
-                            size: size.clone(),
-                            seconds,
-                        })
-                        .await?
-                };
+            let prompts = read_prompts(prompts)?;
+            let graph = !depends_on.is_empty();
+            if graph && depends_on.len() != prompts.len() {
+                anyhow::bail!(
+                    "--depends-on has {} entries but the flow has {} prompts",
+                    depends_on.len(),
+                    prompts.len()
+                );
+            }
+            let mut depends_on = depends_on
+                .into_iter()
+                .map(|parent| (parent != "-").then_some(parent));
+            let models = per_beat("--models", models, model, prompts.len())?;
+            let sizes = per_beat("--sizes", sizes, size, prompts.len())?;
+            let seconds_list = per_beat("--seconds-list", seconds_list, seconds, prompts.len())?;
+            let beats = prompts
+                .into_iter()
+                .zip(models)
+                .zip(sizes)
+                .zip(seconds_list)
+                .map(|(((prompt, model), size), seconds)| FlowBeat {
+                    prompt,
+                    model,
+                    size,
+                    seconds,
+                    depends_on: depends_on.next().flatten(),
+                })
+                .collect();
+            let request = FlowRequest {
+                id: id.clone(),
+                start_from: start_from.clone(),
+                beats,
+                poll_interval_ms: job_poll_interval_ms,
+                resume,
+                beat_name_template: beat_name,
+                skip_stitch: no_stitch,
+                concurrency,
+            };
+            if check {
+                let issues = manager.validate_flow(&request).await;
+                if issues.is_empty() {
+                    println!("flow {id} is valid ({} beats)", request.beats.len());
+                    return Ok(());
+                }
+                for issue in &issues {
+                    match issue.beat {
+                        Some(beat) => eprintln!("beat {beat}: {}", issue.error),
+                        None => eprintln!("flow: {}", issue.error),
+                    }
+                }
+                anyhow::bail!("flow {id} has {} problem(s)", issues.len());
+            }
+            let report = if graph {
+                manager.run_flow_graph(request, print_metadata).await?
+            } else {
+                manager.run_flow(request, print_metadata).await?
+            };
 
-                print_metadata(&metadata);
-                previous = Some(metadata.local_id.clone());
-                generated_ids.push(metadata.local_id);
+            let mut clips: Vec<String> = start_from.into_iter().collect();
+            clips.extend(report.completed.iter().map(|clip| clip.local_id.clone()));
+            if report.is_complete() {
+                if let Some(path) = &report.stitched {
+                    println!("flow stitched {} -> {}", id, path.display());
+                } else {
+                    let generated: Vec<&str> = report
+                        .completed
+                        .iter()
+                        .map(|clip| clip.local_id.as_str())
+                        .collect();
+                    println!("flow generated {}", generated.join(" "));
+                }
+                print_aggregate(&manager.aggregate(&clips).await?);
+                return Ok(());
             }
 
-            let mut clips_for_stitch = Vec::new();
-            if let Some(start) = start_clip {
-                clips_for_stitch.push(start);
+            let completed: Vec<&str> = report
+                .completed
+                .iter()
+                .map(|clip| clip.local_id.as_str())
+                .collect();
+            if completed.is_empty() {
+                eprintln!("no beats completed");
+            } else {
+                eprintln!("completed beats: {}", completed.join(", "));
+            }
+            if let Some(failure) = &report.failure {
+                eprintln!(
+                    "beat {} ({}) failed: {}",
+                    failure.beat, failure.local_id, failure.error
+                );
             }
-            clips_for_stitch.extend(generated_ids);
+            if let Some(path) = &report.stitched {
+                eprintln!("partial stitch -> {}", path.display());
+            }
+            if let Some(error) = &report.stitch_error {
+                eprintln!("stitch failed: {error}");
+            }
+            if !completed.is_empty() {
+                eprintln!("rerun the same command with --resume to reuse the completed beats");
+            }
+            anyhow::bail!("flow {id} did not finish");
+        }
+        Command::ContactSheet {
+            id,
+            seconds,
+            output,
+        } => {
+            let sheet = manager
+                .tail_contact_sheet(&id, seconds, &output)
+                .await
+                .context("failed to render contact sheet")?;
+            println!("contact sheet -> {}", sheet.path.display());
+            for row in sheet.timestamps.chunks(sheet.columns as usize) {
+                let row: Vec<String> = row.iter().map(|time| format!("{time:.3}")).collect();
+                println!("  {}", row.join("  "));
+            }
+            println!("continue from a tile with `continue --from {id} --seed-at <seconds> ...`");
+        }
+        Command::Remix {
+            source_id,
+            id,
+            prompt,
+        } => {
+            let metadata = manager
+                .remix_video(&source_id, &id, &read_prompt(prompt)?)
+                .await
+                .context("failed to remix clip")?;
 
-            let stitched_path = manager
-                .stitch_videos(&id, &clips_for_stitch)
+            print_metadata(&metadata);
+        }
+        Command::Adopt { id, remote_id } => {
+            let metadata = manager
+                .adopt_remote(&id, &remote_id)
                 .await
-                .context("failed to stitch flow clips")?;
+                .context("failed to adopt remote render")?;
 
-            println!("flow stitched {} -> {}", id, stitched_path.display());
+            print_metadata(&metadata);
         }
-        Command::List => {
+        Command::Resume { id } => {
+            let metadata = manager
+                .finish_pending(&id)
+                .await
+                .context("failed to finish pending render")?;
+            print_metadata(&metadata);
+        }
+        Command::Remove { id } => {
+            let metadata = manager
+                .remove_clip(&id)
+                .await
+                .context("failed to remove clip")?;
+            println!("removed {} ({})", id, metadata.file_path.display());
+        }
+        Command::List { summary, json } => {
             let videos = manager.list_videos().await?;
-            if videos.is_empty() {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&videos)?);
+            } else if videos.is_empty() {
                 println!("(no clips recorded)");
             } else {
-                for video in videos {
-                    print_metadata(&video);
+                for video in &videos {
+                    print_metadata(video);
+                }
+            }
+            if !json {
+                for pending in manager.list_pending().await? {
+                    println!(
+                        "pending: {} ({}); collect it with `resume --id {}`",
+                        pending.local_id, pending.remote_id, pending.local_id
+                    );
                 }
             }
+            if summary {
+                let ids: Vec<String> = videos.into_iter().map(|video| video.local_id).collect();
+                print_aggregate(&manager.aggregate(&ids).await?);
+            }
+        }
+        Command::Capabilities { .. } | Command::Completions { .. } => {
+            unreachable!("handled before the manager is built")
+        }
+        Command::Edit {
+            id,
+            prompt,
+            model,
+            backend,
+            notes,
+            add_tags,
+            remove_tags,
+            meta,
+            unset_meta,
+        } => {
+            let patch = MetadataPatch {
+                prompt,
+                model,
+                backend,
+                notes,
+                add_tags,
+                remove_tags,
+                set_extra: meta_map(meta),
+                remove_extra: unset_meta,
+            };
+            if patch.is_empty() {
+                anyhow::bail!(
+                    "nothing to change; pass --prompt, --model, --backend, --notes, or a tag or meta flag"
+                );
+            }
+            let metadata = manager.update_metadata(&id, patch).await?;
+            print_metadata(&metadata);
+        }
+        Command::Batch { file, concurrency } => {
+            let report = manager.run_batch(&file, concurrency).await?;
+            let width = report
+                .results
+                .iter()
+                .map(|result| result.id.len())
+                .max()
+                .unwrap_or(0);
+            for result in &report.results {
+                let status = result.status.as_str();
+                match &result.error {
+                    Some(error) => println!("{:<width$}  {status}: {error}", result.id),
+                    None => println!("{:<width$}  {status}", result.id),
+                }
+            }
+            println!("results -> {}", report.results_path.display());
+            let failed = report.failed();
+            if failed > 0 {
+                anyhow::bail!(
+                    "{failed} of {} job(s) failed; rerun the same command to retry them",
+                    report.results.len()
+                );
+            }
         }
+        Command::Frame {
+            id,
+            at,
+            output,
+            format,
+            quality,
+        } => {
+            let options = (format.is_some() || quality.is_some()).then(|| FrameOptions {
+                format: format.unwrap_or_else(|| ImageFormat::from_path(&output)),
+                quality,
+            });
+            let path = manager.extract_frame(&id, at, &output, options).await?;
+            println!("{}", path.display());
+        }
+        Command::Gallery { out, clips } => {
+            let ids = (!clips.is_empty()).then_some(clips.as_slice());
+            let frames = manager.export_first_frames(ids, &out).await?;
+            let mut failed = 0;
+            for (local_id, result) in &frames {
+                match result {
+                    Ok(path) => println!("{local_id} -> {}", path.display()),
+                    Err(err) => {
+                        failed += 1;
+                        eprintln!("{local_id}: {err}");
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("{failed} frame(s) could not be exported");
+            }
+        }
+        Command::Status { all, clips } => {
+            let pending;
+            let ids = if all {
+                None
+            } else if clips.is_empty() {
+                pending = manager
+                    .list_pending()
+                    .await?
+                    .into_iter()
+                    .map(|metadata| metadata.local_id)
+                    .collect::<Vec<_>>();
+                if pending.is_empty() {
+                    println!("no pending renders");
+                }
+                Some(pending.as_slice())
+            } else {
+                Some(clips.as_slice())
+            };
+            let statuses = manager.remote_status_many(ids).await?;
+            let width = statuses.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+            let mut failed = 0;
+            for (local_id, result) in &statuses {
+                match result {
+                    Ok(status) => {
+                        let progress = status
+                            .progress
+                            .map(|progress| format!(" {progress:.0}%"))
+                            .unwrap_or_default();
+                        println!("{local_id:<width$}  {}{progress}", status.status.as_str());
+                    }
+                    Err(err) => {
+                        failed += 1;
+                        println!("{local_id:<width$}  error: {err}");
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("{failed} clip(s) could not be queried");
+            }
+        }
+        Command::Cancel { id, .. } => {
+            let results = match id {
+                Some(id) => vec![(id.clone(), manager.cancel(&id).await)],
+                None => manager.cancel_all().await?,
+            };
+            if results.is_empty() {
+                println!("no unfinished renders");
+            }
+            let width = results.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+            let mut failed = 0;
+            for (local_id, result) in &results {
+                match result {
+                    Ok(CancelOutcome::Canceled) => println!("{local_id:<width$}  canceled"),
+                    Ok(CancelOutcome::AlreadyFinished(status)) => {
+                        println!("{local_id:<width$}  already {}", status.as_str())
+                    }
+                    Ok(CancelOutcome::Unsupported) => {
+                        println!("{local_id:<width$}  skipped (provider cannot cancel)")
+                    }
+                    Err(err) => {
+                        failed += 1;
+                        println!("{local_id:<width$}  error: {err}");
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("{failed} render(s) could not be canceled");
+            }
+        }
+        Command::Verify { remote } => {
+            let report = manager.verify_checksums().await?;
+            for error in &report.errors {
+                println!("{}: unable to verify ({})", error.local_id, error.error);
+            }
+            for mismatch in &report.mismatches {
+                match &mismatch.actual {
+                    Some(actual) => println!(
+                        "{}: checksum mismatch (expected {}, found {})",
+                        mismatch.local_id, mismatch.expected, actual
+                    ),
+                    None => println!("{}: file missing", mismatch.local_id),
+                }
+            }
+            let mut failed = report.mismatches.len() + report.errors.len();
+            let remote = if remote {
+                match manager.remote_availability().await {
+                    Err(continuator::SoraError::UnsupportedOperation(reason)) => {
+                        println!("skipped remote check: {reason}");
+                        Vec::new()
+                    }
+                    results => results?,
+                }
+            } else {
+                Vec::new()
+            };
+            for (local_id, result) in remote {
+                match result {
+                    Ok(true) => println!("{local_id}: remote copy available"),
+                    Ok(false) => println!("{local_id}: remote copy expired"),
+                    Err(err) => {
+                        failed += 1;
+                        println!("{local_id}: unable to check remote copy ({err})");
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("{failed} clip(s) failed verification");
+            }
+            println!("verified {} checksummed clip(s)", report.checked);
+        }
+        Command::Bundle { command } => match command {
+            BundleCommand::Export { output, clips } => {
+                let ids = (!clips.is_empty()).then_some(clips.as_slice());
+                let manifest = manager
+                    .export_bundle(ids, &output)
+                    .await
+                    .context("failed to export bundle")?;
+                println!(
+                    "exported {} clip(s) to {}",
+                    manifest.clips.len(),
+                    output.display()
+                );
+            }
+            BundleCommand::Import { bundle } => {
+                let imported = manager
+                    .import_bundle(&bundle)
+                    .await
+                    .context("failed to import bundle")?;
+                for metadata in &imported {
+                    print_metadata(metadata);
+                }
+                println!("imported {} clip(s)", imported.len());
+            }
+        },
         Command::Download {
             id,
             variant,
             output,
+            resume,
         } => {
-            let variant = match variant {
-                AssetVariant::Video => VideoVariant::Video,
-                AssetVariant::Thumbnail => VideoVariant::Thumbnail,
-                AssetVariant::Spritesheet => VideoVariant::Spritesheet,
-            };
+            let variant = VideoVariant::from(variant);
 
-            manager
-                .download_asset(&id, variant, &output)
-                .await
-                .context("failed to download asset")?;
+            if is_stdout(&output) {
+                manager
+                    .download_asset_to_writer(&id, variant, &mut tokio::io::stdout())
+                    .await
+                    .context("failed to download asset")?;
+            } else {
+                let path = manager
+                    .download_asset_with(&id, variant, &output, &DownloadOptions { resume })
+                    .await
+                    .context("failed to download asset")?;
 
-            info!(path = %output.display(), "downloaded asset");
+                info!(path = %path.display(), "downloaded asset");
+            }
         }
-        Command::Stitch { id, clips } => {
+        Command::Stitch {
+            id,
+            clips,
+            flow,
+            beat_name,
+            encode,
+            audio_sample_rate,
+            audio_channels,
+            overwrite,
+            chunk_size,
+            verify,
+            gap,
+            gap_color,
+        } => {
+            let options = StitchOptions {
+                encode: encode.into(),
+                audio_sample_rate,
+                audio_channels,
+                overwrite,
+                chunk_size,
+                verify,
+                gap: gap.map(|seconds| GapSpec {
+                    seconds,
+                    color: gap_color.unwrap_or_else(|| GapSpec::default().color),
+                }),
+            };
+            let clips = match &flow {
+                Some(base) => manager
+                    .flow_clips(base, beat_name.as_deref())
+                    .await
+                    .context("failed to find flow beats")?,
+                None => clips,
+            };
+            let id = id.or(flow).expect("clap requires --id or --flow");
             let path = manager
-                .stitch_videos(&id, &clips)
+                .stitch_videos_with(&id, &clips, &options)
                 .await
                 .context("failed to stitch clips")?;
 
             println!("stitched {} -> {}", id, path.display());
         }
-    }
+        Command::Annotate {
+            source_id,
+            id,
+            text,
+            position,
+            font_size,
+            boxed,
+            font_file,
+            overwrite,
+        } => {
+            let metadata = manager
+                .annotate(
+                    &source_id,
+                    &id,
+                    AnnotationSpec {
+                        text,
+                        position,
+                        font_size,
+                        boxed,
+                        font_file,
+                        overwrite,
+                    },
+                )
+                .await
+                .context("failed to annotate clip")?;
+
+            print_metadata(&metadata);
+        }
+        Command::Subtitles {
+            source_id,
+            id,
+            file,
+            overwrite,
+        } => {
+            let metadata = manager
+                .burn_subtitles(&source_id, &id, &file, overwrite)
+                .await
+                .context("failed to burn subtitles")?;
 
+            print_metadata(&metadata);
+        }
+        Command::Boomerang {
+            source_id,
+            id,
+            strip_audio,
+            overwrite,
+        } => {
+            let metadata = manager
+                .boomerang(
+                    &source_id,
+                    &id,
+                    BoomerangOptions {
+                        strip_audio,
+                        overwrite,
+                    },
+                )
+                .await
+                .context("failed to build boomerang clip")?;
+
+            print_metadata(&metadata);
+        }
+        Command::Append {
+            master,
+            clip,
+            overwrite,
+        } => {
+            let metadata = manager
+                .append_to_stitch(&master, &clip, overwrite)
+                .await
+                .context("failed to append clip")?;
+
+            print_metadata(&metadata);
+        }
+        Command::Split {
+            file,
+            id,
+            chunk_seconds,
+        } => {
+            let clips = manager
+                .split(&file, &id, chunk_seconds)
+                .await
+                .context("failed to split video")?;
+
+            for metadata in &clips {
+                print_metadata(metadata);
+            }
+        }
+    }
     Ok(())
 }
 
-fn setup_tracing() {
+/// Under `--print-ffmpeg`, stopping once the commands are printed is the expected outcome.
+fn printed_is_success(result: Result<()>) -> Result<()> {
+    match result {
+        Err(err)
+            if err.chain().any(|cause| {
+                matches!(
+                    cause.downcast_ref(),
+                    Some(continuator::SoraError::CommandsPrinted)
+                )
+            }) =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+fn setup_tracing(format: Option<LogFormat>) -> Result<()> {
+    let format = match (format, std::env::var("CONTINUATOR_LOG_FORMAT")) {
+        (Some(format), _) => format,
+        (None, Ok(value)) => <LogFormat as clap::ValueEnum>::from_str(&value, true)
+            .map_err(|_| anyhow::anyhow!("unknown CONTINUATOR_LOG_FORMAT '{value}'"))?,
+        (None, Err(_)) => LogFormat::default(),
+    };
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    let _ = tracing_subscriber::fmt()
+    // Logs go to stderr so stdout stays clean when video bytes are piped out.
+    let builder = tracing_subscriber::fmt()
         .with_env_filter(filter)
-        .with_target(false)
-        .compact()
-        .try_init();
+        .with_writer(io::stderr)
+        .with_target(false);
+    let _ = match format {
+        LogFormat::Compact => builder.compact().try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).try_init(),
+    };
+    Ok(())
+}
+
+/// The setting chosen by a `--thing`/`--no-thing` pair (clap rejects both at once), or `None`
+/// when neither was given.
+fn switch(on: bool, off: bool) -> Option<bool> {
+    (on || off).then_some(on)
+}
+
+/// Expand a per-beat list to one entry per prompt, repeating the last entry when it is short.
+/// An empty list falls back to the single flag shared by every beat.
+fn per_beat<T: Clone>(
+    flag: &str,
+    values: Vec<T>,
+    single: Option<T>,
+    beats: usize,
+) -> Result<Vec<Option<T>>> {
+    if values.len() > beats {
+        anyhow::bail!(
+            "{flag} has {} entries but the flow only has {beats} prompts",
+            values.len()
+        );
+    }
+    let Some(last) = values.last().cloned() else {
+        return Ok(vec![single; beats]);
+    };
+    let mut expanded: Vec<Option<T>> = values.into_iter().map(Some).collect();
+    expanded.resize(beats, Some(last));
+    Ok(expanded)
+}
+
+fn print_capabilities(provider: ProviderKind, json: bool) -> Result<()> {
+    let capabilities = provider.capabilities();
+    if json {
+        println!("{}", serde_json::to_string_pretty(capabilities)?);
+        return Ok(());
+    }
+    let seconds: Vec<String> = capabilities.seconds.iter().map(u32::to_string).collect();
+    let variants: Vec<String> = capabilities
+        .variants
+        .iter()
+        .map(|variant| format!("{variant:?}").to_lowercase())
+        .collect();
+    println!("provider: {provider:?}");
+    println!("sizes: {}", capabilities.sizes.join(", "));
+    println!("seconds: {}", seconds.join(", "));
+    if !capabilities.resolutions.is_empty() {
+        println!("resolutions: {}", capabilities.resolutions.join(", "));
+    }
+    println!("downloads: {}", variants.join(", "));
+    println!("remix: {}", if capabilities.remix { "yes" } else { "no" });
+    Ok(())
+}
+
+fn print_aggregate(aggregate: &continuator::Aggregate) {
+    println!(
+        "total: {} clips, {:.1}s, {:.1} MiB",
+        aggregate.clip_count,
+        aggregate.total_seconds,
+        aggregate.total_bytes as f64 / (1024.0 * 1024.0)
+    );
+}
+
+/// Whether an output path is the `-` stdout sentinel.
+fn is_stdout(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Whether to ask before submitting: only with `--interactive`, without `--yes`, and when
+/// someone is at the terminal to answer.
+fn wants_confirmation(args: &ConfirmArgs) -> bool {
+    args.interactive && !args.yes && io::stdin().is_terminal()
+}
+
+/// Print the resolved render to stderr and fail unless the answer is yes.
+fn confirm_render(plan: &RenderPlan) -> Result<()> {
+    let mut stderr = io::stderr().lock();
+    writeln!(stderr, "provider: {}", plan.provider.as_str())?;
+    if let Some(parent) = &plan.parent {
+        writeln!(stderr, "continues: {parent}")?;
+    }
+    writeln!(stderr, "model: {}", plan.model)?;
+    writeln!(stderr, "size: {}", plan.size)?;
+    writeln!(stderr, "seconds: {}", plan.seconds)?;
+    match plan.estimated_cost_usd {
+        Some(cost) => writeln!(stderr, "estimated cost: ${cost:.2}")?,
+        None => writeln!(stderr, "estimated cost: unknown for this model")?,
+    }
+    writeln!(stderr, "prompt: {}", plan.prompt)?;
+    write!(stderr, "submit? [y/N] ")?;
+    stderr.flush()?;
+    drop(stderr);
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+        Ok(())
+    } else {
+        anyhow::bail!("not submitted");
+    }
+}
+
+/// The prompt as given, or all of stdin (trimmed) when it is `-`.
+fn read_prompt(prompt: String) -> Result<String> {
+    if prompt != "-" {
+        return Ok(prompt);
+    }
+    let text = io::read_to_string(io::stdin()).context("failed to read the prompt from stdin")?;
+    Ok(text.trim().to_string())
+}
+
+/// The prompts as given, or one per non-blank line of stdin when the only prompt is `-`.
+fn read_prompts(prompts: Vec<String>) -> Result<Vec<String>> {
+    if !prompts.iter().any(|prompt| prompt == "-") {
+        return Ok(prompts);
+    }
+    if prompts.len() > 1 {
+        anyhow::bail!("`-` reads every prompt from stdin and can't be mixed with other prompts");
+    }
+    let text = io::read_to_string(io::stdin()).context("failed to read prompts from stdin")?;
+    let prompts: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    if prompts.is_empty() {
+        anyhow::bail!("no prompts on stdin");
+    }
+    Ok(prompts)
+}
+
+/// Validate a create/continue `--output`, which currently only supports streaming to stdout.
+/// Returns whether the finished clip should be streamed there.
+fn stream_to_stdout(output: Option<&Path>) -> Result<bool> {
+    match output {
+        None => Ok(false),
+        Some(path) if is_stdout(path) => Ok(true),
+        Some(path) => anyhow::bail!(
+            "--output only supports `-` (stdout); got {}",
+            path.display()
+        ),
+    }
+}
+
+/// Copy a finished clip to the `--also-write` path, if one was given.
+async fn copy_delivery(metadata: &continuator::VideoMetadata, path: Option<&Path>) -> Result<()> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    tokio::fs::copy(&metadata.file_path, path)
+        .await
+        .with_context(|| format!("failed to copy clip to {}", path.display()))?;
+    info!(path = %path.display(), "wrote delivery copy");
+    Ok(())
 }
 
 fn print_metadata(metadata: &continuator::VideoMetadata) {
-    println!("id: {}", metadata.local_id);
-    println!("remote_id: {}", metadata.remote_id);
-    println!("backend: {:?}", metadata.backend);
-    println!("model: {}", metadata.model);
-    println!("seconds: {}", metadata.seconds);
-    println!("size: {}", metadata.size);
+    let _ = write_metadata(&mut std::io::stdout().lock(), metadata);
+}
+
+fn write_metadata(out: &mut dyn Write, metadata: &continuator::VideoMetadata) -> io::Result<()> {
+    writeln!(out, "id: {}", metadata.local_id)?;
+    writeln!(out, "remote_id: {}", metadata.remote_id)?;
+    writeln!(out, "backend: {:?}", metadata.backend)?;
+    writeln!(out, "model: {}", metadata.model)?;
+    writeln!(
+        out,
+        "seconds: {} ({})",
+        metadata.seconds,
+        metadata.seconds_source.as_str()
+    )?;
+    writeln!(out, "size: {}", metadata.size)?;
+    if metadata.pending {
+        writeln!(out, "pending: true")?;
+    }
+    if let Some(notes) = &metadata.notes {
+        writeln!(out, "notes: {notes}")?;
+    }
     if let Some(parent) = &metadata.parent {
-        println!("parent: {}", parent);
+        writeln!(out, "parent: {}", parent)?;
+    }
+    if let Some(seed_source) = &metadata.seed_source
+        && metadata.parent.as_ref() != Some(seed_source)
+    {
+        writeln!(out, "seed_source: {}", seed_source)?;
+    }
+    if let Some(remix_of) = &metadata.remix_of {
+        writeln!(out, "remix_of: {}", remix_of)?;
+    }
+    if !metadata.sources.is_empty() {
+        writeln!(out, "sources: {}", metadata.sources.join(", "))?;
+    }
+    if let Some(seed_frame) = &metadata.seed_frame {
+        writeln!(out, "seed_frame: {}", seed_frame.display())?;
+    }
+    if let Some(carried_audio) = &metadata.carried_audio {
+        writeln!(out, "carried_audio: {}", carried_audio.display())?;
+    }
+    for sample in &metadata.samples {
+        writeln!(out, "sample: {}", sample.display())?;
+    }
+    for uri in &metadata.storage_uris {
+        writeln!(out, "sample: {uri}")?;
+    }
+    if let Some(bytes) = metadata.inline_bytes {
+        writeln!(out, "inline_bytes: {bytes}")?;
+    }
+    if !metadata.tags.is_empty() {
+        writeln!(out, "tags: {}", metadata.tags.join(", "))?;
+    }
+    for (key, value) in &metadata.extra {
+        match value {
+            serde_json::Value::String(value) => writeln!(out, "meta: {key}={value}")?,
+            value => writeln!(out, "meta: {key}={value}")?,
+        }
     }
     if let Some(created_at) = metadata.created_at {
-        println!("created_at: {}", created_at);
+        writeln!(
+            out,
+            "created_at: {} ({})",
+            created_at,
+            metadata.created_at_source.as_str()
+        )?;
+    }
+    if let Some(sha256) = &metadata.sha256 {
+        writeln!(out, "sha256: {}", sha256)?;
     }
-    println!("file: {}", metadata.file_path.display());
-    println!("prompt: {}", metadata.prompt);
-    println!();
+    if let Some(delta) = metadata.duration_delta {
+        writeln!(out, "duration_delta: {:+.2}s", delta)?;
+    }
+    writeln!(out, "file: {}", metadata.file_path.display())?;
+    writeln!(out, "prompt: {}", metadata.prompt)?;
+    if let Some(original_prompt) = &metadata.original_prompt {
+        writeln!(out, "original_prompt: {original_prompt}")?;
+    }
+    writeln!(out)?;
+    Ok(())
 }